@@ -0,0 +1,156 @@
+//! One-call headless battles for balance testing.
+//!
+//! [`resolve_battle`] places two armies with the [`ScenarioBuilder`], marches
+//! both to the point between them, and steps the simulation until one side
+//! is wiped out or the tick cap calls a draw. No reinforcements or pylons join, so
+//! the result depends only on the armies, the seed, and [`BalanceSettings`]:
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use core_game::battle::{resolve_battle, UnitSpec};
+//! use core_game::gameplay::UnitKind;
+//!
+//! let army = |x: f32, count: usize| -> Vec<UnitSpec> {
+//!     (0..count)
+//!         .map(|idx| UnitSpec::new(UnitKind::Laser, Vec2::new(x, idx as f32 * 30.0)))
+//!         .collect()
+//! };
+//! let result = resolve_battle(&army(-300.0, 6), &army(300.0, 3), 42, 3_000);
+//! println!("{:?} after {} ticks", result.winner, result.ticks);
+//! ```
+
+use std::time::Duration;
+
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::*;
+
+use crate::gameplay::{
+    issue_order, BalanceSettings, BoardSettings, GameOutcome, MatchStats, Order, PlayerId,
+    PlayerStats, RenderEffects, RenderInterpolation, SimulationParams, SimulationTick, Unit,
+    UnitKind,
+};
+use crate::scenario::ScenarioBuilder;
+use crate::{run_simulation_tick, CoreGamePlugin};
+
+/// Army A fights as player 0, army B as player 1.
+const ARMIES: [PlayerId; 2] = [PlayerId(0), PlayerId(1)];
+
+/// One unit of an army, placed in world coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnitSpec {
+    pub kind: UnitKind,
+    pub position: Vec2,
+}
+
+impl UnitSpec {
+    pub fn new(kind: UnitKind, position: Vec2) -> Self {
+        Self { kind, position }
+    }
+}
+
+/// How a [`resolve_battle`] call ended; indices 0 and 1 are armies A and B.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BattleResult {
+    /// [`GameOutcome::Draw`] when the tick cap ran out first, so a stalemate
+    /// still ends.
+    pub outcome: GameOutcome,
+    /// Player 0 for army A, player 1 for army B; `None` for a draw or cap.
+    pub winner: Option<PlayerId>,
+    pub ticks: u64,
+    pub survivors: [usize; 2],
+    pub stats: [PlayerStats; 2],
+}
+
+/// Fights `army_a` against `army_b` with default [`BalanceSettings`]. The
+/// same inputs always produce the same result.
+pub fn resolve_battle(
+    army_a: &[UnitSpec],
+    army_b: &[UnitSpec],
+    seed: u64,
+    max_ticks: usize,
+) -> BattleResult {
+    resolve_battle_with(army_a, army_b, seed, max_ticks, BalanceSettings::default())
+}
+
+/// [`resolve_battle`] with explicit balance numbers, for sweeping a tuning
+/// value across runs.
+pub fn resolve_battle_with(
+    army_a: &[UnitSpec],
+    army_b: &[UnitSpec],
+    seed: u64,
+    max_ticks: usize,
+    balance: BalanceSettings,
+) -> BattleResult {
+    let params = SimulationParams::from_seed(seed);
+    let step = Duration::from_secs_f64(params.fixed_delta);
+
+    let mut app = App::new();
+    app.insert_resource(params)
+        .insert_resource(BoardSettings {
+            reinforcements: false,
+            max_match_ticks: max_ticks as u64,
+            ..Default::default()
+        })
+        .insert_resource(balance)
+        .insert_resource(DiagnosticsStore::default())
+        .insert_resource(RenderEffects(false))
+        .insert_resource(RenderInterpolation(false));
+    let mut scenario = ScenarioBuilder::new().player_count(ARMIES.len());
+    for (player, army) in ARMIES.into_iter().zip([army_a, army_b]) {
+        for spec in army {
+            scenario = scenario.unit(player, spec.kind, spec.position);
+        }
+    }
+    scenario.build(&mut app);
+    app.add_plugins((MinimalPlugins, CoreGamePlugin));
+    app.update();
+
+    march_armies_together(app.world_mut(), army_a, army_b);
+
+    for _ in 0..max_ticks {
+        app.world_mut().resource_mut::<Time>().advance_by(step);
+        run_simulation_tick(&mut app);
+        if *app.world().resource::<GameOutcome>() != GameOutcome::InProgress {
+            break;
+        }
+    }
+
+    let world = app.world_mut();
+    let mut survivors = [0; 2];
+    for unit in world.query::<&Unit>().iter(world) {
+        if let Some(count) = survivors.get_mut(unit.player.0) {
+            *count += 1;
+        }
+    }
+    let outcome = *world.resource::<GameOutcome>();
+    let stats = world.resource::<MatchStats>();
+    BattleResult {
+        outcome,
+        winner: match outcome {
+            GameOutcome::Victory(player) => Some(player),
+            _ => None,
+        },
+        ticks: world.resource::<SimulationTick>().0,
+        survivors,
+        stats: ARMIES.map(|player| stats.player(player)),
+    }
+}
+
+/// Orders both armies to the midpoint of their centroids so they engage
+/// wherever they were placed instead of marching past each other.
+fn march_armies_together(world: &mut World, army_a: &[UnitSpec], army_b: &[UnitSpec]) {
+    let centroid = |army: &[UnitSpec]| {
+        army.iter().map(|spec| spec.position).sum::<Vec2>() / army.len().max(1) as f32
+    };
+    let target = (centroid(army_a) + centroid(army_b)) / 2.0;
+    for player in ARMIES {
+        let mut units: Vec<Entity> = world
+            .query::<(Entity, &Unit)>()
+            .iter(world)
+            .filter(|(_, unit)| unit.player == player)
+            .map(|(entity, _)| entity)
+            .collect();
+        units.sort();
+        issue_order(world, &units, Order::Move(target));
+    }
+}
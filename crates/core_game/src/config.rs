@@ -0,0 +1,265 @@
+//! Single-file game configuration.
+//!
+//! Point `GAME_CONFIG` at a TOML file to populate [`SimulationParams`],
+//! [`BoardSettings`], and [`ControlSettings`] together. Environment variables
+//! still override individual fields, so a shared repro file can be tweaked
+//! from the command line:
+//!
+//! ```toml
+//! [simulation]
+//! seed = 7
+//! fixed_delta = 0.0333
+//!
+//! [board]
+//! board_size = 1200.0
+//! player_count = 3
+//! spawn_interval = 0.8
+//! pylon_count = 0
+//! max_units_per_player = 50
+//! max_match_ticks = 18000
+//!
+//! [control]
+//! local_player = 1
+//!
+//! [teams]
+//! alliances = [[0, 2], [1, 3]]
+//!
+//! [render]
+//! max_beam_effects = 256
+//!
+//! [theme]
+//! background = [0.03, 0.04, 0.09]
+//! board_color = [0.09, 0.12, 0.2]
+//! unit_size = [24.0, 32.0]
+//! pylon_size = [26.0, 38.0]
+//! beam_thickness = 4.0
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::gameplay::{
+    BoardSettings, ControlSettings, PlayerId, RenderSettings, SimulationParams, Teams,
+    ThemeSettings,
+};
+
+#[derive(Debug, Error)]
+pub enum GameConfigError {
+    #[error("failed to read game config {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse game config {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Every section and field is optional; omitted values keep their defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    pub simulation: SimulationConfig,
+    pub board: BoardConfig,
+    pub control: ControlConfig,
+    pub teams: TeamsConfig,
+    pub render: RenderConfig,
+    pub theme: ThemeConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub seed: Option<u64>,
+    pub fixed_delta: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BoardConfig {
+    pub board_size: Option<f32>,
+    pub player_count: Option<usize>,
+    pub spawn_interval: Option<f32>,
+    pub pylon_count: Option<usize>,
+    pub max_units_per_player: Option<usize>,
+    pub max_match_ticks: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ControlConfig {
+    pub local_player: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TeamsConfig {
+    /// Groups of allied player indices; unlisted players fight alone.
+    pub alliances: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RenderConfig {
+    pub max_beam_effects: Option<usize>,
+}
+
+/// Colors are sRGB components in `0.0..=1.0`; sizes are `[width, height]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub background: Option<[f32; 3]>,
+    pub board_color: Option<[f32; 3]>,
+    pub unit_size: Option<[f32; 2]>,
+    pub pylon_size: Option<[f32; 2]>,
+    pub beam_thickness: Option<f32>,
+}
+
+impl GameConfig {
+    pub fn from_path(path: &Path) -> Result<Self, GameConfigError> {
+        let data = fs::read_to_string(path).map_err(|source| GameConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&data).map_err(|source| GameConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Loads the file named by `GAME_CONFIG`. A missing variable yields `None`;
+    /// an unreadable file is logged and also yields `None`.
+    pub fn from_env() -> Option<Self> {
+        let path = PathBuf::from(std::env::var_os("GAME_CONFIG")?);
+        match Self::from_path(&path) {
+            Ok(config) => {
+                info!(target: "gameplay", "Loaded game config {}", path.display());
+                Some(config)
+            }
+            Err(err) => {
+                error!(target: "gameplay", "{err}; falling back to defaults");
+                None
+            }
+        }
+    }
+
+    pub fn simulation_params(&self) -> SimulationParams {
+        let mut params = SimulationParams::default();
+        if let Some(seed) = self.simulation.seed {
+            params.seed = seed;
+        }
+        if let Some(fixed_delta) = self.simulation.fixed_delta {
+            params.fixed_delta = fixed_delta;
+        }
+        params
+    }
+
+    pub fn board_settings(&self) -> BoardSettings {
+        let mut board = BoardSettings::env_baseline();
+        if let Some(board_size) = self.board.board_size {
+            board.board_size = board_size;
+        }
+        if let Some(player_count) = self.board.player_count {
+            board.player_count = player_count;
+        }
+        if let Some(spawn_interval) = self.board.spawn_interval {
+            board.spawn_interval = spawn_interval;
+        }
+        if let Some(pylon_count) = self.board.pylon_count {
+            board.pylon_count = pylon_count;
+        }
+        if let Some(max_units) = self.board.max_units_per_player {
+            board.max_units_per_player = max_units;
+        }
+        if let Some(max_ticks) = self.board.max_match_ticks {
+            board.max_match_ticks = max_ticks;
+        }
+        board
+    }
+
+    pub fn control_settings(&self) -> ControlSettings {
+        let mut control = ControlSettings::default();
+        if let Some(local_player) = self.control.local_player {
+            control.local_player = PlayerId(local_player);
+        }
+        control
+    }
+
+    pub fn teams(&self) -> Teams {
+        Teams::from_alliances(&self.teams.alliances)
+    }
+
+    pub fn render_settings(&self) -> RenderSettings {
+        let mut render = RenderSettings::default();
+        if let Some(max_beam_effects) = self.render.max_beam_effects {
+            render.max_beam_effects = max_beam_effects;
+        }
+        render
+    }
+
+    pub fn theme_settings(&self) -> ThemeSettings {
+        let mut theme = ThemeSettings::default();
+        if let Some([r, g, b]) = self.theme.background {
+            theme.background = Color::srgb(r, g, b);
+        }
+        if let Some([r, g, b]) = self.theme.board_color {
+            theme.board_color = Color::srgb(r, g, b);
+        }
+        if let Some(size) = self.theme.unit_size {
+            theme.unit_size = Vec2::from(size);
+        }
+        if let Some(size) = self.theme.pylon_size {
+            theme.pylon_size = Vec2::from(size);
+        }
+        if let Some(beam_thickness) = self.theme.beam_thickness {
+            theme.beam_thickness = beam_thickness;
+        }
+        theme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_config_keeps_defaults() {
+        let config: GameConfig = toml::from_str(
+            "[simulation]\nseed = 7\n\n[board]\nplayer_count = 3\n\n[control]\nlocal_player = 1\n",
+        )
+        .expect("config parses");
+
+        let params = config.simulation_params();
+        assert_eq!(params.seed, 7);
+        assert_eq!(params.fixed_delta, SimulationParams::default().fixed_delta);
+        let board = config.board_settings();
+        assert_eq!(board.player_count, 3);
+        assert_eq!(board.board_size, BoardSettings::default().board_size);
+        assert_eq!(config.control_settings().local_player, PlayerId(1));
+        assert_eq!(
+            config.theme_settings().unit_size,
+            ThemeSettings::default().unit_size
+        );
+    }
+
+    #[test]
+    fn theme_section_overrides_sizes_and_colors() {
+        let config: GameConfig = toml::from_str(
+            "[theme]\nboard_color = [1.0, 0.0, 0.0]\nunit_size = [12.0, 16.0]\nbeam_thickness = 2.5\n",
+        )
+        .expect("config parses");
+
+        let theme = config.theme_settings();
+        assert_eq!(theme.board_color, Color::srgb(1.0, 0.0, 0.0));
+        assert_eq!(theme.unit_size, Vec2::new(12.0, 16.0));
+        assert_eq!(theme.beam_thickness, 2.5);
+        assert_eq!(theme.pylon_size, ThemeSettings::default().pylon_size);
+        assert_eq!(theme.background, ThemeSettings::default().background);
+    }
+}
@@ -1,10 +1,88 @@
-use bevy::diagnostic::LogDiagnosticsPlugin;
+//! Simulation diagnostics, recorded every simulation tick into the
+//! `DiagnosticsStore` and logged alongside Bevy's built-in frame metrics.
+//!
+//! Paths are stable so tests and overlays can look them up directly:
+//! - [`UNITS_TOTAL`] (`simulation/units/total`): living units across all players.
+//! - [`UNITS_PER_PLAYER`] (`simulation/units/player_<idx>`): living units per player.
+//! - [`SUPPORT_LINKS`] (`simulation/support_links`): active unit-to-unit support links.
+//! - [`MATCH_TICK`] (`simulation/tick`): current [`SimulationTick`].
+//! - [`BEAM_EFFECTS`] (`render/beam_effects`): live beam entities, capped by
+//!   [`RenderSettings::max_beam_effects`](crate::gameplay::RenderSettings::max_beam_effects).
+
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticPath, Diagnostics, LogDiagnosticsPlugin, RegisterDiagnostic,
+};
 use bevy::prelude::*;
 
+use crate::gameplay::{
+    BeamEffect, SimulationSet, SimulationTick, SupportLinkBuffer, Unit, MAX_PLAYERS,
+};
+use crate::SimulationSchedule;
+
+pub const UNITS_TOTAL: DiagnosticPath = DiagnosticPath::const_new("simulation/units/total");
+pub const UNITS_PER_PLAYER: [DiagnosticPath; MAX_PLAYERS] = [
+    DiagnosticPath::const_new("simulation/units/player_0"),
+    DiagnosticPath::const_new("simulation/units/player_1"),
+    DiagnosticPath::const_new("simulation/units/player_2"),
+    DiagnosticPath::const_new("simulation/units/player_3"),
+    DiagnosticPath::const_new("simulation/units/player_4"),
+    DiagnosticPath::const_new("simulation/units/player_5"),
+    DiagnosticPath::const_new("simulation/units/player_6"),
+    DiagnosticPath::const_new("simulation/units/player_7"),
+    DiagnosticPath::const_new("simulation/units/player_8"),
+    DiagnosticPath::const_new("simulation/units/player_9"),
+    DiagnosticPath::const_new("simulation/units/player_10"),
+    DiagnosticPath::const_new("simulation/units/player_11"),
+    DiagnosticPath::const_new("simulation/units/player_12"),
+    DiagnosticPath::const_new("simulation/units/player_13"),
+    DiagnosticPath::const_new("simulation/units/player_14"),
+    DiagnosticPath::const_new("simulation/units/player_15"),
+];
+pub const SUPPORT_LINKS: DiagnosticPath = DiagnosticPath::const_new("simulation/support_links");
+pub const MATCH_TICK: DiagnosticPath = DiagnosticPath::const_new("simulation/tick");
+pub const BEAM_EFFECTS: DiagnosticPath = DiagnosticPath::const_new("render/beam_effects");
+
 pub struct DiagnosticsPlugin;
 
 impl Plugin for DiagnosticsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(LogDiagnosticsPlugin::default());
+        app.register_diagnostic(Diagnostic::new(UNITS_TOTAL))
+            .register_diagnostic(Diagnostic::new(SUPPORT_LINKS))
+            .register_diagnostic(Diagnostic::new(MATCH_TICK))
+            .register_diagnostic(Diagnostic::new(BEAM_EFFECTS));
+        for path in UNITS_PER_PLAYER {
+            app.register_diagnostic(Diagnostic::new(path));
+        }
+
+        app.add_plugins(LogDiagnosticsPlugin::default())
+            .add_systems(
+                SimulationSchedule,
+                record_simulation_diagnostics.after(SimulationSet::Combat),
+            );
+    }
+}
+
+fn record_simulation_diagnostics(
+    mut diagnostics: Diagnostics,
+    units: Query<&Unit>,
+    links: Res<SupportLinkBuffer>,
+    tick: Res<SimulationTick>,
+    beams: Query<(), With<BeamEffect>>,
+) {
+    let mut per_player = [0usize; MAX_PLAYERS];
+    let mut total = 0usize;
+    for unit in units.iter() {
+        total += 1;
+        if let Some(count) = per_player.get_mut(unit.player.0) {
+            *count += 1;
+        }
+    }
+
+    diagnostics.add_measurement(&UNITS_TOTAL, || total as f64);
+    for (path, count) in UNITS_PER_PLAYER.iter().zip(per_player) {
+        diagnostics.add_measurement(path, || count as f64);
     }
+    diagnostics.add_measurement(&SUPPORT_LINKS, || links.active_links() as f64);
+    diagnostics.add_measurement(&MATCH_TICK, || tick.0 as f64);
+    diagnostics.add_measurement(&BEAM_EFFECTS, || beams.iter().count() as f64);
 }
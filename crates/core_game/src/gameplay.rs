@@ -1,42 +1,74 @@
+use bevy::ecs::schedule::SystemConfigs;
+use bevy::ecs::system::SystemParam;
 use bevy::input::mouse::MouseButton;
 use bevy::input::ButtonInput;
 use bevy::math::IVec2;
 use bevy::prelude::*;
+#[cfg(feature = "render")]
 use bevy::render::camera::Camera;
 use bevy::time::{Fixed, Time};
 use bevy::utils::{HashMap, HashSet};
+#[cfg(feature = "render")]
 use bevy::window::PrimaryWindow;
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::collections::VecDeque;
-use std::f32::consts::TAU;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 use std::ops::RangeInclusive;
 
+use crate::config::GameConfig;
+use crate::SimulationSchedule;
+
 const DEFAULT_SEED: u64 = 42;
 const DEFAULT_FIXED_DELTA: f64 = 1.0 / 30.0;
 const DEFAULT_BOARD_SIZE: f32 = 1600.0;
 const DEFAULT_PLAYER_COUNT: usize = 4;
 const DEFAULT_SPAWN_INTERVAL: f32 = 1.0;
-const MIN_PLAYERS: usize = 2;
-const MAX_PLAYERS: usize = 8;
+const DEFAULT_MAX_UNITS_PER_PLAYER: usize = 50;
+const DEFAULT_PLAYER_MAX_HEALTH: f32 = 100.0;
+const DEFAULT_HEALTH_DECAY_PER_SECOND: f32 = 1.0;
+const DEFAULT_VETERANCY_THRESHOLDS: [u32; 3] = [2, 5, 10];
+const DEFAULT_VETERANCY_DAMAGE_BONUS: f32 = 0.1;
+const DEFAULT_VETERANCY_HEALTH_BONUS: f32 = 5.0;
+const DEFAULT_OOC_REGEN_GRACE_TICKS: u64 = 90;
+const DEFAULT_RETREAT_RESUME_THRESHOLD: f32 = 0.75;
+const DEFAULT_TARGET_LOCK_GRACE_TICKS: u64 = 30;
+const DEFAULT_INCOME_PER_SECOND: f32 = 5.0;
+const DEFAULT_PYLON_INCOME_PER_SECOND: f32 = 2.0;
+const DEFAULT_UNIT_COST: f32 = 5.0;
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 16;
 const UNIT_SPEED: f32 = 120.0;
 const UNIT_ACCELERATION: f32 = 8.0;
 const UNIT_SEPARATION_RADIUS: f32 = 40.0;
 const SEPARATION_FORCE: f32 = 60.0;
 const FORMATION_SPACING: f32 = 60.0;
+const WAYPOINT_RADIUS: f32 = 12.0;
+/// Radians per second a unit turns toward its heading.
+const UNIT_TURN_RATE: f32 = 4.0;
+/// Slower units keep their facing instead of turning toward the drift.
+const FACING_MIN_SPEED: f32 = 10.0;
 const LASER_RANGE: f32 = 260.0;
 const LASER_DAMAGE: f32 = 6.0;
 const LASER_COOLDOWN: f32 = 0.7;
+const CANNON_COOLDOWN: f32 = 1.2;
 const LASER_HEAL_RANGE: f32 = 150.0;
 const BEAM_LIFETIME: f32 = 0.15;
+const DEFAULT_MAX_BEAM_EFFECTS: usize = 512;
 const SUPPORT_HEAL_PER_SECOND: f32 = 1.0;
 const SUPPORT_DAMAGE_BONUS: f32 = 0.05;
-const PYLON_COUNT: usize = 3;
+const DEFAULT_PYLON_COUNT: usize = 3;
 const PYLON_RADIUS: f32 = 180.0;
 const PYLON_DAMAGE_BONUS: f32 = 0.04;
 const PYLON_GRAVITY: f32 = 18000.0;
 const PYLON_MAX_SPEED: f32 = 240.0;
+const PYLON_NEUTRAL_COLOR: Color = Color::srgb(0.4, 0.85, 1.0);
+const DEFAULT_PYLON_MAX_HEALTH: f32 = 300.0;
+const DEFAULT_PYLON_CAPTURE_PER_SECOND: f32 = 0.25;
 
-const PLAYER_COLORS: [Color; MAX_PLAYERS] = [
+/// Hand-picked colors for the first eight players; [`player_color`] generates
+/// the rest.
+const CURATED_PLAYER_COLORS: [Color; 8] = [
     Color::srgb(0.93, 0.26, 0.28),
     Color::srgb(0.26, 0.65, 0.93),
     Color::srgb(0.94, 0.76, 0.16),
@@ -53,14 +85,42 @@ pub struct GameplayPlugin;
 
 impl Plugin for GameplayPlugin {
     fn build(&self, app: &mut App) {
+        let config = GameConfig::from_env();
         if !app.world().contains_resource::<SimulationParams>() {
-            app.insert_resource(SimulationParams::from_env());
+            let params = config
+                .as_ref()
+                .map_or_else(SimulationParams::default, GameConfig::simulation_params);
+            app.insert_resource(params.with_env_overrides());
         }
         if !app.world().contains_resource::<BoardSettings>() {
-            app.insert_resource(BoardSettings::from_env());
+            let board = config
+                .as_ref()
+                .map_or_else(BoardSettings::env_baseline, GameConfig::board_settings);
+            app.insert_resource(board.with_env_overrides());
         }
         if !app.world().contains_resource::<ControlSettings>() {
-            app.insert_resource(ControlSettings::from_env());
+            let control = config
+                .as_ref()
+                .map_or_else(ControlSettings::default, GameConfig::control_settings);
+            app.insert_resource(control.with_env_overrides());
+        }
+        if !app.world().contains_resource::<Teams>() {
+            let teams = config
+                .as_ref()
+                .map_or_else(Teams::default, GameConfig::teams);
+            app.insert_resource(teams);
+        }
+        if !app.world().contains_resource::<RenderSettings>() {
+            let render = config
+                .as_ref()
+                .map_or_else(RenderSettings::default, GameConfig::render_settings);
+            app.insert_resource(render);
+        }
+        if !app.world().contains_resource::<ThemeSettings>() {
+            let theme = config
+                .as_ref()
+                .map_or_else(ThemeSettings::default, GameConfig::theme_settings);
+            app.insert_resource(theme);
         }
         if app
             .world()
@@ -72,9 +132,25 @@ impl Plugin for GameplayPlugin {
         }
 
         app.init_resource::<SimulationRng>()
+            .init_resource::<SpawnCompositionRng>()
+            .init_resource::<SimulationTick>()
+            .init_resource::<GameOutcome>()
+            .init_resource::<BalanceSettings>()
+            .init_resource::<CombatLog>()
+            .init_resource::<MatchStats>()
+            .init_resource::<Economy>()
+            .init_resource::<PlayerHealth>()
+            .init_resource::<RenderEffects>()
+            .init_resource::<RenderInterpolation>()
             .init_resource::<SupportLinkBuffer>()
+            .init_resource::<SupplyState>()
             .init_resource::<SupportLinkPool>()
             .init_resource::<SelectionState>()
+            .init_resource::<DebugOverlays>()
+            .init_resource::<EventFeed>()
+            // Inserted rather than initialized so a rebuilt app numbers its
+            // units from zero again.
+            .insert_resource(NextUnitId::default())
             .add_systems(Startup, configure_fixed_time)
             .add_systems(
                 Startup,
@@ -85,28 +161,85 @@ impl Plugin for GameplayPlugin {
                 ),
             )
             .add_systems(
-                FixedUpdate,
+                SimulationSchedule,
                 (
-                    tick_spawn_timers,
+                    advance_simulation_tick,
+                    spawn_reinforcements.after(advance_simulation_tick),
                     move_units,
-                    update_unit_rally_targets,
-                    unit_combat_system.after(move_units),
+                    // Both write `Unit`; left unordered, the executor could
+                    // pick either order from run to run.
+                    update_unit_rally_targets.before(move_units),
+                    move_pylons
+                        .after(record_previous_positions)
+                        .before(capture_pylons),
+                    capture_pylons.after(move_units).before(unit_combat_system),
+                    unit_combat_system
+                        .in_set(SimulationSet::Combat)
+                        .after(move_units),
+                    retreat_damaged_units.after(unit_combat_system),
                     render_support_links.after(unit_combat_system),
+                    // After the spawns so this tick's reinforcements count.
+                    detect_game_outcome
+                        .in_set(SimulationSet::Outcome)
+                        .after(unit_combat_system)
+                        .after(spawn_reinforcements),
+                    decay_player_health,
+                    accrue_resources
+                        .after(advance_simulation_tick)
+                        .before(spawn_reinforcements),
+                    record_previous_positions
+                        .after(advance_simulation_tick)
+                        .before(move_units),
+                    turn_units
+                        .after(update_unit_rally_targets)
+                        .after(SimulationSet::Combat),
                 ),
             )
             .add_systems(
                 Update,
                 (
-                    handle_selection_input,
-                    update_selection_visuals.after(handle_selection_input),
-                    issue_move_orders.after(update_selection_visuals),
+                    switch_local_player,
+                    issue_command_keys.after(switch_local_player),
                     update_beam_effects,
-                    animate_pylons,
+                    sync_unit_transforms,
+                    sync_pylon_transforms,
+                    toggle_debug_overlays,
+                    update_event_feed,
                 ),
             );
+        // Mouse input needs a window and camera to map the cursor, and the
+        // rest only draws; headless builds leave them out.
+        #[cfg(feature = "render")]
+        app.add_systems(
+            Update,
+            (
+                handle_selection_input.after(switch_local_player),
+                update_selection_visuals.after(handle_selection_input),
+                issue_move_orders.after(update_selection_visuals),
+                draw_range_overlays
+                    .after(toggle_debug_overlays)
+                    .run_if(resource_exists::<GizmoConfigStore>),
+            ),
+        );
     }
 }
 
+/// Just the combat system, for timing it in isolation (see the
+/// `llm_regression` combat benchmark). Relies on the resources
+/// [`GameplayPlugin`] initializes.
+pub fn combat_system() -> SystemConfigs {
+    unit_combat_system.into_configs()
+}
+
+/// Ordering anchors inside `SimulationSchedule` for systems outside this module.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    /// Unit combat, healing, and support-link resolution.
+    Combat,
+    /// [`GameOutcome`] detection, after combat.
+    Outcome,
+}
+
 #[derive(Resource, Clone, Debug)]
 pub struct SimulationParams {
     pub seed: u64,
@@ -115,15 +248,18 @@ pub struct SimulationParams {
 
 impl SimulationParams {
     pub fn from_env() -> Self {
-        let seed = std::env::var("SIMULATION_SEED")
-            .ok()
-            .and_then(|val| val.parse().ok())
-            .unwrap_or(DEFAULT_SEED);
-        let fixed_delta = std::env::var("SIMULATION_FIXED_DT")
-            .ok()
-            .and_then(|val| val.parse().ok())
-            .unwrap_or(DEFAULT_FIXED_DELTA);
-        Self { seed, fixed_delta }
+        Self::default().with_env_overrides()
+    }
+
+    /// Replaces fields with `SIMULATION_SEED` / `SIMULATION_FIXED_DT` when set.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some(seed) = env_value("SIMULATION_SEED") {
+            self.seed = seed;
+        }
+        if let Some(fixed_delta) = env_value("SIMULATION_FIXED_DT") {
+            self.fixed_delta = fixed_delta;
+        }
+        self
     }
 
     pub fn from_seed(seed: u64) -> Self {
@@ -147,34 +283,93 @@ impl Default for SimulationParams {
 pub struct BoardSettings {
     pub board_size: f32,
     pub player_count: usize,
+    /// Seconds between reinforcement waves.
     pub spawn_interval: f32,
+    /// Spawn a wave every `spawn_interval`. Off, players fight with the units
+    /// they start with.
+    pub reinforcements: bool,
+    /// Seeded pylons spawned at startup; `0` disables pylons entirely.
+    pub pylon_count: usize,
+    /// Reinforcements skip a player who already has this many living units;
+    /// `0` removes the cap.
+    pub max_units_per_player: usize,
+    /// Relative weight of each kind among spawned units, drawn from
+    /// [`SpawnCompositionRng`]. Units placed by an [`InitialLayout`] keep
+    /// their own kind. Empty or all-zero weights spawn Lasers.
+    pub spawn_composition: Vec<(UnitKind, u32)>,
+    /// Simulation ticks after which an undecided match ends in
+    /// [`GameOutcome::Draw`]; `0` lets it run forever.
+    pub max_match_ticks: u64,
 }
 
 impl BoardSettings {
     pub fn from_env() -> Self {
+        Self::env_baseline().with_env_overrides()
+    }
+
+    /// Defaults before any overrides: the RTS sandbox starts with more players.
+    pub(crate) fn env_baseline() -> Self {
         let scene_hint = std::env::var("SANDBOX_SCENE").unwrap_or_default();
-        let mut player_count = std::env::var("BOARD_PLAYER_COUNT")
-            .ok()
-            .and_then(|val| val.parse().ok())
-            .unwrap_or(if scene_hint == "rts_board" {
+        Self {
+            player_count: if scene_hint == "rts_board" {
                 DEFAULT_PLAYER_COUNT
             } else {
                 MIN_PLAYERS
-            });
-        player_count = player_count.clamp(MIN_PLAYERS, MAX_PLAYERS);
-        let board_size = std::env::var("BOARD_SIZE")
-            .ok()
-            .and_then(|val| val.parse().ok())
-            .unwrap_or(DEFAULT_BOARD_SIZE);
-        let spawn_interval = std::env::var("BOARD_SPAWN_INTERVAL")
-            .ok()
-            .and_then(|val| val.parse().ok())
-            .unwrap_or(DEFAULT_SPAWN_INTERVAL);
-        Self {
-            board_size,
-            player_count,
-            spawn_interval,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Replaces fields with `BOARD_PLAYER_COUNT` / `BOARD_SIZE` /
+    /// `BOARD_SPAWN_INTERVAL` / `BOARD_PYLON_COUNT` /
+    /// `BOARD_MAX_UNITS_PER_PLAYER` / `BOARD_MAX_MATCH_TICKS` when set.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some(player_count) = env_value("BOARD_PLAYER_COUNT") {
+            self.player_count = player_count;
+        }
+        if let Some(board_size) = env_value("BOARD_SIZE") {
+            self.board_size = board_size;
+        }
+        if let Some(spawn_interval) = env_value("BOARD_SPAWN_INTERVAL") {
+            self.spawn_interval = spawn_interval;
+        }
+        if let Some(pylon_count) = env_value("BOARD_PYLON_COUNT") {
+            self.pylon_count = pylon_count;
         }
+        if let Some(max_units) = env_value("BOARD_MAX_UNITS_PER_PLAYER") {
+            self.max_units_per_player = max_units;
+        }
+        if let Some(max_ticks) = env_value("BOARD_MAX_MATCH_TICKS") {
+            self.max_match_ticks = max_ticks;
+        }
+        self.sanitized()
+    }
+
+    /// Whether a player with `living` units is at the reinforcement cap.
+    pub fn at_unit_cap(&self, living: usize) -> bool {
+        self.max_units_per_player != 0 && living >= self.max_units_per_player
+    }
+
+    /// Whether a match still undecided at `tick` is out of time.
+    pub fn match_time_up(&self, tick: u64) -> bool {
+        self.max_match_ticks != 0 && tick >= self.max_match_ticks
+    }
+
+    /// Picks a kind by `spawn_composition` weight.
+    pub fn sample_unit_kind(&self, rng: &mut impl Rng) -> UnitKind {
+        weighted_choice(&self.spawn_composition, rng).unwrap_or(UnitKind::Laser)
+    }
+
+    /// Half-width of the square area units and pylons are kept inside.
+    pub fn playfield_half_extent(&self) -> f32 {
+        self.board_size * 0.45
+    }
+
+    /// Clamps `player_count` into `MIN_PLAYERS..=MAX_PLAYERS`. Applied by
+    /// `setup_board` so directly constructed settings are safe too.
+    pub fn sanitized(mut self) -> Self {
+        self.player_count = self.player_count.clamp(MIN_PLAYERS, MAX_PLAYERS);
+        self
     }
 }
 
@@ -184,6 +379,11 @@ impl Default for BoardSettings {
             board_size: DEFAULT_BOARD_SIZE,
             player_count: DEFAULT_PLAYER_COUNT,
             spawn_interval: DEFAULT_SPAWN_INTERVAL,
+            reinforcements: true,
+            pylon_count: DEFAULT_PYLON_COUNT,
+            max_units_per_player: DEFAULT_MAX_UNITS_PER_PLAYER,
+            spawn_composition: vec![(UnitKind::Laser, 1)],
+            max_match_ticks: 0,
         }
     }
 }
@@ -191,25 +391,38 @@ impl Default for BoardSettings {
 #[derive(Resource, Clone, Copy, Debug)]
 pub struct ControlSettings {
     pub local_player: PlayerId,
+    /// Selected units drop their orders and stop where they are.
+    pub stop_key: KeyCode,
+    /// Like `stop_key`, and the units then keep their ground: separation
+    /// stops nudging them until they get a move order. They still fire at
+    /// anything in range.
+    pub hold_key: KeyCode,
 }
 
 impl ControlSettings {
     pub fn from_env() -> Self {
-        let id = std::env::var("LOCAL_PLAYER_ID")
-            .ok()
-            .and_then(|val| val.parse().ok())
-            .unwrap_or(0)
-            .clamp(0, MAX_PLAYERS as i32 - 1) as usize;
-        Self {
-            local_player: PlayerId(id),
+        Self::default().with_env_overrides()
+    }
+
+    /// Replaces the local player with `LOCAL_PLAYER_ID` when set.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some(id) = env_value::<i32>("LOCAL_PLAYER_ID") {
+            self.local_player = PlayerId(id.clamp(0, MAX_PLAYERS as i32 - 1) as usize);
         }
+        self
     }
 }
 
+fn env_value<T: std::str::FromStr>(key: &str) -> Option<T> {
+    std::env::var(key).ok().and_then(|val| val.parse().ok())
+}
+
 impl Default for ControlSettings {
     fn default() -> Self {
         Self {
             local_player: PlayerId(0),
+            stop_key: KeyCode::KeyS,
+            hold_key: KeyCode::KeyH,
         }
     }
 }
@@ -218,6 +431,7 @@ impl Default for ControlSettings {
 pub struct SimulationRng {
     seed: u64,
     rng: StdRng,
+    draws: u64,
 }
 
 impl SimulationRng {
@@ -225,6 +439,7 @@ impl SimulationRng {
         Self {
             seed,
             rng: StdRng::seed_from_u64(seed),
+            draws: 0,
         }
     }
 
@@ -232,13 +447,54 @@ impl SimulationRng {
         self.seed
     }
 
+    /// Number of draws (including forks) taken from this stream so far.
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
     pub fn gen_range(&mut self, range: RangeInclusive<u32>) -> u32 {
+        self.draws += 1;
         self.rng.gen_range(range)
     }
 
     pub fn gen_f32(&mut self, range: RangeInclusive<f32>) -> f32 {
+        self.draws += 1;
         self.rng.gen_range(range)
     }
+
+    /// Derives an independent child stream from the parent seed, `label`, and
+    /// the current draw count without consuming values from the main stream.
+    /// Forking bumps the draw count so repeated forks with one label differ.
+    pub fn fork(&mut self, label: &str) -> StdRng {
+        let seed = fork_seed(self.seed, label, self.draws);
+        self.draws += 1;
+        StdRng::seed_from_u64(seed)
+    }
+
+    /// Child stream keyed only by the parent seed and `label`, identical to a
+    /// [`Self::fork`] taken before any draws. Use it for setup whose outcome
+    /// must not shift when unrelated systems draw from the main stream.
+    pub fn stream(&self, label: &str) -> StdRng {
+        StdRng::seed_from_u64(fork_seed(self.seed, label, 0))
+    }
+}
+
+/// FNV-1a over the parent seed, label bytes, and draw count. Kept local so the
+/// derived seeds never change with the standard library's hasher.
+fn fork_seed(seed: u64, label: &str, draws: u64) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash = FNV_OFFSET;
+    let bytes = seed
+        .to_le_bytes()
+        .into_iter()
+        .chain(label.bytes())
+        .chain(draws.to_le_bytes());
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
 }
 
 impl FromWorld for SimulationRng {
@@ -252,1014 +508,4470 @@ impl FromWorld for SimulationRng {
     }
 }
 
-#[derive(Resource, Default, Debug)]
-struct SpawnRegistry {
-    entries: Vec<SpawnEntry>,
+/// One entry of `weights`, each as likely as its share of the total; `None`
+/// when the weights sum to zero, which draws nothing from `rng`.
+fn weighted_choice<T: Copy>(weights: &[(T, u32)], rng: &mut impl Rng) -> Option<T> {
+    let total: u64 = weights.iter().map(|&(_, weight)| u64::from(weight)).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    for &(item, weight) in weights {
+        let weight = u64::from(weight);
+        if roll < weight {
+            return Some(item);
+        }
+        roll -= weight;
+    }
+    None
 }
 
-#[derive(Debug, Clone)]
-struct SpawnEntry {
-    player: PlayerId,
-    position: Vec2,
-}
+/// Stream [`BoardSettings::sample_unit_kind`] draws from. Keyed only by the
+/// seed, so changing the composition never shifts jitter, pylons, or AI.
+#[derive(Resource, Debug)]
+pub struct SpawnCompositionRng(StdRng);
 
-#[derive(Resource)]
-struct SpawnTimers {
-    timers: Vec<Timer>,
+impl FromWorld for SpawnCompositionRng {
+    fn from_world(world: &mut World) -> Self {
+        const LABEL: &str = "spawn_composition";
+        let stream = world
+            .get_resource::<SimulationRng>()
+            .map(|rng| rng.stream(LABEL))
+            .unwrap_or_else(|| SimulationRng::from_world(world).stream(LABEL));
+        Self(stream)
+    }
 }
 
-#[derive(Resource, Default)]
-struct SelectionState {
-    is_dragging: bool,
-    start_world: Vec2,
-    current_world: Vec2,
-    rectangle_entity: Option<Entity>,
-    selected: Vec<Entity>,
-    prev_selected: Vec<Entity>,
-    dirty: bool,
+/// Explicit starting layout, e.g. loaded from a sandbox scene or built with
+/// [`ScenarioBuilder`](crate::scenario::ScenarioBuilder). Each `None` field
+/// falls back to the default layout: two starter units per spawn and seeded
+/// pylon placement.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct InitialLayout {
+    pub units: Option<Vec<(PlayerId, UnitKind, Vec2)>>,
+    pub pylons: Option<Vec<Vec2>>,
 }
 
-#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct PlayerId(pub usize);
-
-#[derive(Component)]
-pub struct Unit {
-    pub player: PlayerId,
-    pub rally_target: Vec2,
-    pub kind: UnitKind,
-    pub health: f32,
-    pub max_health: f32,
-    pub attack_timer: Timer,
-    pub velocity: Vec2,
-    pub base_color: Color,
-    pub boost_visual: Option<Entity>,
+/// Structured record of one combat step, keyed by [`UnitId`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CombatEvent {
+    Heal {
+        tick: u64,
+        unit: UnitId,
+        amount: f32,
+    },
+    Shot {
+        tick: u64,
+        attacker: UnitId,
+        target: UnitId,
+        damage: f32,
+    },
+    Kill {
+        tick: u64,
+        attacker: UnitId,
+        target: UnitId,
+    },
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum UnitKind {
-    Laser,
+/// Append-only combat event stream for trace snapshots.
+///
+/// Within a tick, events are grouped as heals, shots, then kills, each sorted
+/// by unit / attacker id so the log is identical for identical inputs. Once
+/// `capacity` is exceeded the oldest events are dropped.
+#[derive(Resource, Clone, Debug)]
+pub struct CombatLog {
+    events: VecDeque<CombatEvent>,
+    capacity: usize,
+    recorded: u64,
 }
 
-impl UnitKind {
-    fn health(&self) -> f32 {
-        match self {
-            UnitKind::Laser => 45.0,
+impl CombatLog {
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity,
+            recorded: 0,
         }
     }
 
-    fn attack_cooldown(&self) -> f32 {
-        match self {
-            UnitKind::Laser => LASER_COOLDOWN,
+    pub fn events(&self) -> &VecDeque<CombatEvent> {
+        &self.events
+    }
+
+    /// Events ever recorded, including ones since dropped or cleared; a
+    /// cursor for [`CombatLog::events_since`].
+    pub fn total_recorded(&self) -> u64 {
+        self.recorded
+    }
+
+    /// Events recorded after `total_recorded()` returned `cursor`, minus any
+    /// already dropped.
+    pub fn events_since(&self, cursor: u64) -> impl Iterator<Item = &CombatEvent> {
+        let new = self.recorded.saturating_sub(cursor);
+        let new = usize::try_from(new)
+            .unwrap_or(usize::MAX)
+            .min(self.events.len());
+        self.events.range(self.events.len() - new..)
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    fn record_tick(
+        &mut self,
+        tick: u64,
+        ids: &HashMap<Entity, UnitId>,
+        heals: &[(Entity, f32)],
+        shots: &[(Entity, Entity, f32)],
+        kills: &[(Entity, Entity)],
+    ) {
+        let id = |entity: Entity| ids.get(&entity).copied().unwrap_or_default();
+        let before = self.events.len();
+        self.events
+            .extend(heals.iter().map(|&(unit, amount)| CombatEvent::Heal {
+                tick,
+                unit: id(unit),
+                amount,
+            }));
+        self.events.extend(
+            shots
+                .iter()
+                .map(|&(attacker, target, damage)| CombatEvent::Shot {
+                    tick,
+                    attacker: id(attacker),
+                    target: id(target),
+                    damage,
+                }),
+        );
+        self.events
+            .extend(kills.iter().map(|&(attacker, target)| CombatEvent::Kill {
+                tick,
+                attacker: id(attacker),
+                target: id(target),
+            }));
+        self.recorded += (self.events.len() - before) as u64;
+        while self.events.len() > self.capacity {
+            self.events.pop_front();
         }
     }
 }
 
-#[derive(Component)]
-struct SelectionRect;
-
-#[derive(Component)]
-struct SelectionHighlight {
-    glow: Entity,
+impl Default for CombatLog {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
 }
 
-#[derive(Component)]
-struct Pylon {
-    velocity: Vec2,
-    mass: f32,
+/// Combat totals for one player over the whole match.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PlayerStats {
+    pub shots: u32,
+    pub damage_dealt: f32,
+    pub kills: u32,
+    pub losses: u32,
 }
 
-#[derive(Component)]
-struct BeamEffect {
-    timer: Timer,
+/// Running [`PlayerStats`] for every player that has fired or lost a unit.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct MatchStats {
+    players: Vec<PlayerStats>,
 }
 
-#[derive(Component)]
-struct SupportLinkVisual;
+impl MatchStats {
+    pub fn player(&self, player: PlayerId) -> PlayerStats {
+        self.players.get(player.0).cloned().unwrap_or_default()
+    }
 
-#[derive(Clone)]
-struct SupportLinkRenderable {
-    start: Vec2,
-    end: Vec2,
-    color: Color,
-    thickness: f32,
+    fn player_mut(&mut self, player: PlayerId) -> &mut PlayerStats {
+        if self.players.len() <= player.0 {
+            self.players.resize_with(player.0 + 1, PlayerStats::default);
+        }
+        &mut self.players[player.0]
+    }
+
+    fn record_tick(
+        &mut self,
+        owners: &HashMap<Entity, (PlayerId, Vec2)>,
+        damage: &[(Entity, Entity, f32)],
+        deaths: &[(Entity, Entity)],
+    ) {
+        for (attacker, _, amount) in damage {
+            if let Some(&(player, _)) = owners.get(attacker) {
+                let stats = self.player_mut(player);
+                stats.shots += 1;
+                stats.damage_dealt += amount;
+            }
+        }
+        for (attacker, target) in deaths {
+            if let Some(&(player, _)) = owners.get(attacker) {
+                self.player_mut(player).kills += 1;
+            }
+            if let Some(&(player, _)) = owners.get(target) {
+                self.player_mut(player).losses += 1;
+            }
+        }
+    }
 }
 
-#[derive(Resource, Default)]
-struct SupportLinkBuffer {
-    links: Vec<SupportLinkRenderable>,
+/// Something worth a line in the [`EventFeed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeedEvent {
+    /// `owner` is `None` when the unit died before the feed ever saw it.
+    UnitDestroyed {
+        unit: UnitId,
+        owner: Option<PlayerId>,
+        by: UnitId,
+    },
+    PylonCaptured(TeamId),
+    /// The player's last unit died; reinforcements may still bring them back.
+    PlayerEliminated(PlayerId),
+    MatchDecided(GameOutcome),
 }
 
-#[derive(Resource, Default)]
-struct SupportLinkPool {
-    entities: Vec<Entity>,
+impl std::fmt::Display for FeedEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            FeedEvent::UnitDestroyed {
+                unit,
+                owner: Some(owner),
+                by,
+            } => write!(f, "P{} unit {} destroyed by unit {}", owner.0, unit.0, by.0),
+            FeedEvent::UnitDestroyed {
+                unit,
+                owner: None,
+                by,
+            } => write!(f, "unit {} destroyed by unit {}", unit.0, by.0),
+            FeedEvent::PylonCaptured(team) => write!(f, "team {} captured a pylon", team.0),
+            FeedEvent::PlayerEliminated(player) => write!(f, "player {} eliminated", player.0),
+            FeedEvent::MatchDecided(GameOutcome::Victory(player)) => {
+                write!(f, "player {} wins", player.0)
+            }
+            FeedEvent::MatchDecided(GameOutcome::TeamVictory(team)) => {
+                write!(f, "team {} wins", team.0)
+            }
+            FeedEvent::MatchDecided(GameOutcome::Draw) => f.write_str("draw"),
+            FeedEvent::MatchDecided(GameOutcome::InProgress) => f.write_str("match in progress"),
+        }
+    }
 }
-fn setup_board(mut commands: Commands, settings: Res<BoardSettings>) {
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.09, 0.12, 0.2),
-            custom_size: Some(Vec2::splat(settings.board_size)),
-            ..default()
-        },
-        Transform::from_xyz(0.0, 0.0, -0.5),
-    ));
 
-    let mut registry = SpawnRegistry::default();
-    let radius = settings.board_size * 0.35;
-    for idx in 0..settings.player_count {
-        let angle = idx as f32 / settings.player_count as f32 * TAU;
-        let position = Vec2::new(angle.cos() * radius, angle.sin() * radius);
-        let player = PlayerId(idx);
-        registry.entries.push(SpawnEntry { player, position });
+/// A [`FeedEvent`] and the simulation tick it was seen on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeedEntry {
+    pub tick: u64,
+    pub event: FeedEvent,
+}
 
-        commands.spawn((
-            Sprite {
-                color: PLAYER_COLORS[idx],
-                custom_size: Some(Vec2::splat(20.0)),
-                ..default()
-            },
-            Transform::from_xyz(position.x, position.y, 0.1),
-        ));
+impl std::fmt::Display for FeedEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.tick, self.event)
     }
+}
 
-    commands.insert_resource(registry);
+/// Recent kills, pylon captures, eliminations, and the match result, for the
+/// HUD. Filled in `Update` from [`CombatLog`], pylon ownership, and
+/// [`GameOutcome`]; the simulation never reads it. Kills keep the tick the
+/// combat log gave them; the rest are stamped with the tick on which `Update`
+/// first noticed them, which is the tick they happened when every tick gets
+/// a frame. Once `capacity` is exceeded the oldest entries are dropped.
+#[derive(Resource, Clone, Debug)]
+pub struct EventFeed {
+    entries: VecDeque<FeedEntry>,
+    capacity: usize,
+    /// Whether the HUD draws the feed; entries accumulate either way.
+    pub visible: bool,
 }
 
-fn spawn_initial_units(
-    mut commands: Commands,
-    registry: Res<SpawnRegistry>,
-    settings: Res<BoardSettings>,
-) {
-    let mut timers = SpawnTimers { timers: Vec::new() };
-    for entry in registry.entries.iter() {
-        let player_color = PLAYER_COLORS[entry.player.0];
-        let offset = Vec2::new(18.0, 0.0);
-        spawn_unit(
-            &mut commands,
-            entry.player,
-            entry.position + offset,
-            entry.position,
-            player_color,
-        );
-        spawn_unit(
-            &mut commands,
-            entry.player,
-            entry.position - offset,
-            entry.position,
-            player_color,
-        );
-        timers.timers.push(Timer::from_seconds(
-            settings.spawn_interval,
-            TimerMode::Repeating,
-        ));
+impl EventFeed {
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            visible: true,
+        }
+    }
+
+    /// Oldest first.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &FeedEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    /// The newest `count` entries, oldest first.
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &FeedEntry> {
+        self.entries
+            .iter()
+            .skip(self.entries.len().saturating_sub(count))
+    }
+
+    pub fn push(&mut self, tick: u64, event: FeedEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(FeedEntry { tick, event });
     }
-    commands.insert_resource(timers);
 }
 
-fn spawn_pylons(
-    mut commands: Commands,
-    settings: Res<BoardSettings>,
-    mut rng: ResMut<SimulationRng>,
-) {
-    for idx in 0..PYLON_COUNT {
-        let radius = settings.board_size * (0.15 + rng.gen_f32(0.0..=0.15));
-        let angle = rng.gen_f32(0.0..=TAU);
-        let position = Vec2::new(angle.cos(), angle.sin()) * radius;
-        let speed = rng.gen_f32(20.0..=60.0);
-        let velocity = Vec2::new(-angle.sin(), angle.cos()) * speed;
-        let color = Color::srgb(0.4, 0.85, 1.0);
-        commands.spawn((
-            Sprite {
-                color,
-                custom_size: Some(Vec2::new(26.0, 38.0)),
-                ..default()
-            },
-            Transform {
-                translation: Vec3::new(position.x, position.y, 0.2 + idx as f32 * 0.01),
-                rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_4),
-                ..default()
-            },
-            Pylon {
-                velocity,
-                mass: 1.0 + rng.gen_f32(0.0..=1.0),
-            },
-        ));
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
     }
 }
 
-fn animate_pylons(
-    time: Res<Time>,
-    settings: Res<BoardSettings>,
-    mut pylons: Query<(Entity, &mut Transform, &mut Pylon)>,
-) {
-    let dt = time.delta_secs();
-    if pylons.is_empty() {
-        return;
+/// Resources banked by each player while [`BalanceSettings::economy_enabled`]
+/// is set; reinforcements are paid for out of it.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Economy {
+    balances: Vec<f32>,
+}
+
+impl Economy {
+    pub fn balance(&self, player: PlayerId) -> f32 {
+        self.balances.get(player.0).copied().unwrap_or_default()
     }
-    let snapshots: Vec<(Entity, Vec2, Vec2, f32)> = pylons
-        .iter()
-        .map(|(entity, transform, pylon)| {
-            (
-                entity,
-                transform.translation.truncate(),
-                pylon.velocity,
-                pylon.mass,
-            )
-        })
-        .collect();
 
-    let mut accelerations: HashMap<Entity, Vec2> = HashMap::default();
-    for (entity_a, pos_a, _, _) in &snapshots {
-        let mut acc = Vec2::ZERO;
-        for (entity_b, pos_b, _, mass_b) in &snapshots {
-            if entity_a == entity_b {
-                continue;
-            }
-            let offset = *pos_b - *pos_a;
-            let dist_sq = offset.length_squared().max(4000.0);
-            acc += offset.normalize() * (PYLON_GRAVITY * *mass_b / dist_sq);
-        }
-        accelerations.insert(*entity_a, acc);
+    pub fn deposit(&mut self, player: PlayerId, amount: f32) {
+        *self.balance_mut(player) += amount;
     }
 
-    let boundary = settings.board_size * 0.45;
-    for (entity, mut transform, mut pylon) in pylons.iter_mut() {
-        if let Some(acc) = accelerations.get(&entity) {
-            pylon.velocity += *acc * dt;
-        }
-        pylon.velocity = pylon.velocity.clamp_length_max(PYLON_MAX_SPEED);
-        transform.translation.x += pylon.velocity.x * dt;
-        transform.translation.y += pylon.velocity.y * dt;
-        if transform.translation.x.abs() > boundary {
-            transform.translation.x = transform.translation.x.clamp(-boundary, boundary);
-            pylon.velocity.x = -pylon.velocity.x;
+    /// Takes `amount` from `player` if they have it.
+    pub fn try_spend(&mut self, player: PlayerId, amount: f32) -> bool {
+        let balance = self.balance_mut(player);
+        if *balance < amount {
+            return false;
         }
-        if transform.translation.y.abs() > boundary {
-            transform.translation.y = transform.translation.y.clamp(-boundary, boundary);
-            pylon.velocity.y = -pylon.velocity.y;
+        *balance -= amount;
+        true
+    }
+
+    fn balance_mut(&mut self, player: PlayerId) -> &mut f32 {
+        if self.balances.len() <= player.0 {
+            self.balances.resize(player.0 + 1, 0.0);
         }
-        transform.translation.z = 0.2;
+        &mut self.balances[player.0]
     }
 }
 
-fn spawn_unit(
-    commands: &mut Commands,
-    player: PlayerId,
-    position: Vec2,
-    rally_target: Vec2,
-    color: Color,
-) {
-    commands.spawn((
-        Sprite {
-            color,
-            custom_size: Some(Vec2::new(24.0, 32.0)),
-            ..default()
-        },
-        Transform::from_xyz(position.x, position.y, 0.2),
-        Unit {
-            player,
-            rally_target,
-            kind: UnitKind::Laser,
-            health: UnitKind::Laser.health(),
-            max_health: UnitKind::Laser.health(),
-            attack_timer: Timer::from_seconds(
-                UnitKind::Laser.attack_cooldown(),
-                TimerMode::Repeating,
-            ),
-            velocity: Vec2::ZERO,
-            base_color: color,
-            boost_visual: None,
-        },
-    ));
+/// Tunable gameplay numbers that are not tied to the board layout.
+#[derive(Resource, Clone, Debug)]
+pub struct BalanceSettings {
+    /// Starting and maximum value of [`PlayerHealth`].
+    pub player_max_health: f32,
+    /// Health drained from [`PlayerHealth`] per simulated second.
+    pub health_decay_per_second: f32,
+    /// Target choice for every unit kind without an entry in `kind_targeting`.
+    pub targeting: TargetingPolicy,
+    /// Per-kind overrides of `targeting`.
+    pub kind_targeting: HashMap<UnitKind, TargetingPolicy>,
+    /// Units keep shooting the enemy they picked ([`Unit::current_target`])
+    /// until it dies, stays out of range for more than
+    /// `target_lock_grace_ticks`, or an enemy in range beats it on the
+    /// targeting policy's own measure (see [`TargetingPolicy::prefers`]).
+    /// Off, every combat step picks afresh.
+    pub target_lock: bool,
+    /// Ticks a locked target may spend out of range before the lock drops;
+    /// the unit holds fire meanwhile.
+    pub target_lock_grace_ticks: u64,
+    /// Damage multiplier for hits of a [`DamageType`] on a unit kind; below
+    /// 1 resists, above 1 is a weakness. Missing pairs take full damage.
+    pub resistances: HashMap<(UnitKind, DamageType), f32>,
+    /// Units earn ranks from kills, per the `veterancy_*` settings below.
+    /// Off, the default, kills are still counted but nobody is promoted.
+    pub veterancy: bool,
+    /// Kill counts at which a unit reaches rank 1, 2, ...; ascending.
+    pub veterancy_thresholds: Vec<u32>,
+    /// Extra damage per rank, as a fraction of base damage.
+    pub veterancy_damage_bonus: f32,
+    /// Max health added per rank (and healed on promotion).
+    pub veterancy_health_bonus: f32,
+    /// Health per second regained by units out of combat; 0, the default,
+    /// leaves it off.
+    pub ooc_regen_per_second: f32,
+    /// Simulation ticks since a unit last dealt or took damage before it
+    /// counts as out of combat.
+    pub ooc_regen_grace_ticks: u64,
+    /// Pylons power only the team that captured them, and units shoot enemy
+    /// or enemy-contested pylons back to neutral. Off, every pylon powers
+    /// whoever is in range.
+    pub capturable_pylons: bool,
+    /// Health of a capturable pylon; at 0 it turns neutral and heals fully.
+    pub pylon_max_health: f32,
+    /// Capture progress per second for the team with the most units around a
+    /// neutral pylon; 1.0 completes the capture.
+    pub pylon_capture_per_second: f32,
+    /// Ticks a unit stays supplied, with the damage multiplier it last had,
+    /// after its network stops reaching a spawn. 0 drops supply on the same
+    /// tick.
+    pub supply_grace_ticks: u64,
+    /// Spread of per-unit attack cooldowns: each unit's is scaled by a factor
+    /// drawn from `1 ± cooldown_jitter` when it spawns, so squads stop firing
+    /// in lockstep volleys. Clamped to 0.9; 0 keeps every unit on its kind's
+    /// base cooldown and draws nothing.
+    pub cooldown_jitter: f32,
+    /// Health fraction below which a unit that is hit breaks off and heads
+    /// back to its spawn, setting its orders aside; 0 disables retreats.
+    pub retreat_threshold: f32,
+    /// Health fraction at which a retreating unit resumes its orders. Kept
+    /// at or above `retreat_threshold` so units don't flip back and forth.
+    pub retreat_resume_threshold: f32,
+    /// Reinforcements cost `unit_cost` from the player's [`Economy`] balance
+    /// and are skipped while they can't pay. Off, spawning is free.
+    pub economy_enabled: bool,
+    /// Resources every player with a spawn earns per simulated second.
+    pub income_per_second: f32,
+    /// Extra income per pylon the player's team owns; only pylons captured
+    /// under `capturable_pylons` have an owner.
+    pub pylon_income_per_second: f32,
+    /// Price of one reinforcement.
+    pub unit_cost: f32,
+    /// Compute movement smoothing with [`portable_exp`] instead of the
+    /// platform's `exp`, whose last-bit rounding differs between libm
+    /// implementations (and so between x86 and ARM builds). Costs a few extra
+    /// multiplies per tick and shifts trajectories slightly relative to the
+    /// default mode, so seeds recorded in one mode don't replay in the other.
+    pub deterministic_math: bool,
 }
 
-fn tick_spawn_timers(
-    time: Res<Time>,
-    mut rng: ResMut<SimulationRng>,
-    registry: Res<SpawnRegistry>,
-    mut timers: ResMut<SpawnTimers>,
-    mut commands: Commands,
-    units: Query<(&Unit, &Transform)>,
-) {
-    for (idx, timer) in timers.timers.iter_mut().enumerate() {
-        if timer.tick(time.delta()).just_finished() {
-            if let Some(entry) = registry.entries.get(idx) {
-                let jitter = Vec2::new(rng.gen_f32(-20.0..=20.0), rng.gen_f32(-20.0..=20.0));
-                let start = entry.position + jitter;
-                let rally_target =
-                    average_unit_position(entry.player, &units).unwrap_or(entry.position);
-                spawn_unit(
-                    &mut commands,
-                    entry.player,
-                    start,
-                    rally_target,
-                    PLAYER_COLORS[entry.player.0],
-                );
-            }
+impl BalanceSettings {
+    /// Rank earned by `kills`; always 0 while `veterancy` is off.
+    pub fn veterancy_rank(&self, kills: u32) -> u8 {
+        if !self.veterancy {
+            return 0;
         }
+        self.veterancy_thresholds
+            .iter()
+            .take_while(|&&threshold| kills >= threshold)
+            .count() as u8
     }
-}
 
-fn average_unit_position(player: PlayerId, units: &Query<(&Unit, &Transform)>) -> Option<Vec2> {
-    let mut sum = Vec2::ZERO;
-    let mut count = 0.0;
-    for (unit, transform) in units.iter() {
-        if unit.player == player {
-            sum += transform.translation.truncate();
-            count += 1.0;
-        }
+    pub fn veterancy_damage_multiplier(&self, rank: u8) -> f32 {
+        1.0 + f32::from(rank) * self.veterancy_damage_bonus
     }
-    if count > 0.0 {
-        Some(sum / count)
-    } else {
-        None
+
+    /// Resources per second for a player whose team owns `owned_pylons`
+    /// pylons.
+    pub fn income_rate(&self, owned_pylons: usize) -> f32 {
+        self.income_per_second + owned_pylons as f32 * self.pylon_income_per_second
     }
-}
 
-fn handle_selection_input(
-    buttons: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-    mut selection: ResMut<SelectionState>,
-    mut commands: Commands,
-    mut queries: ParamSet<(
-        Query<(Entity, &Transform, &Unit)>,
-        Query<(&mut Sprite, &mut Transform), With<SelectionRect>>,
-    )>,
-    control: Res<ControlSettings>,
-) {
-    let cursor_world = cursor_world_position(&windows, &cameras);
+    pub fn resistance(&self, target: UnitKind, damage: DamageType) -> f32 {
+        self.resistances
+            .get(&(target, damage))
+            .copied()
+            .unwrap_or(1.0)
+    }
 
-    if buttons.just_pressed(MouseButton::Left) {
-        if let Some(pos) = cursor_world {
-            selection.is_dragging = true;
-            selection.start_world = pos;
-            selection.current_world = pos;
+    pub fn targeting_for(&self, kind: UnitKind) -> TargetingPolicy {
+        self.kind_targeting
+            .get(&kind)
+            .copied()
+            .unwrap_or(self.targeting)
+    }
+}
 
-            let entity = commands
-                .spawn((
-                    Sprite {
-                        color: Color::srgba(0.2, 0.8, 1.0, 0.2),
-                        custom_size: Some(Vec2::splat(2.0)),
-                        ..default()
-                    },
-                    Transform::from_xyz(pos.x, pos.y, 0.5),
-                    SelectionRect,
-                ))
-                .id();
-            selection.rectangle_entity = Some(entity);
+impl Default for BalanceSettings {
+    fn default() -> Self {
+        Self {
+            player_max_health: DEFAULT_PLAYER_MAX_HEALTH,
+            health_decay_per_second: DEFAULT_HEALTH_DECAY_PER_SECOND,
+            targeting: TargetingPolicy::default(),
+            kind_targeting: HashMap::default(),
+            target_lock: false,
+            target_lock_grace_ticks: DEFAULT_TARGET_LOCK_GRACE_TICKS,
+            resistances: HashMap::default(),
+            veterancy: false,
+            veterancy_thresholds: DEFAULT_VETERANCY_THRESHOLDS.to_vec(),
+            veterancy_damage_bonus: DEFAULT_VETERANCY_DAMAGE_BONUS,
+            veterancy_health_bonus: DEFAULT_VETERANCY_HEALTH_BONUS,
+            ooc_regen_per_second: 0.0,
+            ooc_regen_grace_ticks: DEFAULT_OOC_REGEN_GRACE_TICKS,
+            capturable_pylons: false,
+            pylon_max_health: DEFAULT_PYLON_MAX_HEALTH,
+            pylon_capture_per_second: DEFAULT_PYLON_CAPTURE_PER_SECOND,
+            supply_grace_ticks: 0,
+            cooldown_jitter: 0.0,
+            retreat_threshold: 0.0,
+            retreat_resume_threshold: DEFAULT_RETREAT_RESUME_THRESHOLD,
+            economy_enabled: false,
+            income_per_second: DEFAULT_INCOME_PER_SECOND,
+            pylon_income_per_second: DEFAULT_PYLON_INCOME_PER_SECOND,
+            unit_cost: DEFAULT_UNIT_COST,
+            deterministic_math: false,
         }
     }
+}
 
-    if selection.is_dragging && buttons.pressed(MouseButton::Left) {
-        if let Some(pos) = cursor_world {
-            selection.current_world = pos;
-        }
-        if let Some(entity) = selection.rectangle_entity {
-            if let Ok((mut sprite, mut transform)) = queries.p1().get_mut(entity) {
-                let min = Vec2::new(
-                    selection.start_world.x.min(selection.current_world.x),
-                    selection.start_world.y.min(selection.current_world.y),
-                );
-                let max = Vec2::new(
-                    selection.start_world.x.max(selection.current_world.x),
-                    selection.start_world.y.max(selection.current_world.y),
-                );
-                let size = max - min;
-                sprite.custom_size = Some(Vec2::new(size.x.abs().max(2.0), size.y.abs().max(2.0)));
-                transform.translation =
-                    Vec3::new((min.x + max.x) * 0.5, (min.y + max.y) * 0.5, 0.5);
-            }
-        }
+/// `e^x` from IEEE-754 multiplies, divides, and adds only. Each of those is
+/// correctly rounded and Rust never fuses them into FMAs, so the result is
+/// bit-identical on every platform. Halves `x` until it is small, sums a
+/// Taylor series, then squares back up; relative error stays near `f32`
+/// precision for the `|x| < 10` range movement smoothing uses.
+pub fn portable_exp(x: f32) -> f32 {
+    let mut reduced = f64::from(x);
+    let mut halvings = 0;
+    while reduced.abs() > 0.125 && halvings < 64 {
+        reduced *= 0.5;
+        halvings += 1;
+    }
+    let mut term = 1.0f64;
+    let mut sum = 1.0f64;
+    for n in 1..=10 {
+        term = term * reduced / f64::from(n);
+        sum += term;
+    }
+    for _ in 0..halvings {
+        sum *= sum;
     }
+    sum as f32
+}
 
-    if selection.is_dragging && buttons.just_released(MouseButton::Left) {
-        let units = queries.p0();
-        let min = Vec2::new(
-            selection.start_world.x.min(selection.current_world.x),
-            selection.start_world.y.min(selection.current_world.y),
-        );
-        let max = Vec2::new(
-            selection.start_world.x.max(selection.current_world.x),
-            selection.start_world.y.max(selection.current_world.y),
-        );
-        let padded_min = min - Vec2::splat(8.0);
-        let padded_max = max + Vec2::splat(8.0);
-        let mut newly_selected = Vec::new();
-        for (entity, transform, unit) in units.iter() {
-            if unit.player == control.local_player {
-                let pos = transform.translation.truncate();
-                if pos.x >= padded_min.x
-                    && pos.x <= padded_max.x
-                    && pos.y >= padded_min.y
-                    && pos.y <= padded_max.y
-                {
-                    newly_selected.push(entity);
-                }
-            }
-        }
+/// How a unit picks among enemies inside its weapon range.
+///
+/// Every policy falls back to distance and then [`UnitId`], so equal
+/// candidates always resolve the same way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TargetingPolicy {
+    #[default]
+    Nearest,
+    /// Finish off the most damaged enemy.
+    LowestHealth,
+    /// Shoot the enemy dealing the most damage per second.
+    HighestThreat,
+}
 
-        let drag_delta = selection.current_world - selection.start_world;
-        let is_click = drag_delta.length_squared() < 16.0;
-        selection.prev_selected = std::mem::take(&mut selection.selected);
-        if is_click && newly_selected.is_empty() {
-            selection.selected.clear();
-        } else {
-            let mut set: HashSet<Entity> = selection.prev_selected.iter().copied().collect();
-            for entity in newly_selected {
-                set.insert(entity);
-            }
-            selection.selected = set.into_iter().collect();
-        }
-        selection.dirty = true;
-        selection.is_dragging = false;
-        selection.current_world = selection.start_world;
-        if let Some(entity) = selection.rectangle_entity.take() {
-            commands.entity(entity).despawn_recursive();
+impl TargetingPolicy {
+    /// True when `challenger` beats `incumbent` on this policy's own measure,
+    /// before the distance and id tie-breaks. `Nearest` has no measure of its
+    /// own, so under it a locked target is never preempted.
+    pub fn prefers(self, challenger: &TargetCandidate, incumbent: &TargetCandidate) -> bool {
+        match self {
+            TargetingPolicy::Nearest => false,
+            TargetingPolicy::LowestHealth => challenger.health < incumbent.health,
+            TargetingPolicy::HighestThreat => challenger.threat > incumbent.threat,
         }
     }
+}
 
-    {
-        let units = queries.p0();
-        selection
-            .selected
-            .retain(|entity| units.get(*entity).is_ok());
-    }
+/// An enemy considered by [`select_target`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TargetCandidate {
+    pub entity: Entity,
+    /// Breaks ties once policy and distance are equal.
+    pub id: UnitId,
+    pub position: Vec2,
+    pub health: f32,
+    /// Damage per second the candidate currently deals.
+    pub threat: f32,
 }
 
-fn issue_move_orders(
-    buttons: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-    selection: Res<SelectionState>,
-    mut units: Query<&mut Unit>,
-) {
-    if !buttons.just_pressed(MouseButton::Right) {
-        return;
-    }
-    let Some(cursor) = cursor_world_position(&windows, &cameras) else {
-        return;
-    };
-    if selection.selected.is_empty() {
-        return;
+/// Picks the candidate `policy` prefers, ignoring anything beyond `range`.
+pub fn select_target(
+    policy: TargetingPolicy,
+    origin: Vec2,
+    range: f32,
+    candidates: impl IntoIterator<Item = TargetCandidate>,
+) -> Option<TargetCandidate> {
+    let range_sq = range * range;
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.position.distance_squared(origin) <= range_sq)
+        .min_by(|a, b| {
+            let primary = match policy {
+                TargetingPolicy::Nearest => std::cmp::Ordering::Equal,
+                TargetingPolicy::LowestHealth => a.health.total_cmp(&b.health),
+                TargetingPolicy::HighestThreat => b.threat.total_cmp(&a.threat),
+            };
+            primary
+                .then_with(|| {
+                    a.position
+                        .distance_squared(origin)
+                        .total_cmp(&b.position.distance_squared(origin))
+                })
+                .then_with(|| a.id.cmp(&b.id))
+        })
+}
+
+/// Hit points bounded to `0.0..=max`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Health {
+    current: f32,
+    max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
     }
 
-    let offsets = compute_formation_offsets(selection.selected.len());
-    for (entity, offset) in selection.selected.iter().zip(offsets.iter()) {
-        if let Ok(mut unit) = units.get_mut(*entity) {
-            unit.rally_target = cursor + *offset;
-        }
+    pub fn current(&self) -> f32 {
+        self.current
     }
-}
 
-fn compute_formation_offsets(count: usize) -> Vec<Vec2> {
-    let mut offsets = Vec::with_capacity(count);
-    if count == 0 {
-        return offsets;
+    pub fn max(&self) -> f32 {
+        self.max
     }
-    if count >= 1 {
-        offsets.push(Vec2::ZERO);
+
+    pub fn is_depleted(&self) -> bool {
+        self.current <= 0.0
     }
-    let mut generated = offsets.len();
-    let mut ring = 1;
-    while generated < count {
-        let slots = (ring * 6) as usize;
-        for idx in 0..slots {
-            if generated == count {
-                break;
-            }
-            let angle = idx as f32 / slots as f32 * TAU;
-            offsets.push(Vec2::from_angle(angle) * (ring as f32 * FORMATION_SPACING));
-            generated += 1;
-        }
-        ring += 1;
+
+    /// Removes `amount`, never dropping below zero.
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
     }
-    offsets
 }
 
-fn cursor_world_position(
-    windows: &Query<&Window, With<PrimaryWindow>>,
-    cameras: &Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-) -> Option<Vec2> {
-    let window = windows.get_single().ok()?;
-    let cursor_pos = window.cursor_position()?;
-    let (camera, camera_transform) = cameras.get_single().ok()?;
-    let ray = camera
-        .viewport_to_world(camera_transform, cursor_pos)
-        .ok()?;
-    Some(ray.origin.truncate())
+/// Player-wide health pool used by the deterministic health regression.
+///
+/// Decay model: every simulation tick removes
+/// `health_decay_per_second * SimulationParams::fixed_delta`, clamped at zero.
+/// The step uses the configured fixed delta rather than `Time`, so after `n`
+/// ticks `current == max - n * rate * fixed_delta` exactly. The seed plays no
+/// part in decay.
+#[derive(Resource, Clone, Debug)]
+pub struct PlayerHealth {
+    pub value: Health,
 }
 
-fn update_selection_visuals(
-    mut commands: Commands,
-    mut selection: ResMut<SelectionState>,
-    units: Query<&Unit>,
-    highlights: Query<&SelectionHighlight>,
-) {
-    if !selection.dirty {
-        return;
-    }
-
-    for entity in selection.prev_selected.drain(..) {
-        if let Ok(highlight) = highlights.get(entity) {
-            commands.entity(highlight.glow).despawn_recursive();
-            commands.entity(entity).remove::<SelectionHighlight>();
+impl FromWorld for PlayerHealth {
+    fn from_world(world: &mut World) -> Self {
+        let max = world
+            .get_resource::<BalanceSettings>()
+            .cloned()
+            .unwrap_or_default()
+            .player_max_health;
+        Self {
+            value: Health::new(max),
         }
     }
+}
 
-    for &entity in &selection.selected {
-        if highlights.get(entity).is_ok() {
-            continue;
-        }
-        if units.get(entity).is_err() {
-            continue;
-        }
-        let glow = commands
-            .spawn((
-                Sprite {
-                    color: Color::srgba(1.0, 0.95, 0.2, 0.35),
-                    custom_size: Some(Vec2::new(42.0, 56.0)),
-                    ..default()
-                },
-                Transform::from_xyz(0.0, 0.0, -0.1),
-            ))
-            .id();
-        commands.entity(entity).add_child(glow);
-        commands.entity(entity).insert(SelectionHighlight { glow });
-    }
+/// Number of `SimulationSchedule` ticks the simulation has run.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulationTick(pub u64);
 
-    selection.dirty = false;
+/// Result of the match. A player is eliminated once every unit they fielded
+/// is dead; one with a spawn who hasn't fielded a unit yet is still waiting
+/// for reinforcements, unless none are due ([`BoardSettings::reinforcements`]
+/// off, or an economy that can never pay for one). Once decided the outcome
+/// no longer changes, even if reinforcements later respawn for an eliminated
+/// player.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "result", content = "winner", rename_all = "snake_case")]
+pub enum GameOutcome {
+    #[default]
+    InProgress,
+    Victory(PlayerId),
+    /// Every surviving player belongs to this team.
+    TeamVictory(TeamId),
+    /// Nobody survived, or [`BoardSettings::max_match_ticks`] ran out first.
+    Draw,
 }
 
-fn update_boost_visual(entity: Entity, unit: &mut Unit, active: bool, commands: &mut Commands) {
-    if active {
-        if unit.boost_visual.is_some() {
-            return;
-        }
-        let glow = commands
-            .spawn((
-                Sprite {
-                    color: Color::srgba(0.98, 0.95, 0.45, 0.2),
-                    custom_size: Some(Vec2::splat(40.0)),
-                    ..default()
-                },
-                Transform::from_xyz(0.0, 0.0, -0.05),
-            ))
-            .id();
-        commands.entity(entity).add_child(glow);
-        unit.boost_visual = Some(glow);
-    } else if let Some(glow) = unit.boost_visual.take() {
-        if let Some(cmds) = commands.get_entity(glow) {
-            cmds.despawn_recursive();
-        }
-    }
+#[derive(Resource, Default, Debug)]
+struct SpawnRegistry {
+    entries: Vec<SpawnEntry>,
 }
 
-fn move_units(time: Res<Time>, mut units: Query<(&mut Transform, &mut Unit)>) {
-    let dt = time.delta_secs();
-    let accel = 1.0 - (-UNIT_ACCELERATION * dt).exp();
-    for (mut transform, mut unit) in units.iter_mut() {
-        let pos = transform.translation.truncate();
-        let delta = unit.rally_target - pos;
-        let desired = if delta.length_squared() > 1.0 {
-            delta.normalize() * UNIT_SPEED
-        } else {
-            Vec2::ZERO
-        };
-        unit.velocity = unit.velocity.lerp(desired, accel);
-        transform.translation.x += unit.velocity.x * dt;
-        transform.translation.y += unit.velocity.y * dt;
-    }
+#[derive(Debug, Clone)]
+struct SpawnEntry {
+    player: PlayerId,
+    position: Vec2,
 }
 
-fn update_unit_rally_targets(mut units: Query<(Entity, &mut Unit, &Transform)>) {
-    let mut positions = Vec::new();
-    for (entity, unit, transform) in units.iter() {
-        positions.push((entity, unit.player, transform.translation.truncate()));
-    }
+/// The drag fields are only driven by mouse input, which needs `render`.
+#[derive(Resource, Default)]
+#[cfg_attr(not(feature = "render"), allow(dead_code))]
+struct SelectionState {
+    is_dragging: bool,
+    start_world: Vec2,
+    current_world: Vec2,
+    rectangle_entity: Option<Entity>,
+    selected: Vec<Entity>,
+    prev_selected: Vec<Entity>,
+    dirty: bool,
+}
 
-    for (entity, mut unit, transform) in units.iter_mut() {
-        let mut push = Vec2::ZERO;
-        for (other_entity, other_player, other_pos) in positions.iter() {
-            if entity == *other_entity || unit.player != *other_player {
-                continue;
-            }
-            let offset = transform.translation.truncate() - *other_pos;
-            let distance = offset.length();
-            if distance > 0.1 && distance < UNIT_SEPARATION_RADIUS {
-                push += offset.normalize() * (UNIT_SEPARATION_RADIUS - distance)
-                    / UNIT_SEPARATION_RADIUS;
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct PlayerId(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct TeamId(pub usize);
+
+/// Alliance table. Allied players never target each other and share support
+/// links and supply networks. Unlisted players are alone on `TeamId(player)`.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Teams {
+    assignments: HashMap<PlayerId, TeamId>,
+}
+
+impl Teams {
+    /// Builds teams from groups of player indices; each group is named after
+    /// its lowest member.
+    pub fn from_alliances(alliances: &[Vec<usize>]) -> Self {
+        let mut teams = Self::default();
+        for group in alliances {
+            if let Some(&leader) = group.iter().min() {
+                for &player in group {
+                    teams.assign(PlayerId(player), TeamId(leader));
+                }
             }
         }
-        if push.length_squared() > 0.0 {
-            let push_dir = push.normalize_or_zero();
-            unit.rally_target += push_dir * 5.0;
-            unit.velocity += push_dir * SEPARATION_FORCE;
-            unit.velocity = unit.velocity.clamp_length_max(UNIT_SPEED * 1.5);
-        }
+        teams
+    }
+
+    pub fn assign(&mut self, player: PlayerId, team: TeamId) {
+        self.assignments.insert(player, team);
+    }
+
+    pub fn team_of(&self, player: PlayerId) -> TeamId {
+        self.assignments
+            .get(&player)
+            .copied()
+            .unwrap_or(TeamId(player.0))
+    }
+
+    pub fn allied(&self, a: PlayerId, b: PlayerId) -> bool {
+        self.team_of(a) == self.team_of(b)
     }
 }
 
-fn unit_combat_system(
-    time: Res<Time>,
-    spawn_registry: Res<SpawnRegistry>,
-    pylons: Query<&Transform, (With<Pylon>, Without<Unit>)>,
-    mut link_buffer: ResMut<SupportLinkBuffer>,
-    mut commands: Commands,
-    mut unit_queries: ParamSet<(
-        Query<(Entity, &Transform, &Unit)>,
-        Query<(Entity, &mut Transform, &mut Sprite, &mut Unit)>,
-    )>,
-) {
-    link_buffer.links.clear();
+/// Stable unit identity: assigned in spawn order from [`NextUnitId`], so
+/// unlike [`Entity`] ids it never depends on how the ECS recycles slots.
+/// Combat logs and tie-breaks use it.
+#[derive(
+    Component,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+pub struct UnitId(pub u64);
 
-    let snapshot: Vec<_> = {
-        let query = unit_queries.p0();
-        query
-            .iter()
-            .map(|(entity, transform, unit)| {
-                (entity, unit.player, transform.translation.truncate())
-            })
-            .collect()
-    };
+/// Counter behind [`UnitId`]; [`GameplayPlugin`] resets it on build.
+#[derive(Resource, Debug, Default)]
+pub struct NextUnitId(u64);
 
-    let mut entity_info: HashMap<Entity, (PlayerId, Vec2)> = HashMap::default();
-    for (entity, player, pos) in &snapshot {
-        entity_info.insert(*entity, (*player, *pos));
+impl NextUnitId {
+    pub fn allocate(&mut self) -> UnitId {
+        let id = UnitId(self.0);
+        self.0 += 1;
+        id
     }
+}
 
-    let mut adjacency: HashMap<Entity, Vec<Entity>> = HashMap::default();
-    let mut connections: HashMap<Entity, usize> = HashMap::default();
-    let mut support_links: Vec<(Entity, Entity)> = Vec::new();
+#[derive(Component)]
+pub struct Unit {
+    pub player: PlayerId,
+    /// Current destination.
+    pub rally_target: Vec2,
+    /// Destinations queued after `rally_target`, visited in order.
+    pub waypoints: VecDeque<Vec2>,
+    pub kind: UnitKind,
+    pub health: f32,
+    pub max_health: f32,
+    pub attack_timer: Timer,
+    /// Multiplier on the kind's attack cooldown, fixed at spawn; see
+    /// [`BalanceSettings::cooldown_jitter`].
+    pub cooldown_scale: f32,
+    pub velocity: Vec2,
+    pub base_color: Color,
+    pub boost_visual: Option<Entity>,
+    /// Kills credited to this unit; drives `rank`.
+    pub kills: u32,
+    /// Veterancy rank from [`BalanceSettings::veterancy_rank`].
+    pub rank: u8,
+    /// [`SimulationTick`] at which this unit last dealt or took damage.
+    pub last_combat_tick: u64,
+    /// [`SimulationTick`] at which this unit last took damage.
+    pub last_hit_tick: Option<u64>,
+    /// Orders set aside while the unit retreats; `Some` exactly while
+    /// retreating. See [`BalanceSettings::retreat_threshold`].
+    pub suspended_order: Option<SuspendedOrder>,
+    /// Holding position after [`Order::Hold`]: separation doesn't push it
+    /// around. Cleared by the next move order.
+    pub holding: bool,
+    /// Last [`SimulationTick`] this unit counts as supplied once its network
+    /// disconnects; see [`BalanceSettings::supply_grace_ticks`].
+    pub supplied_until_tick: Option<u64>,
+    /// Supply damage multiplier from the last tick it was connected, kept
+    /// through the grace period.
+    pub supply_multiplier: f32,
+    /// Radians counterclockwise from +x; spawns facing up (+y), the way the
+    /// sprite is drawn. Turns toward `aim`, else the velocity, at a capped
+    /// rate. Only the sprite's rotation reads it.
+    pub facing: f32,
+    /// Position of the enemy targeted in the last combat step; `None` when
+    /// it had no target. Only a locked target can be out of range.
+    pub aim: Option<Vec2>,
+    /// Enemy targeted in the last combat step; kept across steps under
+    /// [`BalanceSettings::target_lock`].
+    pub current_target: Option<UnitId>,
+    /// [`SimulationTick`] at which the locked target went out of range, while
+    /// it stays out.
+    pub target_out_of_range_since: Option<u64>,
+}
 
-    let cell_size = LASER_HEAL_RANGE;
-    let mut spatial: HashMap<IVec2, Vec<usize>> = HashMap::default();
-    for (idx, (_, _, pos)) in snapshot.iter().enumerate() {
-        spatial
-            .entry(spatial_cell(*pos, cell_size))
-            .or_default()
-            .push(idx);
+impl Unit {
+    /// Seconds between this unit's shots.
+    pub fn attack_cooldown(&self) -> f32 {
+        self.kind.attack_cooldown() * self.cooldown_scale
     }
 
-    let mut neighbor_offsets = Vec::with_capacity(9);
-    for dy in -1..=1 {
-        for dx in -1..=1 {
-            neighbor_offsets.push(IVec2::new(dx, dy));
-        }
+    /// True once `balance.ooc_regen_grace_ticks` have passed without this
+    /// unit dealing or taking damage.
+    pub fn out_of_combat(&self, tick: u64, balance: &BalanceSettings) -> bool {
+        tick.saturating_sub(self.last_combat_tick) >= balance.ooc_regen_grace_ticks
     }
 
-    for (i, &(entity_a, player_a, pos_a)) in snapshot.iter().enumerate() {
-        let cell = spatial_cell(pos_a, cell_size);
-        for offset in &neighbor_offsets {
-            if let Some(indices) = spatial.get(&(cell + *offset)) {
-                for &j in indices {
-                    if j <= i {
-                        continue;
-                    }
-                    let (entity_b, player_b, pos_b) = snapshot[j];
-                    if player_a != player_b {
-                        continue;
-                    }
-                    let delta = pos_a - pos_b;
-                    if delta.length_squared() <= LASER_HEAL_RANGE * LASER_HEAL_RANGE {
-                        connections
-                            .entry(entity_a)
-                            .and_modify(|c| *c += 1)
-                            .or_insert(1);
-                        connections
-                            .entry(entity_b)
-                            .and_modify(|c| *c += 1)
-                            .or_insert(1);
-                        adjacency.entry(entity_a).or_default().push(entity_b);
-                        adjacency.entry(entity_b).or_default().push(entity_a);
-                        support_links.push((entity_a, entity_b));
-                    }
-                }
-            }
+    /// Replaces the current destination and any queued waypoints. A
+    /// retreating unit takes the order once it has healed.
+    pub fn order_move(&mut self, target: Vec2) {
+        self.holding = false;
+        if let Some(order) = &mut self.suspended_order {
+            order.waypoints.clear();
+            order.rally_target = target;
+        } else {
+            self.waypoints.clear();
+            self.rally_target = target;
         }
     }
 
-    let mut connected_entities: HashSet<Entity> = HashSet::default();
-    let mut supply_components: Vec<Vec<Entity>> = Vec::new();
-    for entry in spawn_registry.entries.iter() {
-        let mut queue = VecDeque::new();
-        let mut component = Vec::new();
-        for (entity, _player, pos) in snapshot
-            .iter()
-            .filter(|(_, player, _)| *player == entry.player)
-        {
-            if pos.distance(entry.position) <= LASER_HEAL_RANGE {
-                if connected_entities.insert(*entity) {
-                    queue.push_back(*entity);
-                    component.push(*entity);
-                }
-            }
-        }
-        while let Some(current) = queue.pop_front() {
-            if let Some(neighbors) = adjacency.get(&current) {
-                for &neighbor in neighbors {
-                    if entity_info
-                        .get(&neighbor)
-                        .map(|(player, _)| *player == entry.player)
-                        .unwrap_or(false)
-                    {
-                        if connected_entities.insert(neighbor) {
-                            queue.push_back(neighbor);
-                            component.push(neighbor);
-                        }
-                    }
-                }
-            }
-        }
-        if !component.is_empty() {
-            supply_components.push(component);
+    /// Appends `target` after the current destination and queued waypoints.
+    pub fn queue_move(&mut self, target: Vec2) {
+        self.holding = false;
+        if let Some(order) = &mut self.suspended_order {
+            order.waypoints.push_back(target);
+        } else {
+            self.waypoints.push_back(target);
         }
     }
 
-    let pylon_positions: Vec<Vec2> = pylons
-        .iter()
-        .map(|transform| transform.translation.truncate())
-        .collect();
+    /// Drops the destination and queued waypoints, settling at `position`.
+    pub fn stop(&mut self, position: Vec2) {
+        self.order_move(position);
+    }
 
-    let mut component_bonus: HashMap<Entity, f32> = HashMap::default();
-    let mut component_pylon_active: HashSet<Entity> = HashSet::default();
-    for component in supply_components {
-        let mut bonus = 0.0;
-        let mut component_powered_pairs = Vec::new();
-        for entity in &component {
-            if let Some((_, pos)) = entity_info.get(entity) {
-                for pylon_pos in &pylon_positions {
-                    if pos.distance(*pylon_pos) <= PYLON_RADIUS {
-                        bonus += PYLON_DAMAGE_BONUS;
-                        component_powered_pairs.push((*pylon_pos, *pos));
-                        break;
-                    }
-                }
-            }
-        }
-        if !component_powered_pairs.is_empty() {
-            for (pylon_pos, unit_pos) in component_powered_pairs {
-                emit_support_link(
-                    &mut link_buffer.links,
-                    pylon_pos,
-                    unit_pos,
-                    Color::srgb(0.2, 0.7, 1.0),
-                );
-            }
-            for entity in component.iter().copied() {
-                component_pylon_active.insert(entity);
-            }
-        }
-        for entity in component.iter().copied() {
-            component_bonus.insert(entity, bonus);
-        }
+    /// [`stop`](Self::stop), then stay put until the next move order.
+    pub fn hold(&mut self, position: Vec2) {
+        self.stop(position);
+        self.holding = true;
     }
 
-    let delta = time.delta();
-    let delta_secs = delta.as_secs_f32();
-    let mut damage_events: Vec<(Entity, f32)> = Vec::new();
-    let mut deaths: Vec<Entity> = Vec::new();
-    let mut beams: Vec<(Vec2, Vec2, Color, f32)> = Vec::new();
+    pub fn is_retreating(&self) -> bool {
+        self.suspended_order.is_some()
+    }
+}
 
-    let mut unit_write = unit_queries.p1();
-    for (entity, mut transform, mut sprite, mut unit) in unit_write.iter_mut() {
-        unit.attack_timer.tick(delta);
-        let connection_count = connections.get(&entity).copied().unwrap_or(0);
-        let boost_active = connected_entities.contains(&entity);
-        let pylon_bonus = component_bonus.get(&entity).copied().unwrap_or(0.0);
-        update_boost_visual(entity, &mut unit, boost_active, &mut commands);
-        let scale = if boost_active { 1.12 } else { 1.0 };
-        transform.scale = Vec3::new(scale, scale, 1.0);
-        sprite.color = unit.base_color;
+/// Destination and waypoints a retreating unit returns to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SuspendedOrder {
+    pub rally_target: Vec2,
+    pub waypoints: VecDeque<Vec2>,
+}
 
-        if boost_active && connection_count > 0 && unit.health < unit.max_health {
-            let heal_amount = connection_count as f32 * SUPPORT_HEAL_PER_SECOND * delta_secs;
-            unit.health = (unit.health + heal_amount).min(unit.max_health);
-        }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitKind {
+    Laser,
+    /// Sturdier and slower-firing than a Laser, with kinetic hits.
+    Cannon,
+}
 
-        // Attack
-        if let Some((target_entity, target_pos)) = snapshot
-            .iter()
-            .filter(|(_, player, _)| *player != unit.player)
-            .min_by(|(_, _, a), (_, _, b)| {
-                a.distance_squared(transform.translation.truncate())
-                    .partial_cmp(&b.distance_squared(transform.translation.truncate()))
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            })
-            .map(|(entity, _, pos)| (*entity, *pos))
-        {
-            let distance = target_pos.distance(transform.translation.truncate());
-            if distance <= LASER_RANGE && unit.attack_timer.finished() {
-                let mut damage_multiplier = 1.0;
-                if boost_active {
-                    damage_multiplier += connection_count as f32 * SUPPORT_DAMAGE_BONUS;
-                    damage_multiplier += pylon_bonus;
-                }
-                damage_events.push((target_entity, LASER_DAMAGE * damage_multiplier));
-                beams.push((
-                    transform.translation.truncate(),
-                    target_pos,
-                    Color::srgb(1.0, 0.2, 0.2),
-                    4.0,
-                ));
-                let cooldown = unit.kind.attack_cooldown();
-                unit.attack_timer
-                    .set_duration(std::time::Duration::from_secs_f32(cooldown));
-                unit.attack_timer.reset();
-            }
+/// What a unit's attack deals, matched against [`BalanceSettings::resistances`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageType {
+    Kinetic,
+    Energy,
+}
+
+impl UnitKind {
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            UnitKind::Laser => DamageType::Energy,
+            UnitKind::Cannon => DamageType::Kinetic,
         }
     }
 
-    for (entity, amount) in damage_events {
-        if let Ok((_, _, _, mut unit)) = unit_write.get_mut(entity) {
-            unit.health -= amount;
-            if unit.health <= 0.0 {
-                deaths.push(entity);
-            }
+    fn health(&self) -> f32 {
+        match self {
+            UnitKind::Laser => 45.0,
+            UnitKind::Cannon => 70.0,
         }
     }
 
-    for (entity_a, entity_b) in support_links {
-        let Some((_, pos_a)) = entity_info.get(&entity_a) else {
-            continue;
-        };
-        let Some((_, pos_b)) = entity_info.get(&entity_b) else {
-            continue;
-        };
-        let pylon_active = component_pylon_active.contains(&entity_a)
-            || component_pylon_active.contains(&entity_b);
-        let color = support_link_color(pylon_active);
-        emit_support_link(&mut link_buffer.links, *pos_a, *pos_b, color);
+    fn attack_cooldown(&self) -> f32 {
+        match self {
+            UnitKind::Laser => LASER_COOLDOWN,
+            UnitKind::Cannon => CANNON_COOLDOWN,
+        }
     }
 
-    for entity in deaths {
-        commands.entity(entity).despawn_recursive();
+    fn damage_per_second(&self) -> f32 {
+        match self {
+            UnitKind::Laser => LASER_DAMAGE / LASER_COOLDOWN,
+            UnitKind::Cannon => LASER_DAMAGE / CANNON_COOLDOWN,
+        }
     }
+}
 
-    for (start, end, color, thickness) in beams {
-        spawn_beam(&mut commands, start, end, color, thickness);
+#[cfg(feature = "render")]
+#[derive(Component)]
+struct SelectionRect;
+
+#[cfg(feature = "render")]
+#[derive(Component)]
+struct SelectionHighlight {
+    glow: Entity,
+}
+
+#[derive(Component)]
+struct Pylon {
+    velocity: Vec2,
+    mass: f32,
+    /// The remaining fields only change with
+    /// [`BalanceSettings::capturable_pylons`].
+    health: f32,
+    owner: Option<TeamId>,
+    /// Team capturing this neutral pylon, and its progress toward 1.0.
+    control: Option<(TeamId, f32)>,
+}
+
+impl Pylon {
+    /// Back to neutral at full health, dropping any capture progress.
+    fn neutralize(&mut self, max_health: f32) {
+        self.health = max_health;
+        self.owner = None;
+        self.control = None;
     }
 }
 
-fn spawn_beam(commands: &mut Commands, start: Vec2, end: Vec2, color: Color, thickness: f32) {
-    let diff = end - start;
-    let length = diff.length().max(1.0);
-    let angle = diff.y.atan2(diff.x);
-    let translation = Vec3::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0, 0.6);
-    commands.spawn((
-        Sprite {
-            color,
-            custom_size: Some(Vec2::new(length, thickness)),
-            ..default()
-        },
-        Transform {
-            translation,
-            rotation: Quat::from_rotation_z(angle),
-            ..default()
-        },
-        BeamEffect {
-            timer: Timer::from_seconds(BEAM_LIFETIME, TimerMode::Once),
-        },
-    ));
+/// A pylon as combat saw it at the start of the step.
+struct PylonSite {
+    entity: Entity,
+    position: Vec2,
+    owner: Option<TeamId>,
+    capturer: Option<TeamId>,
 }
 
-fn support_link_color(pylon_active: bool) -> Color {
-    if pylon_active {
-        Color::srgb(0.22, 0.8, 0.95)
-    } else {
-        Color::srgb(0.24, 0.98, 0.55)
+impl PylonSite {
+    fn powers(&self, team: TeamId, capturable: bool) -> bool {
+        !capturable || self.owner == Some(team)
+    }
+
+    /// Owned or being captured by a team other than `team`.
+    fn hostile_to(&self, team: TeamId) -> bool {
+        self.owner
+            .or(self.capturer)
+            .is_some_and(|holder| holder != team)
     }
 }
 
-fn spatial_cell(position: Vec2, cell_size: f32) -> IVec2 {
-    IVec2::new(
-        (position.x / cell_size).floor() as i32,
-        (position.y / cell_size).floor() as i32,
-    )
+fn pylon_color(owner: Option<TeamId>) -> Color {
+    owner.map_or(PYLON_NEUTRAL_COLOR, |team| player_color(PlayerId(team.0)))
 }
 
-fn emit_support_link(
-    buffer: &mut Vec<SupportLinkRenderable>,
+#[derive(Component)]
+pub(crate) struct BeamEffect {
+    pub(crate) timer: Timer,
+}
+
+#[derive(Component)]
+struct SupportLinkVisual;
+
+#[derive(Clone)]
+struct SupportLinkRenderable {
     start: Vec2,
     end: Vec2,
     color: Color,
-) {
-    buffer.push(SupportLinkRenderable {
-        start,
-        end,
-        color: color.with_alpha(0.85),
-        thickness: 2.6,
-    });
-    buffer.push(SupportLinkRenderable {
-        start,
-        end,
-        color: color.with_alpha(0.3),
-        thickness: 4.4,
-    });
+    thickness: f32,
 }
 
-fn render_support_links(
-    mut commands: Commands,
-    buffer: Res<SupportLinkBuffer>,
-    mut pool: ResMut<SupportLinkPool>,
-    mut visuals: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<SupportLinkVisual>>,
-) {
-    for (idx, link) in buffer.links.iter().enumerate() {
-        if idx >= pool.entities.len() {
-            let entity = spawn_link_visual(&mut commands, link);
-            pool.entities.push(entity);
-            continue;
+#[derive(Resource, Default)]
+pub(crate) struct SupportLinkBuffer {
+    links: Vec<SupportLinkRenderable>,
+    active_links: usize,
+}
+
+impl SupportLinkBuffer {
+    /// Unit-to-unit support links formed during the last combat step.
+    pub(crate) fn active_links(&self) -> usize {
+        self.active_links
+    }
+}
+
+/// Whether combat spawns cosmetic entities (beams, support links, boost
+/// glows). Headless runs turn this off; simulation results are identical
+/// either way.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderEffects(pub bool);
+
+impl Default for RenderEffects {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Whether `Update` draws unit sprites between their last two simulation
+/// positions, so 30 Hz movement looks smooth at higher frame rates, or at the
+/// latest one. Either way the sprite's `Transform` only follows
+/// [`SimulationPosition`]; headless runs turn this off.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderInterpolation(pub bool);
+
+impl Default for RenderInterpolation {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// A unit's authoritative position. Simulation systems read and move
+/// `current`; `previous` is where the unit stood when the tick began. The
+/// unit's `Transform` is derived from these in `Update` and never read back,
+/// so to move a unit, set `current` (or replace the component with
+/// [`SimulationPosition::new`] to skip the interpolated slide).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SimulationPosition {
+    pub previous: Vec2,
+    pub current: Vec2,
+}
+
+impl SimulationPosition {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            previous: position,
+            current: position,
         }
+    }
 
-        let entity = pool.entities[idx];
-        if let Ok((mut transform, mut sprite, mut visibility)) = visuals.get_mut(entity) {
-            configure_link_visual(&mut transform, &mut sprite, &mut visibility, link);
-        } else {
-            let entity = spawn_link_visual(&mut commands, link);
-            pool.entities[idx] = entity;
+    /// `alpha` of the way from `previous` to `current`.
+    pub fn interpolated(&self, alpha: f32) -> Vec2 {
+        self.previous.lerp(self.current, alpha.clamp(0.0, 1.0))
+    }
+}
+
+/// Developer overlays drawn with gizmos in `Update`; they never touch the
+/// simulation.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DebugOverlays {
+    /// Laser and heal range rings around the local player's units.
+    pub ranges: bool,
+}
+
+/// Toggles [`DebugOverlays::ranges`].
+pub const RANGE_OVERLAY_KEY: KeyCode = KeyCode::F2;
+#[cfg(feature = "render")]
+const LASER_RANGE_OVERLAY_COLOR: Color = Color::srgba(1.0, 0.45, 0.35, 0.3);
+#[cfg(feature = "render")]
+const HEAL_RANGE_OVERLAY_COLOR: Color = Color::srgba(0.4, 1.0, 0.6, 0.3);
+
+/// Budgets for cosmetic entities.
+#[derive(Resource, Clone, Debug)]
+pub struct RenderSettings {
+    /// Live `BeamEffect` entities allowed at once. New beams are dropped past
+    /// this cap so existing ones finish their fade; damage is unaffected.
+    pub max_beam_effects: usize,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            max_beam_effects: DEFAULT_MAX_BEAM_EFFECTS,
         }
     }
+}
 
-    for idx in buffer.links.len()..pool.entities.len() {
-        if let Ok((_, _, mut visibility)) = visuals.get_mut(pool.entities[idx]) {
-            *visibility = Visibility::Hidden;
+/// Colors and sprite sizes for the board and its pieces. Purely cosmetic;
+/// ranges and collisions don't depend on these.
+#[derive(Resource, Clone, Debug)]
+pub struct ThemeSettings {
+    /// Window clear color around the board.
+    pub background: Color,
+    pub board_color: Color,
+    pub unit_size: Vec2,
+    pub pylon_size: Vec2,
+    pub beam_thickness: f32,
+}
+
+impl Default for ThemeSettings {
+    fn default() -> Self {
+        Self {
+            background: Color::srgb_u8(8, 10, 24),
+            board_color: Color::srgb(0.09, 0.12, 0.2),
+            unit_size: Vec2::new(24.0, 32.0),
+            pylon_size: Vec2::new(26.0, 38.0),
+            beam_thickness: 4.0,
         }
     }
 }
 
-#[allow(deprecated)]
-fn spawn_link_visual(commands: &mut Commands, link: &SupportLinkRenderable) -> Entity {
-    let diff = link.end - link.start;
-    let length = diff.length().max(1.0);
-    let midpoint = (link.start + link.end) * 0.5;
-    let angle = diff.y.atan2(diff.x);
-    commands
-        .spawn((
-            SpriteBundle {
-                sprite: Sprite {
-                    color: link.color,
-                    custom_size: Some(Vec2::new(length, link.thickness)),
-                    ..default()
-                },
-                transform: Transform {
-                    translation: Vec3::new(midpoint.x, midpoint.y, 0.6),
-                    rotation: Quat::from_rotation_z(angle),
-                    ..default()
+/// Supply status from the last combat step. A unit is supplied when its
+/// support-link network reaches an allied spawn point; supplied units grow,
+/// heal, and deal bonus damage, including the pylon bonus of their network.
+#[derive(Resource, Default, Debug)]
+pub struct SupplyState {
+    supplied: HashSet<Entity>,
+    pylon_bonus: HashMap<Entity, f32>,
+}
+
+impl SupplyState {
+    pub fn is_supplied(&self, entity: Entity) -> bool {
+        self.supplied.contains(&entity)
+    }
+
+    /// Damage multiplier contributed by pylons; `0.0` for unsupplied units.
+    pub fn pylon_bonus(&self, entity: Entity) -> f32 {
+        self.pylon_bonus.get(&entity).copied().unwrap_or(0.0)
+    }
+
+    pub fn supplied_count(&self) -> usize {
+        self.supplied.len()
+    }
+}
+
+/// The supply network as a GraphViz DOT graph, for debugging why a unit is
+/// or isn't supplied. Render with `neato -n -Tsvg`: nodes are pinned to their
+/// world positions.
+///
+/// Units are named and labeled by [`UnitId`] and filled with their player's
+/// color, boxes when supplied and circles when not; solid edges are support
+/// links. Spawn anchors are double octagons with dashed edges to the units
+/// they seed (any on the spawn's team), and pylons are diamonds numbered in
+/// spawn order. Links and seeds are recomputed from current positions, so a
+/// snapshot taken between ticks matches the last combat step.
+pub fn export_supply_dot(world: &World) -> String {
+    use std::fmt::Write;
+
+    let hex = |color: Color| color.to_srgba().to_hex();
+    let mut snapshot: Vec<(Entity, UnitId, PlayerId, Vec2)> = Vec::new();
+    let mut pylons: Vec<(Entity, Vec2, Option<TeamId>)> = Vec::new();
+    for entity in world.iter_entities() {
+        if let (Some(unit), Some(position)) =
+            (entity.get::<Unit>(), entity.get::<SimulationPosition>())
+        {
+            let id = entity.get::<UnitId>().copied().unwrap_or_default();
+            snapshot.push((entity.id(), id, unit.player, position.current));
+        } else if let (Some(pylon), Some(position)) =
+            (entity.get::<Pylon>(), entity.get::<SimulationPosition>())
+        {
+            pylons.push((entity.id(), position.current, pylon.owner));
+        }
+    }
+    snapshot.sort_by_key(|(_, id, _, _)| *id);
+    pylons.sort_by_key(|(entity, _, _)| *entity);
+
+    let default_teams = Teams::default();
+    let teams = world.get_resource::<Teams>().unwrap_or(&default_teams);
+    let spawns = world
+        .get_resource::<SpawnRegistry>()
+        .map_or(&[][..], |registry| registry.entries.as_slice());
+    let supply = world.get_resource::<SupplyState>();
+
+    let mut dot = String::from("graph supply {\n    node [style=filled, fontsize=8];\n");
+    for (idx, spawn) in spawns.iter().enumerate() {
+        let _ = writeln!(
+            dot,
+            "    spawn{idx} [label=\"spawn {}\", shape=doubleoctagon, fillcolor=\"{}\", pos=\"{},{}!\"];",
+            spawn.player.0,
+            hex(player_color(spawn.player)),
+            spawn.position.x,
+            spawn.position.y,
+        );
+    }
+    for (idx, (_, position, owner)) in pylons.iter().enumerate() {
+        let _ = writeln!(
+            dot,
+            "    p{idx} [label=\"pylon\", shape=diamond, fillcolor=\"{}\", pos=\"{},{}!\"];",
+            hex(pylon_color(*owner)),
+            position.x,
+            position.y,
+        );
+    }
+    for (entity, id, player, position) in &snapshot {
+        let supplied = supply.is_some_and(|supply| supply.is_supplied(*entity));
+        let _ = writeln!(
+            dot,
+            "    u{} [label=\"{}\", shape={}, fillcolor=\"{}\", pos=\"{},{}!\"];",
+            id.0,
+            id.0,
+            if supplied { "box" } else { "circle" },
+            hex(player_color(*player)),
+            position.x,
+            position.y,
+        );
+    }
+    for (idx, spawn) in spawns.iter().enumerate() {
+        for (_, id, player, position) in &snapshot {
+            if teams.allied(*player, spawn.player)
+                && position.distance(spawn.position) <= LASER_HEAL_RANGE
+            {
+                let _ = writeln!(dot, "    spawn{idx} -- u{} [style=dashed];", id.0);
+            }
+        }
+    }
+    let ids: HashMap<Entity, UnitId> = snapshot
+        .iter()
+        .map(|&(entity, id, _, _)| (entity, id))
+        .collect();
+    for (a, b) in build_support_links(&snapshot, teams, LASER_HEAL_RANGE) {
+        let _ = writeln!(dot, "    u{} -- u{};", ids[&a].0, ids[&b].0);
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Lossy, read-only view of the battlefield for visualizers, prompts, and
+/// tests; see [`export_world_state`]. Positions are `[x, y]` in world units.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WorldState {
+    pub tick: u64,
+    pub outcome: GameOutcome,
+    /// Ordered by id.
+    pub units: Vec<UnitState>,
+    /// In spawn order.
+    pub pylons: Vec<PylonState>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct UnitState {
+    pub id: UnitId,
+    pub player: PlayerId,
+    pub kind: UnitKind,
+    pub position: [f32; 2],
+    pub health: f32,
+    pub max_health: f32,
+    /// As of the last combat step; see [`SupplyState`].
+    pub supplied: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PylonState {
+    pub position: [f32; 2],
+    /// Capturing team; always `None` without
+    /// [`BalanceSettings::capturable_pylons`].
+    pub owner: Option<TeamId>,
+    pub health: f32,
+}
+
+/// Snapshot of tick, outcome, units, and pylons. Missing resources read as
+/// their defaults, so it works on any world, headless or not.
+pub fn export_world_state(world: &World) -> WorldState {
+    let supply = world.get_resource::<SupplyState>();
+    let mut units = Vec::new();
+    let mut pylons: Vec<(Entity, PylonState)> = Vec::new();
+    for entity in world.iter_entities() {
+        if let (Some(unit), Some(id), Some(position)) = (
+            entity.get::<Unit>(),
+            entity.get::<UnitId>(),
+            entity.get::<SimulationPosition>(),
+        ) {
+            units.push(UnitState {
+                id: *id,
+                player: unit.player,
+                kind: unit.kind,
+                position: position.current.to_array(),
+                health: unit.health,
+                max_health: unit.max_health,
+                supplied: supply.is_some_and(|supply| supply.is_supplied(entity.id())),
+            });
+        } else if let (Some(pylon), Some(position)) =
+            (entity.get::<Pylon>(), entity.get::<SimulationPosition>())
+        {
+            pylons.push((
+                entity.id(),
+                PylonState {
+                    position: position.current.to_array(),
+                    owner: pylon.owner,
+                    health: pylon.health,
                 },
+            ));
+        }
+    }
+    units.sort_by_key(|unit| unit.id);
+    pylons.sort_by_key(|(entity, _)| *entity);
+
+    WorldState {
+        tick: world
+            .get_resource::<SimulationTick>()
+            .map_or(0, |tick| tick.0),
+        outcome: world
+            .get_resource::<GameOutcome>()
+            .copied()
+            .unwrap_or_default(),
+        units,
+        pylons: pylons.into_iter().map(|(_, pylon)| pylon).collect(),
+    }
+}
+
+/// Why one unit is doing what it's doing, assembled from the same inputs
+/// `move_units` and the combat system read; see [`diagnose_unit`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct UnitDiagnosis {
+    pub id: UnitId,
+    pub player: PlayerId,
+    pub position: [f32; 2],
+    pub activity: UnitActivity,
+    pub order: UnitOrder,
+    /// Heading back to spawn with `order` set aside until it heals; see
+    /// [`BalanceSettings::retreat_threshold`].
+    pub retreating: bool,
+    /// Holding position after [`Order::Hold`].
+    pub holding: bool,
+    /// See [`Unit::facing`].
+    pub facing: f32,
+    /// See [`Unit::current_target`].
+    pub current_target: Option<UnitId>,
+    /// Closest unit the combat system would consider hostile.
+    pub nearest_enemy: Option<EnemyContact>,
+    /// Seconds until the attack timer next allows a shot.
+    pub cooldown_remaining: f32,
+    /// As of the last combat step; see [`SupplyState`].
+    pub supplied: bool,
+    /// Pylon share of the damage multiplier, as of the last combat step.
+    pub pylon_bonus: f32,
+}
+
+/// What the unit will do on the next simulation tick, in the combat system's
+/// order of precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitActivity {
+    /// An enemy is in range and the attack timer is ready.
+    Engaging,
+    /// An enemy is in range but the attack timer is still running.
+    Reloading,
+    /// No enemy in range; heading for its rally target.
+    Moving,
+    /// No enemy in range and already at its rally target.
+    Idle,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "order", rename_all = "snake_case")]
+pub enum UnitOrder {
+    /// At its rally target, with no waypoints queued. Not to be confused
+    /// with [`Order::Hold`], reported as [`UnitDiagnosis::holding`].
+    AtRally,
+    Move {
+        target: [f32; 2],
+        distance: f32,
+        queued_waypoints: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EnemyContact {
+    pub id: UnitId,
+    pub distance: f32,
+    /// Attack range the distance is compared against.
+    pub range: f32,
+    pub in_range: bool,
+}
+
+/// Diagnosis for the unit with `id`, or `None` if it isn't alive. Like
+/// [`export_world_state`], it only reads the world and treats missing
+/// resources as their defaults.
+pub fn diagnose_unit(world: &World, id: UnitId) -> Option<UnitDiagnosis> {
+    let mut subject = None;
+    let mut others: Vec<(UnitId, PlayerId, Vec2)> = Vec::new();
+    for entity in world.iter_entities() {
+        let (Some(unit), Some(&unit_id), Some(position)) = (
+            entity.get::<Unit>(),
+            entity.get::<UnitId>(),
+            entity.get::<SimulationPosition>(),
+        ) else {
+            continue;
+        };
+        let position = position.current;
+        if unit_id == id {
+            subject = Some((entity.id(), unit, position));
+        } else {
+            others.push((unit_id, unit.player, position));
+        }
+    }
+    let (entity, unit, position) = subject?;
+
+    let teams = world.get_resource::<Teams>().cloned().unwrap_or_default();
+    let nearest_enemy = others
+        .iter()
+        .filter(|(_, player, _)| !teams.allied(*player, unit.player))
+        .map(|&(other, _, other_position)| (other, position.distance(other_position)))
+        .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+        .map(|(other, distance)| EnemyContact {
+            id: other,
+            distance,
+            range: LASER_RANGE,
+            in_range: distance <= LASER_RANGE,
+        });
+
+    let to_target = unit.rally_target - position;
+    // `move_units` stops steering within one world unit of the target.
+    let moving = to_target.length_squared() > 1.0;
+    let order = if moving || !unit.waypoints.is_empty() {
+        UnitOrder::Move {
+            target: unit.rally_target.to_array(),
+            distance: to_target.length(),
+            queued_waypoints: unit.waypoints.len(),
+        }
+    } else {
+        UnitOrder::AtRally
+    };
+    let cooldown_remaining = unit.attack_timer.remaining_secs();
+    let activity = match &nearest_enemy {
+        Some(enemy) if enemy.in_range && unit.attack_timer.finished() => UnitActivity::Engaging,
+        Some(enemy) if enemy.in_range => UnitActivity::Reloading,
+        _ if moving => UnitActivity::Moving,
+        _ => UnitActivity::Idle,
+    };
+
+    let supply = world.get_resource::<SupplyState>();
+    Some(UnitDiagnosis {
+        id,
+        player: unit.player,
+        position: position.to_array(),
+        activity,
+        order,
+        retreating: unit.is_retreating(),
+        holding: unit.holding,
+        facing: unit.facing,
+        current_target: unit.current_target,
+        nearest_enemy,
+        cooldown_remaining,
+        supplied: supply.is_some_and(|supply| supply.is_supplied(entity)),
+        pylon_bonus: supply.map_or(0.0, |supply| supply.pylon_bonus(entity)),
+    })
+}
+
+#[derive(Resource, Default)]
+struct SupportLinkPool {
+    entities: Vec<Entity>,
+}
+
+#[cfg(feature = "render")]
+type UnitReadQuery<'w, 's> = Query<'w, 's, (Entity, &'static Transform, &'static Unit)>;
+type CombatReadQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static SimulationPosition,
+        &'static Unit,
+        &'static UnitId,
+    ),
+>;
+type UnitWriteQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static SimulationPosition,
+        &'static mut Transform,
+        &'static mut Sprite,
+        &'static mut Unit,
+    ),
+>;
+#[cfg(feature = "render")]
+type SelectionRectQuery<'w, 's> =
+    Query<'w, 's, (&'static mut Sprite, &'static mut Transform), With<SelectionRect>>;
+/// Color for `player`: the curated palette first, then hues stepped by the
+/// golden ratio so each new id lands far from the ones before it. Depends only
+/// on the id, so every run and seed colors a player the same way.
+pub fn player_color(player: PlayerId) -> Color {
+    if let Some(&color) = CURATED_PLAYER_COLORS.get(player.0) {
+        return color;
+    }
+    const GOLDEN_RATIO_CONJUGATE: f32 = 0.618_034;
+    let step = player.0 - CURATED_PLAYER_COLORS.len();
+    let hue = (0.975 + step as f32 * GOLDEN_RATIO_CONJUGATE).fract() * 360.0;
+    // Full saturation sets them apart from the softer curated colors, and
+    // alternating brightness separates ids whose hues land close together.
+    let value = if step % 2 == 0 { 1.0 } else { 0.6 };
+    Color::hsv(hue, 1.0, value)
+}
+
+fn setup_board(
+    mut commands: Commands,
+    mut settings: ResMut<BoardSettings>,
+    theme: Option<Res<ThemeSettings>>,
+) {
+    let sanitized = settings.clone().sanitized();
+    if sanitized.player_count != settings.player_count {
+        warn!(
+            target: "gameplay",
+            "player_count {} outside {MIN_PLAYERS}..={MAX_PLAYERS}; clamping",
+            settings.player_count
+        );
+        *settings = sanitized;
+    }
+    let board_color = theme.map_or(ThemeSettings::default().board_color, |theme| {
+        theme.board_color
+    });
+    commands.spawn((
+        Sprite {
+            color: board_color,
+            custom_size: Some(Vec2::splat(settings.board_size)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, -0.5),
+    ));
+
+    let mut registry = SpawnRegistry::default();
+    let radius = settings.board_size * 0.35;
+    for idx in 0..settings.player_count {
+        let angle = idx as f32 / settings.player_count as f32 * TAU;
+        let position = Vec2::new(angle.cos() * radius, angle.sin() * radius);
+        let player = PlayerId(idx);
+        registry.entries.push(SpawnEntry { player, position });
+
+        commands.spawn((
+            Sprite {
+                color: player_color(player),
+                custom_size: Some(Vec2::splat(20.0)),
                 ..default()
             },
-            SupportLinkVisual,
-        ))
-        .id()
+            Transform::from_xyz(position.x, position.y, 0.1),
+        ));
+    }
+
+    commands.insert_resource(registry);
 }
 
-fn configure_link_visual(
-    transform: &mut Transform,
-    sprite: &mut Sprite,
-    visibility: &mut Visibility,
-    link: &SupportLinkRenderable,
+fn spawn_initial_units(
+    settings: Res<BoardSettings>,
+    registry: Res<SpawnRegistry>,
+    layout: Option<Res<InitialLayout>>,
+    mut spawner: UnitSpawner,
 ) {
-    let diff = link.end - link.start;
-    let length = diff.length().max(1.0);
-    let midpoint = (link.start + link.end) * 0.5;
-    let angle = diff.y.atan2(diff.x);
-    transform.translation = Vec3::new(midpoint.x, midpoint.y, 0.6);
-    transform.rotation = Quat::from_rotation_z(angle);
-    transform.scale = Vec3::ONE;
-    sprite.custom_size = Some(Vec2::new(length, link.thickness));
-    sprite.color = link.color;
-    *visibility = Visibility::Visible;
+    let cooldown_jitter = spawner
+        .balance
+        .as_ref()
+        .map_or(0.0, |balance| balance.cooldown_jitter);
+    let placements = layout.as_ref().and_then(|layout| layout.units.as_ref());
+    if placements.is_none() {
+        for entry in registry.entries.iter() {
+            let offset = Vec2::new(18.0, 0.0);
+            for start in [entry.position + offset, entry.position - offset] {
+                let kind = settings.sample_unit_kind(&mut spawner.composition.0);
+                let cooldown_scale = roll_cooldown_scale(cooldown_jitter, &mut spawner.rng);
+                spawner.spawn(entry.player, kind, start, entry.position, cooldown_scale);
+            }
+        }
+    }
+
+    for &(player, kind, position) in placements.into_iter().flatten() {
+        if player.0 >= registry.entries.len() {
+            warn!(
+                target: "gameplay",
+                "Skipping initial unit for player {} (board has {} players)",
+                player.0,
+                registry.entries.len()
+            );
+            continue;
+        }
+        let cooldown_scale = roll_cooldown_scale(cooldown_jitter, &mut spawner.rng);
+        spawner.spawn(player, kind, position, position, cooldown_scale);
+    }
 }
 
-fn update_beam_effects(
-    time: Res<Time>,
+fn spawn_pylons(
     mut commands: Commands,
-    mut beams: Query<(Entity, &mut BeamEffect)>,
+    settings: Res<BoardSettings>,
+    simulation_rng: Res<SimulationRng>,
+    layout: Option<Res<InitialLayout>>,
+    theme: Option<Res<ThemeSettings>>,
+    balance: Option<Res<BalanceSettings>>,
 ) {
-    for (entity, mut effect) in beams.iter_mut() {
-        if effect.timer.tick(time.delta()).finished() {
-            commands.entity(entity).despawn_recursive();
-        }
+    let size = theme.map_or(ThemeSettings::default().pylon_size, |theme| {
+        theme.pylon_size
+    });
+    let health = balance.map_or(DEFAULT_PYLON_MAX_HEALTH, |balance| balance.pylon_max_health);
+    // Pylons draw from their own stream so their layout depends only on the
+    // seed, not on whatever else consumed `SimulationRng` during startup.
+    let mut rng = simulation_rng.stream("pylons");
+    let fixed_positions = layout.as_ref().and_then(|layout| layout.pylons.as_ref());
+    let count = fixed_positions.map_or(settings.pylon_count, Vec::len);
+    for idx in 0..count {
+        let (position, angle) = match fixed_positions {
+            Some(positions) => {
+                let position = positions[idx];
+                (position, position.y.atan2(position.x))
+            }
+            None => {
+                let radius = settings.board_size * (0.15 + rng.gen_range(0.0..=0.15));
+                let angle = rng.gen_range(0.0..=TAU);
+                (Vec2::new(angle.cos(), angle.sin()) * radius, angle)
+            }
+        };
+        let speed = rng.gen_range(20.0..=60.0);
+        let velocity = Vec2::new(-angle.sin(), angle.cos()) * speed;
+        commands.spawn((
+            Sprite {
+                color: pylon_color(None),
+                custom_size: Some(size),
+                ..default()
+            },
+            Transform {
+                translation: Vec3::new(position.x, position.y, 0.2 + idx as f32 * 0.01),
+                rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_4),
+                ..default()
+            },
+            SimulationPosition::new(position),
+            Pylon {
+                velocity,
+                mass: 1.0 + rng.gen_range(0.0..=1.0),
+                health,
+                owner: None,
+                control: None,
+            },
+        ));
     }
 }
 
-fn configure_fixed_time(mut fixed_time: ResMut<Time<Fixed>>, params: Res<SimulationParams>) {
-    fixed_time.set_timestep_seconds(params.fixed_delta);
-}
+/// The target for a unit under [`BalanceSettings::target_lock`]: `locked`,
+/// its previous target if still alive, unless the lock has lapsed or `best`
+/// is preferred over it; then `best`, which becomes the new lock. The result
+/// may be out of range while the grace period runs.
+fn keep_target_lock(
+    unit: &mut Unit,
+    policy: TargetingPolicy,
+    origin: Vec2,
+    locked: Option<TargetCandidate>,
+    best: Option<TargetCandidate>,
+    tick: u64,
+    balance: &BalanceSettings,
+) -> Option<TargetCandidate> {
+    let Some(locked) = locked else {
+        unit.target_out_of_range_since = None;
+        return best;
+    };
+    if locked.position.distance_squared(origin) <= LASER_RANGE * LASER_RANGE {
+        unit.target_out_of_range_since = None;
+    } else {
+        unit.target_out_of_range_since.get_or_insert(tick);
+    }
+    let lapsed = unit
+        .target_out_of_range_since
+        .is_some_and(|since| tick - since > balance.target_lock_grace_ticks);
+    match best {
+        Some(best) if lapsed || policy.prefers(&best, &locked) => {
+            unit.target_out_of_range_since = None;
+            Some(best)
+        }
+        _ if lapsed => {
+            unit.target_out_of_range_since = None;
+            None
+        }
+        _ => Some(locked),
+    }
+}
+
+/// Moves each neutral pylon's capture progress toward the team with the most
+/// units in its radius. A different leader first drains the current
+/// capturer's progress; ties and empty pylons hold still. Owned pylons have to
+/// be shot back to neutral before anyone else can capture them.
+fn capture_pylons(
+    time: Res<Time>,
+    balance: Res<BalanceSettings>,
+    teams: Res<Teams>,
+    units: Query<(&SimulationPosition, &Unit)>,
+    mut pylons: Query<(&SimulationPosition, &mut Pylon, &mut Sprite), Without<Unit>>,
+) {
+    if !balance.capturable_pylons {
+        return;
+    }
+    let step = balance.pylon_capture_per_second * time.delta().as_secs_f32();
+    for (position, mut pylon, mut sprite) in &mut pylons {
+        if pylon.owner.is_some() {
+            continue;
+        }
+        let center = position.current;
+        let mut counts: BTreeMap<TeamId, usize> = BTreeMap::new();
+        for (position, unit) in &units {
+            if position.current.distance(center) <= PYLON_RADIUS {
+                *counts.entry(teams.team_of(unit.player)).or_default() += 1;
+            }
+        }
+        let mut ranked: Vec<(TeamId, usize)> = counts.into_iter().collect();
+        ranked.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        let leader = match ranked.as_slice() {
+            [(team, _)] => *team,
+            [(team, most), (_, next), ..] if most > next => *team,
+            _ => continue,
+        };
+        let (team, progress) = match pylon.control {
+            Some((team, progress)) if team != leader && progress > step => (team, progress - step),
+            Some((team, progress)) if team == leader => (team, progress + step),
+            Some(_) => (leader, 0.0),
+            None => (leader, step),
+        };
+        if progress >= 1.0 {
+            pylon.owner = Some(team);
+            pylon.control = None;
+            pylon.health = balance.pylon_max_health;
+            sprite.color = pylon_color(pylon.owner);
+        } else {
+            pylon.control = Some((team, progress));
+        }
+    }
+}
+
+/// Three-body drift: pylons pull on each other and bounce off the playfield
+/// edges.
+fn move_pylons(
+    time: Res<Time>,
+    settings: Res<BoardSettings>,
+    mut pylons: Query<(Entity, &mut SimulationPosition, &mut Pylon)>,
+) {
+    let dt = time.delta_secs();
+    if pylons.is_empty() {
+        return;
+    }
+    let snapshots: Vec<(Entity, Vec2, Vec2, f32)> = pylons
+        .iter()
+        .map(|(entity, position, pylon)| (entity, position.current, pylon.velocity, pylon.mass))
+        .collect();
+
+    let mut accelerations: HashMap<Entity, Vec2> = HashMap::default();
+    for (entity_a, pos_a, _, _) in &snapshots {
+        let mut acc = Vec2::ZERO;
+        for (entity_b, pos_b, _, mass_b) in &snapshots {
+            if entity_a == entity_b {
+                continue;
+            }
+            let offset = *pos_b - *pos_a;
+            let dist_sq = offset.length_squared().max(4000.0);
+            acc += offset.normalize() * (PYLON_GRAVITY * *mass_b / dist_sq);
+        }
+        accelerations.insert(*entity_a, acc);
+    }
+
+    let boundary = settings.playfield_half_extent();
+    for (entity, mut position, mut pylon) in pylons.iter_mut() {
+        if let Some(acc) = accelerations.get(&entity) {
+            pylon.velocity += *acc * dt;
+        }
+        pylon.velocity = pylon.velocity.clamp_length_max(PYLON_MAX_SPEED);
+        let mut next = position.current + pylon.velocity * dt;
+        if next.x.abs() > boundary {
+            next.x = next.x.clamp(-boundary, boundary);
+            pylon.velocity.x = -pylon.velocity.x;
+        }
+        if next.y.abs() > boundary {
+            next.y = next.y.clamp(-boundary, boundary);
+            pylon.velocity.y = -pylon.velocity.y;
+        }
+        position.current = next;
+    }
+}
+
+/// Cooldown multiplier for a unit about to spawn, drawn from `1 ± jitter`
+/// (see [`BalanceSettings::cooldown_jitter`]). No draw when `jitter` is 0.
+fn roll_cooldown_scale(jitter: f32, rng: &mut SimulationRng) -> f32 {
+    let jitter = jitter.clamp(0.0, 0.9);
+    if jitter == 0.0 {
+        return 1.0;
+    }
+    rng.gen_f32(1.0 - jitter..=1.0 + jitter)
+}
+
+/// What spawning a unit draws on: commands, [`UnitId`]s, the RNG streams for
+/// jitter and kind, and the optional theme and balance settings.
+#[derive(SystemParam)]
+struct UnitSpawner<'w, 's> {
+    commands: Commands<'w, 's>,
+    unit_ids: ResMut<'w, NextUnitId>,
+    rng: ResMut<'w, SimulationRng>,
+    composition: ResMut<'w, SpawnCompositionRng>,
+    theme: Option<Res<'w, ThemeSettings>>,
+    balance: Option<Res<'w, BalanceSettings>>,
+}
+
+impl UnitSpawner<'_, '_> {
+    /// Spawns a unit with the next [`UnitId`] in its player's color, its
+    /// attack cooldown scaled by `cooldown_scale`.
+    fn spawn(
+        &mut self,
+        player: PlayerId,
+        kind: UnitKind,
+        position: Vec2,
+        rally_target: Vec2,
+        cooldown_scale: f32,
+    ) {
+        let id = self.unit_ids.allocate();
+        let color = player_color(player);
+        let size = self
+            .theme
+            .as_ref()
+            .map_or(ThemeSettings::default().unit_size, |theme| theme.unit_size);
+        self.commands.spawn((
+            Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            Transform::from_xyz(position.x, position.y, 0.2),
+            SimulationPosition::new(position),
+            id,
+            Unit {
+                player,
+                rally_target,
+                waypoints: VecDeque::new(),
+                kind,
+                health: kind.health(),
+                max_health: kind.health(),
+                attack_timer: Timer::from_seconds(
+                    kind.attack_cooldown() * cooldown_scale,
+                    TimerMode::Repeating,
+                ),
+                cooldown_scale,
+                velocity: Vec2::ZERO,
+                base_color: color,
+                boost_visual: None,
+                kills: 0,
+                rank: 0,
+                last_combat_tick: 0,
+                last_hit_tick: None,
+                suspended_order: None,
+                holding: false,
+                supplied_until_tick: None,
+                supply_multiplier: 1.0,
+                facing: FRAC_PI_2,
+                aim: None,
+                current_target: None,
+                target_out_of_range_since: None,
+            },
+        ));
+    }
+}
+
+/// Reinforcements arrive every `spawn_period_ticks` simulation ticks rather
+/// than on accumulated `Timer` seconds, so spawn counts follow exactly from
+/// the tick count regardless of float drift in frame deltas.
+fn spawn_reinforcements(
+    tick: Res<SimulationTick>,
+    params: Res<SimulationParams>,
+    settings: Res<BoardSettings>,
+    registry: Res<SpawnRegistry>,
+    units: Query<(&Unit, &SimulationPosition)>,
+    mut spawner: UnitSpawner,
+    mut economy: Option<ResMut<Economy>>,
+) {
+    if !settings.reinforcements
+        || tick.0 == 0
+        || tick.0 % spawn_period_ticks(&settings, &params) != 0
+    {
+        return;
+    }
+    let cooldown_jitter = spawner
+        .balance
+        .as_ref()
+        .map_or(0.0, |balance| balance.cooldown_jitter);
+    let unit_cost = spawner
+        .balance
+        .as_ref()
+        .filter(|balance| balance.economy_enabled)
+        .map(|balance| balance.unit_cost);
+    let mut living = [0usize; MAX_PLAYERS];
+    for (unit, _) in units.iter() {
+        if let Some(count) = living.get_mut(unit.player.0) {
+            *count += 1;
+        }
+    }
+    for entry in registry.entries.iter() {
+        // Jitter, kind, and cooldown are drawn even for capped players so the
+        // others' spawns don't depend on who is at the cap.
+        let rng = &mut spawner.rng;
+        let jitter = Vec2::new(rng.gen_f32(-20.0..=20.0), rng.gen_f32(-20.0..=20.0));
+        let kind = settings.sample_unit_kind(&mut spawner.composition.0);
+        let cooldown_scale = roll_cooldown_scale(cooldown_jitter, &mut spawner.rng);
+        if settings.at_unit_cap(living[entry.player.0]) {
+            continue;
+        }
+        if let (Some(cost), Some(economy)) = (unit_cost, economy.as_deref_mut()) {
+            if !economy.try_spend(entry.player, cost) {
+                continue;
+            }
+        }
+        let start = entry.position + jitter;
+        let rally_target = average_unit_position(entry.player, &units).unwrap_or(entry.position);
+        spawner.spawn(entry.player, kind, start, rally_target, cooldown_scale);
+    }
+}
+
+/// `spawn_interval` expressed in whole fixed steps (at least one).
+pub fn spawn_period_ticks(settings: &BoardSettings, params: &SimulationParams) -> u64 {
+    (f64::from(settings.spawn_interval) / params.fixed_delta)
+        .round()
+        .max(1.0) as u64
+}
+
+fn average_unit_position(
+    player: PlayerId,
+    units: &Query<(&Unit, &SimulationPosition)>,
+) -> Option<Vec2> {
+    let mut sum = Vec2::ZERO;
+    let mut count = 0.0;
+    for (unit, position) in units.iter() {
+        if unit.player == player {
+            sum += position.current;
+            count += 1.0;
+        }
+    }
+    if count > 0.0 {
+        Some(sum / count)
+    } else {
+        None
+    }
+}
+
+/// Number keys 1-8 switch [`ControlSettings::local_player`] among the first
+/// eight players for debugging; with Shift held they pick players 9-16, so
+/// every one of [`MAX_PLAYERS`] can be controlled.
+pub const PLAYER_SWITCH_KEYS: [KeyCode; 8] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+];
+
+/// Hands control to another player, clamped to the board's player count, and
+/// drops the previous player's selection.
+fn switch_local_player(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    board: Res<BoardSettings>,
+    mut control: ResMut<ControlSettings>,
+    mut selection: ResMut<SelectionState>,
+) {
+    let Some(idx) = keys.and_then(|keys| {
+        let bank = if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            PLAYER_SWITCH_KEYS.len()
+        } else {
+            0
+        };
+        PLAYER_SWITCH_KEYS
+            .iter()
+            .position(|key| keys.just_pressed(*key))
+            .map(|idx| bank + idx)
+    }) else {
+        return;
+    };
+    let player = PlayerId(idx.min(board.player_count.saturating_sub(1)));
+    if player == control.local_player {
+        return;
+    }
+    control.local_player = player;
+    selection.prev_selected = std::mem::take(&mut selection.selected);
+    selection.dirty = true;
+}
+
+#[cfg(feature = "render")]
+/// Mouse buttons and modifier keys, plus the window and camera that map the
+/// cursor into the world.
+#[derive(SystemParam)]
+struct PointerInput<'w, 's> {
+    buttons: Res<'w, ButtonInput<MouseButton>>,
+    keys: Option<Res<'w, ButtonInput<KeyCode>>>,
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<Camera2d>>,
+}
+
+#[cfg(feature = "render")]
+fn handle_selection_input(
+    pointer: PointerInput,
+    mut selection: ResMut<SelectionState>,
+    mut commands: Commands,
+    mut queries: ParamSet<(UnitReadQuery, SelectionRectQuery)>,
+    control: Res<ControlSettings>,
+    mut warned: Local<bool>,
+) {
+    let PointerInput {
+        buttons,
+        keys,
+        windows,
+        cameras,
+    } = pointer;
+    let cursor_world = cursor_world_position(&windows, &cameras);
+
+    if buttons.just_pressed(MouseButton::Left) {
+        warn_missing_camera(&cameras, &mut warned, "handle_selection_input");
+        if let Some(pos) = cursor_world {
+            selection.is_dragging = true;
+            selection.start_world = pos;
+            selection.current_world = pos;
+
+            let entity = commands
+                .spawn((
+                    Sprite {
+                        color: Color::srgba(0.2, 0.8, 1.0, 0.2),
+                        custom_size: Some(Vec2::splat(2.0)),
+                        ..default()
+                    },
+                    Transform::from_xyz(pos.x, pos.y, 0.5),
+                    SelectionRect,
+                ))
+                .id();
+            selection.rectangle_entity = Some(entity);
+        }
+    }
+
+    if selection.is_dragging && buttons.pressed(MouseButton::Left) {
+        if let Some(pos) = cursor_world {
+            selection.current_world = pos;
+        }
+        if let Some(entity) = selection.rectangle_entity {
+            if let Ok((mut sprite, mut transform)) = queries.p1().get_mut(entity) {
+                let min = Vec2::new(
+                    selection.start_world.x.min(selection.current_world.x),
+                    selection.start_world.y.min(selection.current_world.y),
+                );
+                let max = Vec2::new(
+                    selection.start_world.x.max(selection.current_world.x),
+                    selection.start_world.y.max(selection.current_world.y),
+                );
+                let size = max - min;
+                sprite.custom_size = Some(Vec2::new(size.x.abs().max(2.0), size.y.abs().max(2.0)));
+                transform.translation =
+                    Vec3::new((min.x + max.x) * 0.5, (min.y + max.y) * 0.5, 0.5);
+            }
+        }
+    }
+
+    if selection.is_dragging && buttons.just_released(MouseButton::Left) {
+        let units = queries.p0();
+        let min = Vec2::new(
+            selection.start_world.x.min(selection.current_world.x),
+            selection.start_world.y.min(selection.current_world.y),
+        );
+        let max = Vec2::new(
+            selection.start_world.x.max(selection.current_world.x),
+            selection.start_world.y.max(selection.current_world.y),
+        );
+        let padded_min = min - Vec2::splat(8.0);
+        let padded_max = max + Vec2::splat(8.0);
+        let mut newly_selected = Vec::new();
+        for (entity, transform, unit) in units.iter() {
+            if unit.player == control.local_player {
+                let pos = transform.translation.truncate();
+                if pos.x >= padded_min.x
+                    && pos.x <= padded_max.x
+                    && pos.y >= padded_min.y
+                    && pos.y <= padded_max.y
+                {
+                    newly_selected.push(entity);
+                }
+            }
+        }
+
+        let mode = SelectionMode::from_keys(keys.as_deref());
+        selection.prev_selected = std::mem::take(&mut selection.selected);
+        selection.selected = mode.merge(&selection.prev_selected, newly_selected);
+        selection.dirty = true;
+        selection.is_dragging = false;
+        selection.current_world = selection.start_world;
+        if let Some(entity) = selection.rectangle_entity.take() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    {
+        let units = queries.p0();
+        selection
+            .selected
+            .retain(|entity| units.get(*entity).is_ok());
+    }
+}
+
+/// How a finished drag or click combines with the current selection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Select exactly the picked units; a click on empty ground deselects.
+    #[default]
+    Replace,
+    /// Shift: add the picked units.
+    Add,
+    /// Ctrl: remove the picked units.
+    Subtract,
+}
+
+impl SelectionMode {
+    /// Ctrl wins when both modifiers are held.
+    pub fn from_keys(keys: Option<&ButtonInput<KeyCode>>) -> Self {
+        let Some(keys) = keys else {
+            return Self::Replace;
+        };
+        if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+            Self::Subtract
+        } else if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            Self::Add
+        } else {
+            Self::Replace
+        }
+    }
+
+    /// Keeps `current` order and appends newly added units in `picked`
+    /// order, so the result doesn't depend on hashing.
+    pub fn merge(self, current: &[Entity], picked: Vec<Entity>) -> Vec<Entity> {
+        match self {
+            Self::Replace => picked,
+            Self::Add => {
+                let mut merged = current.to_vec();
+                for entity in picked {
+                    if !merged.contains(&entity) {
+                        merged.push(entity);
+                    }
+                }
+                merged
+            }
+            Self::Subtract => current
+                .iter()
+                .copied()
+                .filter(|entity| !picked.contains(entity))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+/// Right-click replaces each selected unit's orders; shift+right-click queues
+/// a waypoint instead. Formation offsets apply to the newly placed waypoint.
+fn issue_move_orders(
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    selection: Res<SelectionState>,
+    mut units: Query<(&mut Unit, &SimulationPosition)>,
+    mut warned: Local<bool>,
+) {
+    if !buttons.just_pressed(MouseButton::Right) {
+        return;
+    }
+    warn_missing_camera(&cameras, &mut warned, "issue_move_orders");
+    let Some(cursor) = cursor_world_position(&windows, &cameras) else {
+        return;
+    };
+    if selection.selected.is_empty() {
+        return;
+    }
+
+    let queue =
+        keys.is_some_and(|keys| keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]));
+    let order = if queue {
+        Order::Queue(cursor)
+    } else {
+        Order::Move(cursor)
+    };
+    for (entity, target) in order.targets(&selection.selected) {
+        if let Ok((mut unit, position)) = units.get_mut(entity) {
+            order.apply(&mut unit, position.current, target);
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+/// Mouse input does nothing without a camera to map the cursor through;
+/// say so once instead of failing silently.
+fn warn_missing_camera(
+    cameras: &Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    warned: &mut bool,
+    system: &str,
+) {
+    if cameras.is_empty() && !*warned {
+        *warned = true;
+        warn!(
+            target: "gameplay",
+            "{system} ignored mouse input: no Camera2d to map the cursor; use gameplay::issue_order to command units headlessly"
+        );
+    }
+}
+
+/// A command for a group of units, as issued by right-click or the
+/// [`ControlSettings`] keys.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Order {
+    /// Replace current orders with a move to the point.
+    Move(Vec2),
+    /// Append a waypoint at the point.
+    Queue(Vec2),
+    /// Drop all orders and stop in place.
+    Stop,
+    /// Stop in place and hold that ground, still firing at enemies in range.
+    Hold,
+}
+
+impl Order {
+    /// Per-unit destinations: a formation around the order's point. Stop and
+    /// hold have none; each unit settles where it stands.
+    fn targets(self, units: &[Entity]) -> Vec<(Entity, Option<Vec2>)> {
+        match self {
+            Order::Move(anchor) | Order::Queue(anchor) => {
+                assign_formation_targets(anchor, units, FormationKind::default())
+                    .into_iter()
+                    .map(|(entity, target)| (entity, Some(target)))
+                    .collect()
+            }
+            Order::Stop | Order::Hold => units.iter().map(|&entity| (entity, None)).collect(),
+        }
+    }
+
+    fn apply(self, unit: &mut Unit, position: Vec2, target: Option<Vec2>) {
+        let target = target.unwrap_or(position);
+        match self {
+            Order::Move(_) => unit.order_move(target),
+            Order::Queue(_) => unit.queue_move(target),
+            Order::Stop => unit.stop(position),
+            Order::Hold => unit.hold(position),
+        }
+    }
+}
+
+/// Gives `units` the same order a right-click or command key would, without
+/// a window, camera, or selection. Entities that aren't units are skipped.
+pub fn issue_order(world: &mut World, units: &[Entity], order: Order) {
+    for (entity, target) in order.targets(units) {
+        let Some(position) = world
+            .get::<SimulationPosition>(entity)
+            .map(|position| position.current)
+        else {
+            continue;
+        };
+        if let Some(mut unit) = world.get_mut::<Unit>(entity) {
+            order.apply(&mut unit, position, target);
+        }
+    }
+}
+
+/// [`ControlSettings::stop_key`] and [`ControlSettings::hold_key`] for the
+/// selected units.
+fn issue_command_keys(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    control: Res<ControlSettings>,
+    selection: Res<SelectionState>,
+    mut units: Query<(&mut Unit, &SimulationPosition)>,
+) {
+    let Some(keys) = keys else {
+        return;
+    };
+    let order = if keys.just_pressed(control.hold_key) {
+        Order::Hold
+    } else if keys.just_pressed(control.stop_key) {
+        Order::Stop
+    } else {
+        return;
+    };
+    for (entity, target) in order.targets(&selection.selected) {
+        if let Ok((mut unit, position)) = units.get_mut(entity) {
+            order.apply(&mut unit, position.current, target);
+        }
+    }
+}
+
+/// Shape a group of units arranges itself in around a move target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FormationKind {
+    /// The first unit on the anchor, the rest on concentric hexagonal rings
+    /// `FORMATION_SPACING` apart.
+    #[default]
+    Rings,
+}
+
+/// World-space destination for each of `units` when ordered to `anchor`.
+/// Offsets are handed out in `units` order, so the same slice always yields
+/// the same assignment.
+pub fn assign_formation_targets(
+    anchor: Vec2,
+    units: &[Entity],
+    kind: FormationKind,
+) -> Vec<(Entity, Vec2)> {
+    let offsets = match kind {
+        FormationKind::Rings => compute_formation_offsets(units.len()),
+    };
+    units
+        .iter()
+        .zip(offsets)
+        .map(|(&entity, offset)| (entity, anchor + offset))
+        .collect()
+}
+
+fn compute_formation_offsets(count: usize) -> Vec<Vec2> {
+    let mut offsets = Vec::with_capacity(count);
+    if count == 0 {
+        return offsets;
+    }
+    if count >= 1 {
+        offsets.push(Vec2::ZERO);
+    }
+    let mut generated = offsets.len();
+    let mut ring = 1;
+    while generated < count {
+        let slots = (ring * 6) as usize;
+        for idx in 0..slots {
+            if generated == count {
+                break;
+            }
+            let angle = idx as f32 / slots as f32 * TAU;
+            offsets.push(Vec2::from_angle(angle) * (ring as f32 * FORMATION_SPACING));
+            generated += 1;
+        }
+        ring += 1;
+    }
+    offsets
+}
+
+#[cfg(feature = "render")]
+fn cursor_world_position(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+) -> Option<Vec2> {
+    let window = windows.get_single().ok()?;
+    let cursor_pos = window.cursor_position()?;
+    let (camera, camera_transform) = cameras.get_single().ok()?;
+    let ray = camera
+        .viewport_to_world(camera_transform, cursor_pos)
+        .ok()?;
+    Some(ray.origin.truncate())
+}
+
+#[cfg(feature = "render")]
+fn update_selection_visuals(
+    mut commands: Commands,
+    mut selection: ResMut<SelectionState>,
+    units: Query<&Unit>,
+    highlights: Query<&SelectionHighlight>,
+) {
+    if !selection.dirty {
+        return;
+    }
+
+    for entity in selection.prev_selected.drain(..) {
+        if let Ok(highlight) = highlights.get(entity) {
+            commands.entity(highlight.glow).despawn_recursive();
+            commands.entity(entity).remove::<SelectionHighlight>();
+        }
+    }
+
+    for &entity in &selection.selected {
+        if highlights.get(entity).is_ok() {
+            continue;
+        }
+        if units.get(entity).is_err() {
+            continue;
+        }
+        let glow = commands
+            .spawn((
+                Sprite {
+                    color: Color::srgba(1.0, 0.95, 0.2, 0.35),
+                    custom_size: Some(Vec2::new(42.0, 56.0)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 0.0, -0.1),
+            ))
+            .id();
+        commands.entity(entity).add_child(glow);
+        commands.entity(entity).insert(SelectionHighlight { glow });
+    }
+
+    selection.dirty = false;
+}
+
+/// Adds one chevron above `unit` for its newly reached `rank`.
+fn spawn_rank_chevron(commands: &mut Commands, unit: Entity, rank: u8) {
+    let chevron = commands
+        .spawn((
+            Sprite {
+                color: Color::srgb(0.98, 0.82, 0.3),
+                custom_size: Some(Vec2::splat(7.0)),
+                ..default()
+            },
+            Transform {
+                translation: Vec3::new(0.0, 18.0 + f32::from(rank) * 6.0, 0.1),
+                rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_4),
+                ..default()
+            },
+        ))
+        .id();
+    commands.entity(unit).add_child(chevron);
+}
+
+fn update_boost_visual(entity: Entity, unit: &mut Unit, active: bool, commands: &mut Commands) {
+    if active {
+        if unit.boost_visual.is_some() {
+            return;
+        }
+        let glow = commands
+            .spawn((
+                Sprite {
+                    color: Color::srgba(0.98, 0.95, 0.45, 0.2),
+                    custom_size: Some(Vec2::splat(40.0)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 0.0, -0.05),
+            ))
+            .id();
+        commands.entity(entity).add_child(glow);
+        unit.boost_visual = Some(glow);
+    } else if let Some(glow) = unit.boost_visual.take() {
+        if let Some(cmds) = commands.get_entity(glow) {
+            cmds.despawn_recursive();
+        }
+    }
+}
+
+fn move_units(
+    time: Res<Time>,
+    settings: Res<BoardSettings>,
+    balance: Option<Res<BalanceSettings>>,
+    mut units: Query<(&mut SimulationPosition, &mut Unit)>,
+) {
+    let dt = time.delta_secs();
+    let decay = if balance.is_some_and(|balance| balance.deterministic_math) {
+        portable_exp(-UNIT_ACCELERATION * dt)
+    } else {
+        (-UNIT_ACCELERATION * dt).exp()
+    };
+    let accel = 1.0 - decay;
+    let boundary = settings.playfield_half_extent();
+    for (mut position, mut unit) in units.iter_mut() {
+        let pos = position.current;
+        if !unit.waypoints.is_empty()
+            && pos.distance_squared(unit.rally_target) <= WAYPOINT_RADIUS * WAYPOINT_RADIUS
+        {
+            if let Some(next) = unit.waypoints.pop_front() {
+                unit.rally_target = next;
+            }
+        }
+        let delta = unit.rally_target - pos;
+        let desired = if delta.length_squared() > 1.0 {
+            delta.normalize() * UNIT_SPEED
+        } else {
+            Vec2::ZERO
+        };
+        unit.velocity = unit.velocity.lerp(desired, accel);
+        let mut next = pos + unit.velocity * dt;
+        // Units stop at the edge instead of bouncing like pylons so a far
+        // rally target parks them on the boundary.
+        if next.x.abs() > boundary {
+            next.x = next.x.clamp(-boundary, boundary);
+            unit.velocity.x = 0.0;
+        }
+        if next.y.abs() > boundary {
+            next.y = next.y.clamp(-boundary, boundary);
+            unit.velocity.y = 0.0;
+        }
+        position.current = next;
+    }
+}
+
+/// Turns each unit toward the enemy it aims at, else toward where it is
+/// heading; [`sync_unit_transforms`] rotates its sprite to match. Facing
+/// derives from positions and velocities alone, so it replays with them.
+fn turn_units(time: Res<Time>, mut units: Query<(&SimulationPosition, &mut Unit)>) {
+    let max_turn = UNIT_TURN_RATE * time.delta_secs();
+    for (position, mut unit) in units.iter_mut() {
+        let heading = match unit.aim {
+            Some(aim) => aim - position.current,
+            None if unit.velocity.length() > FACING_MIN_SPEED => unit.velocity,
+            None => Vec2::ZERO,
+        };
+        if heading != Vec2::ZERO {
+            let turn = wrap_angle(heading.to_angle() - unit.facing);
+            unit.facing = wrap_angle(unit.facing + turn.clamp(-max_turn, max_turn));
+        }
+    }
+}
+
+/// `angle` in `[-PI, PI)`.
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+fn update_unit_rally_targets(mut units: Query<(Entity, &mut Unit, &SimulationPosition)>) {
+    let mut positions = Vec::new();
+    for (entity, unit, position) in units.iter() {
+        positions.push((entity, unit.player, position.current));
+    }
+
+    for (entity, mut unit, position) in units.iter_mut() {
+        let mut push = Vec2::ZERO;
+        for (other_entity, other_player, other_pos) in positions.iter() {
+            if entity == *other_entity || unit.player != *other_player {
+                continue;
+            }
+            let offset = position.current - *other_pos;
+            let distance = offset.length();
+            if distance > 0.1 && distance < UNIT_SEPARATION_RADIUS {
+                push += offset.normalize() * (UNIT_SEPARATION_RADIUS - distance)
+                    / UNIT_SEPARATION_RADIUS;
+            }
+        }
+        if push.length_squared() > 0.0 && !unit.holding {
+            let push_dir = push.normalize_or_zero();
+            unit.rally_target += push_dir * 5.0;
+            unit.velocity += push_dir * SEPARATION_FORCE;
+            unit.velocity = unit.velocity.clamp_length_max(UNIT_SPEED * 1.5);
+        }
+    }
+}
+
+/// Match rules and state [`unit_combat_system`] reads.
+#[derive(SystemParam)]
+struct CombatContext<'w> {
+    tick: Res<'w, SimulationTick>,
+    teams: Res<'w, Teams>,
+    balance: Res<'w, BalanceSettings>,
+    spawn_registry: Res<'w, SpawnRegistry>,
+}
+
+/// What [`unit_combat_system`] records each step: the combat log, match
+/// stats, supply, and the support links to draw.
+#[derive(SystemParam)]
+struct CombatRecords<'w> {
+    combat_log: ResMut<'w, CombatLog>,
+    match_stats: ResMut<'w, MatchStats>,
+    link_buffer: ResMut<'w, SupportLinkBuffer>,
+    supply_state: ResMut<'w, SupplyState>,
+}
+
+/// Settings for the beams and chevrons [`unit_combat_system`] spawns.
+#[derive(SystemParam)]
+struct CombatEffects<'w, 's> {
+    render_effects: Res<'w, RenderEffects>,
+    render_settings: Res<'w, RenderSettings>,
+    theme: Res<'w, ThemeSettings>,
+    beam_effects: Query<'w, 's, (), With<BeamEffect>>,
+}
+
+fn unit_combat_system(
+    time: Res<Time>,
+    context: CombatContext,
+    records: CombatRecords,
+    effects: CombatEffects,
+    mut pylons: Query<(Entity, &SimulationPosition, &mut Pylon, &mut Sprite), Without<Unit>>,
+    mut commands: Commands,
+    mut unit_queries: ParamSet<(CombatReadQuery, UnitWriteQuery)>,
+) {
+    let CombatContext {
+        tick,
+        teams,
+        balance,
+        spawn_registry,
+    } = context;
+    let CombatRecords {
+        mut combat_log,
+        mut match_stats,
+        mut link_buffer,
+        mut supply_state,
+    } = records;
+    let CombatEffects {
+        render_effects,
+        render_settings,
+        theme,
+        beam_effects,
+    } = effects;
+    link_buffer.links.clear();
+
+    let snapshot: Vec<_> = {
+        let query = unit_queries.p0();
+        query
+            .iter()
+            .map(|(entity, position, unit, id)| (entity, *id, unit.player, position.current))
+            .collect()
+    };
+
+    let vitals: HashMap<Entity, (f32, UnitKind)> = unit_queries
+        .p0()
+        .iter()
+        .map(|(entity, _, unit, _)| (entity, (unit.health, unit.kind)))
+        .collect();
+    let ids: HashMap<Entity, UnitId> = snapshot
+        .iter()
+        .map(|&(entity, id, _, _)| (entity, id))
+        .collect();
+    let id_of = |entity: Entity| ids.get(&entity).copied().unwrap_or_default();
+
+    let mut entity_info: HashMap<Entity, (PlayerId, Vec2)> = HashMap::default();
+    for (entity, _, player, pos) in &snapshot {
+        entity_info.insert(*entity, (*player, *pos));
+    }
+
+    let support_links = build_support_links(&snapshot, &teams, LASER_HEAL_RANGE);
+    let mut adjacency: HashMap<Entity, Vec<Entity>> = HashMap::default();
+    let mut connections: HashMap<Entity, usize> = HashMap::default();
+    for &(entity_a, entity_b) in &support_links {
+        *connections.entry(entity_a).or_default() += 1;
+        *connections.entry(entity_b).or_default() += 1;
+        adjacency.entry(entity_a).or_default().push(entity_b);
+        adjacency.entry(entity_b).or_default().push(entity_a);
+    }
+
+    let (mut connected_entities, supply_components) =
+        collect_supply_components(&snapshot, &adjacency, &spawn_registry.entries, &teams);
+
+    let mut pylon_sites: Vec<PylonSite> = pylons
+        .iter()
+        .map(|(entity, position, pylon, _)| PylonSite {
+            entity,
+            position: position.current,
+            owner: pylon.owner,
+            capturer: pylon.control.map(|(team, _)| team),
+        })
+        .collect();
+    pylon_sites.sort_by_key(|site| site.entity);
+
+    let mut component_bonus: HashMap<Entity, f32> = HashMap::default();
+    let mut component_pylon_active: HashSet<Entity> = HashSet::default();
+    for component in supply_components {
+        let mut bonus = 0.0;
+        let mut component_powered_pairs = Vec::new();
+        for entity in &component {
+            if let Some((player, pos)) = entity_info.get(entity) {
+                let team = teams.team_of(*player);
+                for site in &pylon_sites {
+                    if site.powers(team, balance.capturable_pylons)
+                        && pos.distance(site.position) <= PYLON_RADIUS
+                    {
+                        bonus += PYLON_DAMAGE_BONUS;
+                        component_powered_pairs.push((site.position, *pos));
+                        break;
+                    }
+                }
+            }
+        }
+        if !component_powered_pairs.is_empty() {
+            for (pylon_pos, unit_pos) in component_powered_pairs {
+                emit_support_link(
+                    &mut link_buffer.links,
+                    pylon_pos,
+                    unit_pos,
+                    Color::srgb(0.2, 0.7, 1.0),
+                );
+            }
+            for entity in component.iter().copied() {
+                component_pylon_active.insert(entity);
+            }
+        }
+        for entity in component.iter().copied() {
+            component_bonus.insert(entity, bonus);
+        }
+    }
+
+    // Units that just lost their connection stay supplied, at their last
+    // multiplier, until their grace period runs out.
+    let mut grace: HashMap<Entity, f32> = HashMap::default();
+    if balance.supply_grace_ticks > 0 {
+        for (entity, _, unit, _) in unit_queries.p0().iter() {
+            if !connected_entities.contains(&entity)
+                && unit
+                    .supplied_until_tick
+                    .is_some_and(|until| tick.0 <= until)
+            {
+                grace.insert(entity, unit.supply_multiplier);
+            }
+        }
+        connected_entities.extend(grace.keys().copied());
+    }
+
+    let damage_multiplier = |entity: Entity| {
+        if let Some(&multiplier) = grace.get(&entity) {
+            multiplier
+        } else if connected_entities.contains(&entity) {
+            1.0 + connections.get(&entity).copied().unwrap_or(0) as f32 * SUPPORT_DAMAGE_BONUS
+                + component_bonus.get(&entity).copied().unwrap_or(0.0)
+        } else {
+            1.0
+        }
+    };
+    let candidates: Vec<(PlayerId, TargetCandidate)> = snapshot
+        .iter()
+        .map(|&(entity, id, player, position)| {
+            let (health, kind) = vitals[&entity];
+            let threat = kind.damage_per_second() * damage_multiplier(entity);
+            (
+                player,
+                TargetCandidate {
+                    entity,
+                    id,
+                    position,
+                    health,
+                    threat,
+                },
+            )
+        })
+        .collect();
+
+    let delta = time.delta();
+    let delta_secs = delta.as_secs_f32();
+    let mut damage_events: Vec<(Entity, Entity, f32)> = Vec::new();
+    let mut pylon_hits: Vec<(Entity, Entity, f32)> = Vec::new();
+    let mut heals: Vec<(Entity, f32)> = Vec::new();
+    let mut deaths: Vec<(Entity, Entity)> = Vec::new();
+    let mut beams: Vec<(Vec2, Vec2, Color, f32)> = Vec::new();
+
+    let mut unit_write = unit_queries.p1();
+    for (entity, position, mut transform, mut sprite, mut unit) in unit_write.iter_mut() {
+        unit.attack_timer.tick(delta);
+        let connection_count = connections.get(&entity).copied().unwrap_or(0);
+        let boost_active = connected_entities.contains(&entity);
+        if boost_active && !grace.contains_key(&entity) {
+            unit.supplied_until_tick = Some(tick.0 + balance.supply_grace_ticks);
+            unit.supply_multiplier = damage_multiplier(entity);
+        }
+        if render_effects.0 {
+            update_boost_visual(entity, &mut unit, boost_active, &mut commands);
+        }
+        let scale = if boost_active { 1.12 } else { 1.0 };
+        transform.scale = Vec3::new(scale, scale, 1.0);
+        sprite.color = unit.base_color;
+
+        let mut heal_per_second = 0.0;
+        if boost_active {
+            heal_per_second += connection_count as f32 * SUPPORT_HEAL_PER_SECOND;
+        }
+        if unit.out_of_combat(tick.0, &balance) {
+            heal_per_second += balance.ooc_regen_per_second;
+        }
+        if heal_per_second > 0.0 && unit.health < unit.max_health {
+            let healed = (unit.health + heal_per_second * delta_secs).min(unit.max_health);
+            heals.push((entity, healed - unit.health));
+            unit.health = healed;
+        }
+
+        // Attack
+        let player = unit.player;
+        let enemies = candidates
+            .iter()
+            .filter(|(owner, _)| !teams.allied(*owner, player))
+            .map(|(_, candidate)| *candidate);
+        let origin = position.current;
+        let policy = balance.targeting_for(unit.kind);
+        let best = select_target(policy, origin, LASER_RANGE, enemies.clone());
+        let target = if balance.target_lock {
+            let locked = unit
+                .current_target
+                .and_then(|id| enemies.clone().find(|candidate| candidate.id == id));
+            keep_target_lock(&mut unit, policy, origin, locked, best, tick.0, &balance)
+        } else {
+            best
+        };
+        unit.current_target = target.map(|target| target.id);
+        unit.aim = target.map(|target| target.position);
+        if let Some(target) = target {
+            let (target_entity, target_pos) = (target.entity, target.position);
+            let in_range = target_pos.distance_squared(origin) <= LASER_RANGE * LASER_RANGE;
+            if in_range && unit.attack_timer.finished() {
+                unit.last_combat_tick = tick.0;
+                let (_, target_kind) = vitals[&target_entity];
+                damage_events.push((
+                    entity,
+                    target_entity,
+                    LASER_DAMAGE
+                        * damage_multiplier(entity)
+                        * balance.veterancy_damage_multiplier(unit.rank)
+                        * balance.resistance(target_kind, unit.kind.damage_type()),
+                ));
+                beams.push((
+                    origin,
+                    target_pos,
+                    beam_color(unit.base_color),
+                    theme.beam_thickness,
+                ));
+                let cooldown = unit.attack_cooldown();
+                unit.attack_timer
+                    .set_duration(std::time::Duration::from_secs_f32(cooldown));
+                unit.attack_timer.reset();
+            }
+        } else if balance.capturable_pylons && unit.attack_timer.finished() {
+            // With no enemy unit in range, shoot the nearest pylon another
+            // team owns or is capturing.
+            let position = origin;
+            let team = teams.team_of(unit.player);
+            let pylon_target = pylon_sites
+                .iter()
+                .filter(|site| {
+                    site.hostile_to(team) && site.position.distance(position) <= LASER_RANGE
+                })
+                .min_by(|a, b| {
+                    a.position
+                        .distance(position)
+                        .total_cmp(&b.position.distance(position))
+                });
+            if let Some(site) = pylon_target {
+                unit.last_combat_tick = tick.0;
+                pylon_hits.push((
+                    entity,
+                    site.entity,
+                    LASER_DAMAGE
+                        * damage_multiplier(entity)
+                        * balance.veterancy_damage_multiplier(unit.rank),
+                ));
+                beams.push((
+                    position,
+                    site.position,
+                    beam_color(unit.base_color),
+                    theme.beam_thickness,
+                ));
+                let cooldown = unit.attack_cooldown();
+                unit.attack_timer
+                    .set_duration(std::time::Duration::from_secs_f32(cooldown));
+                unit.attack_timer.reset();
+            }
+        }
+    }
+
+    pylon_hits.sort_by_key(|&(attacker, pylon, _)| (id_of(attacker), pylon.index()));
+    for &(_, pylon_entity, amount) in &pylon_hits {
+        if let Ok((_, _, mut pylon, mut sprite)) = pylons.get_mut(pylon_entity) {
+            pylon.health -= amount;
+            if pylon.health <= 0.0 {
+                pylon.neutralize(balance.pylon_max_health);
+                sprite.color = pylon_color(None);
+            }
+        }
+    }
+
+    // Resolve in attacker order so health math and kill credit never depend
+    // on query iteration order.
+    damage_events.sort_by_key(|&(attacker, target, _)| (id_of(attacker), id_of(target)));
+    for &(attacker, target, amount) in &damage_events {
+        if let Ok((_, _, _, _, mut unit)) = unit_write.get_mut(target) {
+            let was_alive = unit.health > 0.0;
+            unit.health -= amount;
+            unit.last_combat_tick = tick.0;
+            unit.last_hit_tick = Some(tick.0);
+            if was_alive && unit.health <= 0.0 {
+                deaths.push((attacker, target));
+            }
+        }
+    }
+
+    // Deaths are in attacker order, so promotions replay identically.
+    for &(attacker, _) in &deaths {
+        let Ok((_, _, _, _, mut unit)) = unit_write.get_mut(attacker) else {
+            continue;
+        };
+        unit.kills += 1;
+        let rank = balance.veterancy_rank(unit.kills);
+        if rank <= unit.rank {
+            continue;
+        }
+        let bonus = f32::from(rank - unit.rank) * balance.veterancy_health_bonus;
+        unit.rank = rank;
+        unit.max_health += bonus;
+        if unit.health > 0.0 {
+            unit.health += bonus;
+        }
+        if render_effects.0 {
+            spawn_rank_chevron(&mut commands, attacker, rank);
+        }
+    }
+
+    heals.sort_by_key(|&(unit, _)| id_of(unit));
+    combat_log.record_tick(tick.0, &ids, &heals, &damage_events, &deaths);
+    match_stats.record_tick(&entity_info, &damage_events, &deaths);
+
+    link_buffer.active_links = support_links.len();
+    for (entity_a, entity_b) in support_links {
+        let Some((_, pos_a)) = entity_info.get(&entity_a) else {
+            continue;
+        };
+        let Some((_, pos_b)) = entity_info.get(&entity_b) else {
+            continue;
+        };
+        let pylon_active = component_pylon_active.contains(&entity_a)
+            || component_pylon_active.contains(&entity_b);
+        let color = support_link_color(pylon_active);
+        emit_support_link(&mut link_buffer.links, *pos_a, *pos_b, color);
+    }
+
+    for &(_, entity) in &deaths {
+        connected_entities.remove(&entity);
+        component_bonus.remove(&entity);
+    }
+    supply_state.supplied = connected_entities;
+    supply_state.pylon_bonus = component_bonus;
+
+    for (_, entity) in deaths {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if !render_effects.0 {
+        return;
+    }
+    let budget = render_settings
+        .max_beam_effects
+        .saturating_sub(beam_effects.iter().count());
+    for (start, end, color, thickness) in beams.into_iter().take(budget) {
+        spawn_beam(&mut commands, start, end, color, thickness);
+    }
+}
+
+/// Sends units hit below [`BalanceSettings::retreat_threshold`] back to their
+/// spawn, and returns healed ones to the orders they left. Runs after combat,
+/// so the hit that triggers a retreat is this tick's.
+fn retreat_damaged_units(
+    tick: Res<SimulationTick>,
+    balance: Res<BalanceSettings>,
+    registry: Res<SpawnRegistry>,
+    mut units: Query<&mut Unit>,
+) {
+    if balance.retreat_threshold <= 0.0 {
+        return;
+    }
+    let resume_threshold = balance
+        .retreat_resume_threshold
+        .max(balance.retreat_threshold);
+    for mut unit in units.iter_mut() {
+        let health = unit.health / unit.max_health;
+        if let Some(order) = &unit.suspended_order {
+            if health >= resume_threshold {
+                let order = order.clone();
+                unit.rally_target = order.rally_target;
+                unit.waypoints = order.waypoints;
+                unit.suspended_order = None;
+            }
+        } else if health < balance.retreat_threshold && unit.last_hit_tick == Some(tick.0) {
+            let Some(spawn) = registry
+                .entries
+                .iter()
+                .find(|entry| entry.player == unit.player)
+            else {
+                continue;
+            };
+            unit.suspended_order = Some(SuspendedOrder {
+                rally_target: unit.rally_target,
+                waypoints: std::mem::take(&mut unit.waypoints),
+            });
+            unit.rally_target = spawn.position;
+        }
+    }
+}
+
+fn spawn_beam(commands: &mut Commands, start: Vec2, end: Vec2, color: Color, thickness: f32) {
+    let diff = end - start;
+    let length = diff.length().max(1.0);
+    let angle = diff.y.atan2(diff.x);
+    let translation = Vec3::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0, 0.6);
+    commands.spawn((
+        Sprite {
+            color,
+            custom_size: Some(Vec2::new(length, thickness)),
+            ..default()
+        },
+        Transform {
+            translation,
+            rotation: Quat::from_rotation_z(angle),
+            ..default()
+        },
+        BeamEffect {
+            timer: Timer::from_seconds(BEAM_LIFETIME, TimerMode::Once),
+        },
+    ));
+}
+
+/// Beams take the firing unit's color, lightened so they stand out against
+/// the unit sprites.
+fn beam_color(base: Color) -> Color {
+    base.lighter(0.15)
+}
+
+fn support_link_color(pylon_active: bool) -> Color {
+    if pylon_active {
+        Color::srgb(0.22, 0.8, 0.95)
+    } else {
+        Color::srgb(0.24, 0.98, 0.55)
+    }
+}
+
+/// Flood-fills supply outward from each spawn point across `adjacency`. A
+/// spawn seeds and extends its network through every unit on its player's
+/// team, so allies share supply.
+///
+/// Seeds and neighbor lists are visited in [`UnitId`] order, and each
+/// component is sorted the same way, so both membership and traversal order
+/// are independent of snapshot and adjacency insertion order. Components are
+/// returned sorted by their lowest `UnitId`.
+fn collect_supply_components(
+    snapshot: &[(Entity, UnitId, PlayerId, Vec2)],
+    adjacency: &HashMap<Entity, Vec<Entity>>,
+    spawns: &[SpawnEntry],
+    teams: &Teams,
+) -> (HashSet<Entity>, Vec<Vec<Entity>>) {
+    let players: HashMap<Entity, PlayerId> = snapshot
+        .iter()
+        .map(|&(entity, _, player, _)| (entity, player))
+        .collect();
+    let ids: HashMap<Entity, UnitId> = snapshot
+        .iter()
+        .map(|&(entity, id, _, _)| (entity, id))
+        .collect();
+    let id_of = |entity: &Entity| ids.get(entity).copied().unwrap_or_default();
+    let mut connected: HashSet<Entity> = HashSet::default();
+    let mut components: Vec<Vec<Entity>> = Vec::new();
+    for entry in spawns {
+        let mut seeds: Vec<Entity> = snapshot
+            .iter()
+            .filter(|(_, _, player, pos)| {
+                teams.allied(*player, entry.player)
+                    && pos.distance(entry.position) <= LASER_HEAL_RANGE
+            })
+            .map(|(entity, _, _, _)| *entity)
+            .collect();
+        seeds.sort_by_key(id_of);
+
+        let mut queue = VecDeque::new();
+        let mut component = Vec::new();
+        for entity in seeds {
+            if connected.insert(entity) {
+                queue.push_back(entity);
+                component.push(entity);
+            }
+        }
+        while let Some(current) = queue.pop_front() {
+            let Some(neighbors) = adjacency.get(&current) else {
+                continue;
+            };
+            let mut neighbors = neighbors.clone();
+            neighbors.sort_by_key(id_of);
+            for neighbor in neighbors {
+                let allied = players
+                    .get(&neighbor)
+                    .is_some_and(|player| teams.allied(*player, entry.player));
+                if allied && connected.insert(neighbor) {
+                    queue.push_back(neighbor);
+                    component.push(neighbor);
+                }
+            }
+        }
+        if !component.is_empty() {
+            component.sort_by_key(id_of);
+            components.push(component);
+        }
+    }
+    components.sort_by_key(|component| id_of(&component[0]));
+    (connected, components)
+}
+
+/// Allied unit pairs within `range` of each other, found through a spatial
+/// grid of `range`-sized cells. Each pair is ordered by [`UnitId`] and the
+/// list is sorted, so the result does not depend on snapshot order.
+fn build_support_links(
+    snapshot: &[(Entity, UnitId, PlayerId, Vec2)],
+    teams: &Teams,
+    range: f32,
+) -> Vec<(Entity, Entity)> {
+    let mut spatial: HashMap<IVec2, Vec<usize>> = HashMap::default();
+    for (idx, (_, _, _, pos)) in snapshot.iter().enumerate() {
+        spatial
+            .entry(spatial_cell(*pos, range))
+            .or_default()
+            .push(idx);
+    }
+
+    let mut links = Vec::new();
+    for (i, &(entity_a, id_a, player_a, pos_a)) in snapshot.iter().enumerate() {
+        let cell = spatial_cell(pos_a, range);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let Some(indices) = spatial.get(&(cell + IVec2::new(dx, dy))) else {
+                    continue;
+                };
+                for &j in indices.iter().filter(|&&j| j > i) {
+                    let (entity_b, id_b, player_b, pos_b) = snapshot[j];
+                    if teams.allied(player_a, player_b)
+                        && pos_a.distance_squared(pos_b) <= range * range
+                    {
+                        links.push(if id_a <= id_b {
+                            ((id_a, entity_a), (id_b, entity_b))
+                        } else {
+                            ((id_b, entity_b), (id_a, entity_a))
+                        });
+                    }
+                }
+            }
+        }
+    }
+    links.sort_by_key(|&((id_a, _), (id_b, _))| (id_a, id_b));
+    links
+        .into_iter()
+        .map(|((_, entity_a), (_, entity_b))| (entity_a, entity_b))
+        .collect()
+}
+
+fn spatial_cell(position: Vec2, cell_size: f32) -> IVec2 {
+    IVec2::new(
+        (position.x / cell_size).floor() as i32,
+        (position.y / cell_size).floor() as i32,
+    )
+}
+
+fn emit_support_link(
+    buffer: &mut Vec<SupportLinkRenderable>,
+    start: Vec2,
+    end: Vec2,
+    color: Color,
+) {
+    buffer.push(SupportLinkRenderable {
+        start,
+        end,
+        color: color.with_alpha(0.85),
+        thickness: 2.6,
+    });
+    buffer.push(SupportLinkRenderable {
+        start,
+        end,
+        color: color.with_alpha(0.3),
+        thickness: 4.4,
+    });
+}
+
+/// What [`update_event_feed`] saw last frame.
+#[derive(Default)]
+struct FeedCursor {
+    combat: u64,
+    owners: HashMap<UnitId, PlayerId>,
+    alive: Vec<PlayerId>,
+    pylons: BTreeMap<Entity, Option<TeamId>>,
+    outcome: GameOutcome,
+}
+
+/// Appends to the [`EventFeed`] whatever changed since the last frame: new
+/// kills from the [`CombatLog`], then pylon captures, eliminations, and the
+/// match result.
+fn update_event_feed(
+    tick: Res<SimulationTick>,
+    log: Res<CombatLog>,
+    outcome: Res<GameOutcome>,
+    units: Query<(&UnitId, &Unit)>,
+    pylons: Query<(Entity, &Pylon)>,
+    mut feed: ResMut<EventFeed>,
+    mut seen: Local<FeedCursor>,
+) {
+    for event in log.events_since(seen.combat) {
+        if let CombatEvent::Kill {
+            tick,
+            attacker,
+            target,
+        } = *event
+        {
+            let owner = seen.owners.remove(&target);
+            feed.push(
+                tick,
+                FeedEvent::UnitDestroyed {
+                    unit: target,
+                    owner,
+                    by: attacker,
+                },
+            );
+        }
+    }
+    seen.combat = log.total_recorded();
+
+    for (entity, pylon) in &pylons {
+        let previous = seen.pylons.insert(entity, pylon.owner);
+        if let (Some(previous), Some(team)) = (previous, pylon.owner) {
+            if previous != Some(team) {
+                feed.push(tick.0, FeedEvent::PylonCaptured(team));
+            }
+        }
+    }
+
+    let mut alive: Vec<PlayerId> = Vec::new();
+    for (&id, unit) in &units {
+        seen.owners.insert(id, unit.player);
+        if !alive.contains(&unit.player) {
+            alive.push(unit.player);
+        }
+    }
+    alive.sort_by_key(|player| player.0);
+    for player in &seen.alive {
+        if !alive.contains(player) {
+            feed.push(tick.0, FeedEvent::PlayerEliminated(*player));
+        }
+    }
+    seen.alive = alive;
+
+    if *outcome != seen.outcome {
+        seen.outcome = *outcome;
+        if *outcome != GameOutcome::InProgress {
+            feed.push(tick.0, FeedEvent::MatchDecided(*outcome));
+        }
+    }
+}
+
+fn toggle_debug_overlays(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut overlays: ResMut<DebugOverlays>,
+) {
+    if keys.is_some_and(|keys| keys.just_pressed(RANGE_OVERLAY_KEY)) {
+        overlays.ranges = !overlays.ranges;
+    }
+}
+
+#[cfg(feature = "render")]
+fn draw_range_overlays(
+    overlays: Res<DebugOverlays>,
+    control: Res<ControlSettings>,
+    units: Query<(&Transform, &Unit)>,
+    mut gizmos: Gizmos,
+) {
+    if !overlays.ranges {
+        return;
+    }
+    for (transform, unit) in units.iter() {
+        if unit.player != control.local_player {
+            continue;
+        }
+        let center = Isometry2d::from_translation(transform.translation.truncate());
+        gizmos.circle_2d(center, LASER_RANGE, LASER_RANGE_OVERLAY_COLOR);
+        gizmos.circle_2d(center, LASER_HEAL_RANGE, HEAL_RANGE_OVERLAY_COLOR);
+    }
+}
+
+fn render_support_links(
+    mut commands: Commands,
+    buffer: Res<SupportLinkBuffer>,
+    render_effects: Res<RenderEffects>,
+    mut pool: ResMut<SupportLinkPool>,
+    mut visuals: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<SupportLinkVisual>>,
+) {
+    if !render_effects.0 {
+        return;
+    }
+    for (idx, link) in buffer.links.iter().enumerate() {
+        if idx >= pool.entities.len() {
+            let entity = spawn_link_visual(&mut commands, link);
+            pool.entities.push(entity);
+            continue;
+        }
+
+        let entity = pool.entities[idx];
+        if let Ok((mut transform, mut sprite, mut visibility)) = visuals.get_mut(entity) {
+            configure_link_visual(&mut transform, &mut sprite, &mut visibility, link);
+        } else {
+            let entity = spawn_link_visual(&mut commands, link);
+            pool.entities[idx] = entity;
+        }
+    }
+
+    for idx in buffer.links.len()..pool.entities.len() {
+        if let Ok((_, _, mut visibility)) = visuals.get_mut(pool.entities[idx]) {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn spawn_link_visual(commands: &mut Commands, link: &SupportLinkRenderable) -> Entity {
+    let diff = link.end - link.start;
+    let length = diff.length().max(1.0);
+    let midpoint = (link.start + link.end) * 0.5;
+    let angle = diff.y.atan2(diff.x);
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: link.color,
+                    custom_size: Some(Vec2::new(length, link.thickness)),
+                    ..default()
+                },
+                transform: Transform {
+                    translation: Vec3::new(midpoint.x, midpoint.y, 0.6),
+                    rotation: Quat::from_rotation_z(angle),
+                    ..default()
+                },
+                ..default()
+            },
+            SupportLinkVisual,
+        ))
+        .id()
+}
+
+fn configure_link_visual(
+    transform: &mut Transform,
+    sprite: &mut Sprite,
+    visibility: &mut Visibility,
+    link: &SupportLinkRenderable,
+) {
+    let diff = link.end - link.start;
+    let length = diff.length().max(1.0);
+    let midpoint = (link.start + link.end) * 0.5;
+    let angle = diff.y.atan2(diff.x);
+    transform.translation = Vec3::new(midpoint.x, midpoint.y, 0.6);
+    transform.rotation = Quat::from_rotation_z(angle);
+    transform.scale = Vec3::ONE;
+    sprite.custom_size = Some(Vec2::new(length, link.thickness));
+    sprite.color = link.color;
+    *visibility = Visibility::Visible;
+}
+
+fn update_beam_effects(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut beams: Query<(Entity, &mut BeamEffect)>,
+) {
+    for (entity, mut effect) in beams.iter_mut() {
+        if effect.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Resources [`detect_game_outcome`] reads to tell which players with a
+/// spawn have yet to field their first unit.
+#[derive(SystemParam)]
+struct UnfieldedArmies<'w, 's> {
+    balance: Res<'w, BalanceSettings>,
+    economy: Res<'w, Economy>,
+    stats: Res<'w, MatchStats>,
+    registry: Res<'w, SpawnRegistry>,
+    teams: Res<'w, Teams>,
+    pylons: Query<'w, 's, &'static Pylon>,
+}
+
+impl UnfieldedArmies<'_, '_> {
+    /// Players with a spawn who haven't lost a unit yet, and so have never
+    /// fielded one, and who can afford a reinforcement now or will earn one,
+    /// counting the income from pylons their team owns.
+    fn players(&self) -> impl Iterator<Item = PlayerId> + '_ {
+        let balance = &self.balance;
+        self.registry
+            .entries
+            .iter()
+            .map(|entry| entry.player)
+            .filter(|&player| self.stats.player(player).losses == 0)
+            .filter(move |&player| {
+                let team = self.teams.team_of(player);
+                let owned = self
+                    .pylons
+                    .iter()
+                    .filter(|pylon| pylon.owner == Some(team))
+                    .count();
+                !balance.economy_enabled
+                    || balance.income_rate(owned) > 0.0
+                    || self.economy.balance(player) >= balance.unit_cost
+            })
+    }
+}
+
+fn detect_game_outcome(
+    mut outcome: ResMut<GameOutcome>,
+    tick: Res<SimulationTick>,
+    settings: Res<BoardSettings>,
+    teams: Res<Teams>,
+    unfielded: UnfieldedArmies,
+    units: Query<&Unit>,
+) {
+    if *outcome != GameOutcome::InProgress {
+        return;
+    }
+    let mut alive: HashSet<PlayerId> = units.iter().map(|unit| unit.player).collect();
+    // A player who has never fielded a unit isn't out while reinforcements
+    // will still bring their first units; with none due (reinforcements off,
+    // or an economy that can never pay for one) an empty army is eliminated.
+    if settings.reinforcements {
+        alive.extend(unfielded.players());
+    }
+    let alive_teams: HashSet<TeamId> = alive.iter().map(|player| teams.team_of(*player)).collect();
+    let mut alive = alive.into_iter();
+    *outcome = match (alive.next(), alive.next()) {
+        (None, _) => GameOutcome::Draw,
+        (Some(player), None) => GameOutcome::Victory(player),
+        (Some(player), Some(_)) if alive_teams.len() == 1 => {
+            GameOutcome::TeamVictory(teams.team_of(player))
+        }
+        _ if settings.match_time_up(tick.0) => GameOutcome::Draw,
+        _ => return,
+    };
+    info!(target: "gameplay", "Match decided: {:?}", *outcome);
+}
+
+/// Pays every player with a spawn their income for this tick, plus the pylon
+/// bonus for each pylon their team owns.
+fn accrue_resources(
+    params: Res<SimulationParams>,
+    balance: Res<BalanceSettings>,
+    teams: Res<Teams>,
+    registry: Res<SpawnRegistry>,
+    pylons: Query<&Pylon>,
+    mut economy: ResMut<Economy>,
+) {
+    if !balance.economy_enabled {
+        return;
+    }
+    let step = params.fixed_delta as f32;
+    for entry in registry.entries.iter() {
+        let team = teams.team_of(entry.player);
+        let owned = pylons
+            .iter()
+            .filter(|pylon| pylon.owner == Some(team))
+            .count();
+        economy.deposit(entry.player, balance.income_rate(owned) * step);
+    }
+}
+
+fn decay_player_health(
+    params: Res<SimulationParams>,
+    balance: Res<BalanceSettings>,
+    mut health: ResMut<PlayerHealth>,
+) {
+    let _span = info_span!("gameplay.health_decay").entered();
+    let amount = balance.health_decay_per_second * params.fixed_delta as f32;
+    health.value.damage(amount);
+}
+
+fn record_previous_positions(mut units: Query<&mut SimulationPosition>) {
+    for mut position in units.iter_mut() {
+        position.previous = position.current;
+    }
+}
+
+/// Places and rotates each unit's sprite from its [`SimulationPosition`] and
+/// facing: `overstep_fraction` of the way from its previous to its current
+/// tick position with [`RenderInterpolation`] on, at the current one
+/// otherwise.
+fn sync_unit_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    interpolation: Res<RenderInterpolation>,
+    mut units: Query<(&mut Transform, &SimulationPosition, &Unit)>,
+) {
+    let alpha = if interpolation.0 {
+        fixed_time.overstep_fraction()
+    } else {
+        1.0
+    };
+    for (mut transform, position, unit) in units.iter_mut() {
+        let drawn = position.interpolated(alpha);
+        transform.translation.x = drawn.x;
+        transform.translation.y = drawn.y;
+        transform.rotation = Quat::from_rotation_z(unit.facing - FRAC_PI_2);
+    }
+}
+
+/// Places pylon sprites from their [`SimulationPosition`] like
+/// [`sync_unit_transforms`] does for units.
+fn sync_pylon_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    interpolation: Res<RenderInterpolation>,
+    mut pylons: Query<(&mut Transform, &SimulationPosition), With<Pylon>>,
+) {
+    let alpha = if interpolation.0 {
+        fixed_time.overstep_fraction()
+    } else {
+        1.0
+    };
+    for (mut transform, position) in pylons.iter_mut() {
+        let drawn = position.interpolated(alpha);
+        transform.translation.x = drawn.x;
+        transform.translation.y = drawn.y;
+    }
+}
+
+fn advance_simulation_tick(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
+
+fn configure_fixed_time(mut fixed_time: ResMut<Time<Fixed>>, params: Res<SimulationParams>) {
+    fixed_time.set_timestep_seconds(params.fixed_delta);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A full-health Laser standing at `position`, rallying where it stands.
+    /// Override fields with struct-update syntax.
+    fn test_unit(player: PlayerId, position: Vec2) -> (Unit, SimulationPosition) {
+        let unit = Unit {
+            player,
+            rally_target: position,
+            waypoints: VecDeque::new(),
+            kind: UnitKind::Laser,
+            health: UnitKind::Laser.health(),
+            max_health: UnitKind::Laser.health(),
+            attack_timer: Timer::from_seconds(LASER_COOLDOWN, TimerMode::Repeating),
+            cooldown_scale: 1.0,
+            velocity: Vec2::ZERO,
+            base_color: Color::WHITE,
+            boost_visual: None,
+            kills: 0,
+            rank: 0,
+            last_combat_tick: 0,
+            last_hit_tick: None,
+            suspended_order: None,
+            holding: false,
+            supplied_until_tick: None,
+            supply_multiplier: 1.0,
+            facing: FRAC_PI_2,
+            aim: None,
+            current_target: None,
+            target_out_of_range_since: None,
+        };
+        (unit, SimulationPosition::new(position))
+    }
+
+    #[test]
+    fn spawn_registry_matches_player_count() {
+        let mut app = App::new();
+        app.insert_resource(BoardSettings {
+            player_count: 3,
+            ..Default::default()
+        });
+        app.add_systems(Startup, setup_board);
+        app.update();
+        let registry = app.world().resource::<SpawnRegistry>();
+        assert_eq!(registry.entries.len(), 3);
+    }
+
+    #[test]
+    fn sprites_follow_simulation_positions_without_feeding_back() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let position = SimulationPosition {
+            previous: Vec2::ZERO,
+            current: Vec2::new(10.0, 0.0),
+        };
+        assert_eq!(position.interpolated(0.25), Vec2::new(2.5, 0.0));
+
+        let mut app = App::new();
+        app.insert_resource(BoardSettings {
+            reinforcements: false,
+            pylon_count: 0,
+            ..Default::default()
+        });
+        app.insert_resource(bevy::diagnostic::DiagnosticsStore::default());
+        crate::scenario::ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+            .build(&mut app);
+        app.add_plugins((MinimalPlugins, crate::CoreGamePlugin));
+        app.update();
+        let world = app.world_mut();
+        let unit = world.query_filtered::<Entity, With<Unit>>().single(world);
+        issue_order(world, &[unit], Order::Move(Vec2::new(500.0, 0.0)));
+        let tick = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(std::time::Duration::from_millis(100));
+            crate::run_simulation_tick(app);
+        };
+        for _ in 0..3 {
+            tick(&mut app);
+        }
+        let moved = *app.world().get::<SimulationPosition>(unit).unwrap();
+        assert!(moved.current.x > moved.previous.x && moved.previous.x > 0.0);
+
+        // The simulation neither writes the sprite nor reads it back.
+        let world = app.world_mut();
+        assert_eq!(world.get::<Transform>(unit).unwrap().translation.x, 0.0);
+        world.get_mut::<Transform>(unit).unwrap().translation.x = -999.0;
+        tick(&mut app);
+        let next = *app.world().get::<SimulationPosition>(unit).unwrap();
+        assert_eq!(next.previous, moved.current);
+        assert!(next.current.x > moved.current.x);
+
+        // No overstep yet, so the sprite is drawn at the previous position,
+        // or at the current one without interpolation.
+        let world = app.world_mut();
+        world.insert_resource(Time::<Fixed>::default());
+        world.run_system_once(sync_unit_transforms).unwrap();
+        let transform = *world.get::<Transform>(unit).unwrap();
+        assert_eq!(transform.translation.truncate(), next.previous);
+        assert_eq!(transform.translation.z, 0.2);
+        let facing = world.get::<Unit>(unit).unwrap().facing;
+        assert_eq!(
+            transform.rotation,
+            Quat::from_rotation_z(facing - FRAC_PI_2)
+        );
+        world.insert_resource(RenderInterpolation(false));
+        world.run_system_once(sync_unit_transforms).unwrap();
+        assert_eq!(
+            world.get::<Transform>(unit).unwrap().translation.truncate(),
+            next.current
+        );
+    }
+
+    #[test]
+    fn portable_exp_tracks_exp_with_pinned_bits() {
+        for x in [-9.5f32, -3.0, -0.5, -0.01, 0.0, 0.25, 2.0] {
+            let expected = x.exp();
+            let relative = ((portable_exp(x) - expected) / expected).abs();
+            assert!(
+                relative < 1e-6,
+                "x = {x}: {} vs {expected}",
+                portable_exp(x)
+            );
+        }
+        // Smoothing factor at the default 30 Hz step. The bits must not
+        // change across platforms or releases.
+        let step = SimulationParams::default().fixed_delta as f32;
+        assert_eq!(
+            (1.0 - portable_exp(-UNIT_ACCELERATION * step)).to_bits(),
+            0x3e6f_b07c
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn command_keys_stop_or_hold_the_selection_with_remappable_bindings() {
+        let mut app = App::new();
+        app.insert_resource(ControlSettings {
+            hold_key: KeyCode::KeyG,
+            ..Default::default()
+        });
+        app.init_resource::<ButtonInput<KeyCode>>();
+        let mut spawn = |x: f32| {
+            let (mut unit, position) = test_unit(PlayerId(0), Vec2::new(x, 0.0));
+            unit.order_move(Vec2::new(500.0, 0.0));
+            unit.queue_move(Vec2::new(500.0, 500.0));
+            app.world_mut().spawn((unit, position)).id()
+        };
+        let (selected, other) = (spawn(10.0), spawn(20.0));
+        app.insert_resource(SelectionState {
+            selected: vec![selected],
+            ..Default::default()
+        });
+        app.add_systems(Update, issue_command_keys);
+
+        let press = |app: &mut App, key: KeyCode| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.clear();
+            keys.press(key);
+            app.update();
+        };
+        press(&mut app, KeyCode::KeyH);
+        assert!(!app.world().get::<Unit>(selected).unwrap().holding);
+        press(&mut app, KeyCode::KeyG);
+        let unit = app.world().get::<Unit>(selected).unwrap();
+        assert!(unit.holding);
+        assert_eq!(unit.rally_target, Vec2::new(10.0, 0.0));
+        assert!(unit.waypoints.is_empty());
+
+        press(&mut app, KeyCode::KeyS);
+        let unit = app.world().get::<Unit>(selected).unwrap();
+        assert!(!unit.holding);
+        assert_eq!(unit.rally_target, Vec2::new(10.0, 0.0));
+        let untouched = app.world().get::<Unit>(other).unwrap();
+        assert_eq!(untouched.rally_target, Vec2::new(500.0, 0.0));
+        assert_eq!(untouched.waypoints.len(), 1);
+    }
 
     #[test]
-    fn spawn_registry_matches_player_count() {
+    fn number_keys_switch_local_player_within_player_count() {
         let mut app = App::new();
         app.insert_resource(BoardSettings {
             player_count: 3,
             ..Default::default()
         });
-        app.add_systems(Startup, setup_board);
+        app.init_resource::<ControlSettings>();
+        app.init_resource::<ButtonInput<KeyCode>>();
+        let selected = app.world_mut().spawn_empty().id();
+        app.insert_resource(SelectionState {
+            selected: vec![selected],
+            ..Default::default()
+        });
+        app.add_systems(Update, switch_local_player);
+
+        let press = |app: &mut App, key: KeyCode| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.clear();
+            keys.press(key);
+            app.update();
+            app.world_mut()
+                .resource_mut::<ButtonInput<KeyCode>>()
+                .release(key);
+        };
+
+        press(&mut app, KeyCode::Digit2);
+        assert_eq!(
+            app.world().resource::<ControlSettings>().local_player,
+            PlayerId(1)
+        );
+        let selection = app.world().resource::<SelectionState>();
+        assert!(selection.selected.is_empty());
+        assert_eq!(selection.prev_selected, vec![selected]);
+        assert!(selection.dirty);
+
+        press(&mut app, KeyCode::Digit8);
+        assert_eq!(
+            app.world().resource::<ControlSettings>().local_player,
+            PlayerId(2)
+        );
+
+        // Shift picks from the second bank of eight.
+        app.world_mut().resource_mut::<BoardSettings>().player_count = MAX_PLAYERS;
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ShiftLeft);
+        let shifted = |app: &mut App, key: KeyCode| {
+            press(app, key);
+            app.world().resource::<ControlSettings>().local_player
+        };
+        assert_eq!(shifted(&mut app, KeyCode::Digit1), PlayerId(8));
+        assert_eq!(shifted(&mut app, KeyCode::Digit8), PlayerId(15));
+    }
+
+    #[test]
+    fn range_overlay_key_toggles_overlay() {
+        let mut app = App::new();
+        app.init_resource::<DebugOverlays>();
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.add_systems(Update, toggle_debug_overlays);
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(RANGE_OVERLAY_KEY);
         app.update();
-        let registry = app.world().resource::<SpawnRegistry>();
-        assert_eq!(registry.entries.len(), 3);
+        assert!(app.world().resource::<DebugOverlays>().ranges);
+
+        // Held, not re-pressed: stays on.
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.update();
+        assert!(app.world().resource::<DebugOverlays>().ranges);
+    }
+
+    #[test]
+    fn out_of_range_player_count_is_clamped() {
+        let mut app = App::new();
+        app.insert_resource(BoardSettings {
+            player_count: 99,
+            ..Default::default()
+        });
+        app.init_resource::<SimulationRng>();
+        app.init_resource::<SpawnCompositionRng>();
+        app.init_resource::<NextUnitId>();
+        app.add_systems(
+            Startup,
+            (setup_board, spawn_initial_units.after(setup_board)),
+        );
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<SpawnRegistry>().entries.len(),
+            MAX_PLAYERS
+        );
+        assert_eq!(
+            app.world().resource::<BoardSettings>().player_count,
+            MAX_PLAYERS
+        );
+    }
+
+    #[test]
+    fn player_colors_are_distinct_and_stable() {
+        let colors: Vec<Color> = (0..12).map(|idx| player_color(PlayerId(idx))).collect();
+        assert_eq!(colors[..8], CURATED_PLAYER_COLORS);
+        assert_eq!(colors[0], Color::srgb(0.93, 0.26, 0.28));
+        assert_eq!(colors[7], Color::srgb(0.93, 0.36, 0.6));
+        let again: Vec<Color> = (0..12).map(|idx| player_color(PlayerId(idx))).collect();
+        assert_eq!(colors, again);
+
+        let rgb: Vec<Vec3> = (0..MAX_PLAYERS)
+            .map(|idx| {
+                Vec3::from_slice(&player_color(PlayerId(idx)).to_srgba().to_f32_array()[..3])
+            })
+            .collect();
+        for (idx, color) in rgb.iter().enumerate() {
+            for (other_idx, other) in rgb.iter().enumerate().skip(idx + 1) {
+                assert!(
+                    color.distance(*other) > 0.15,
+                    "players {idx} and {other_idx} look alike: {color} vs {other}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pylon_income_keeps_an_unfielded_player_in_the_match() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.insert_resource(BalanceSettings {
+            economy_enabled: true,
+            income_per_second: 0.0,
+            pylon_income_per_second: 2.0,
+            ..Default::default()
+        });
+        world.insert_resource(BoardSettings::default());
+        world.insert_resource(SpawnRegistry {
+            entries: [PlayerId(0), PlayerId(1)]
+                .map(|player| SpawnEntry {
+                    player,
+                    position: Vec2::ZERO,
+                })
+                .to_vec(),
+        });
+        world.init_resource::<GameOutcome>();
+        world.init_resource::<SimulationTick>();
+        world.init_resource::<Teams>();
+        world.init_resource::<Economy>();
+        world.init_resource::<MatchStats>();
+        world.spawn(test_unit(PlayerId(0), Vec2::ZERO));
+        let pylon = world
+            .spawn(Pylon {
+                velocity: Vec2::ZERO,
+                mass: 1.0,
+                health: DEFAULT_PYLON_MAX_HEALTH,
+                owner: Some(TeamId(1)),
+                control: None,
+            })
+            .id();
+
+        // Player 1 has no units and no money, but their pylon will pay for one.
+        world.run_system_once(detect_game_outcome).unwrap();
+        assert_eq!(*world.resource::<GameOutcome>(), GameOutcome::InProgress);
+
+        world.get_mut::<Pylon>(pylon).unwrap().owner = None;
+        world.run_system_once(detect_game_outcome).unwrap();
+        assert_eq!(
+            *world.resource::<GameOutcome>(),
+            GameOutcome::Victory(PlayerId(0))
+        );
+    }
+
+    #[test]
+    fn units_ordered_off_map_stop_at_boundary() {
+        let settings = BoardSettings {
+            board_size: 400.0,
+            ..Default::default()
+        };
+        let boundary = settings.playfield_half_extent();
+        let mut app = App::new();
+        app.insert_resource(settings);
+        app.init_resource::<Time>();
+        app.add_systems(Update, move_units);
+        let (unit, position) = test_unit(PlayerId(0), Vec2::ZERO);
+        let unit = app
+            .world_mut()
+            .spawn((
+                position,
+                Unit {
+                    rally_target: Vec2::new(5000.0, -5000.0),
+                    ..unit
+                },
+            ))
+            .id();
+
+        for _ in 0..300 {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(std::time::Duration::from_millis(50));
+            app.update();
+        }
+
+        let pos = app.world().get::<SimulationPosition>(unit).unwrap().current;
+        assert_eq!((pos.x, pos.y), (boundary, -boundary));
+    }
+
+    #[test]
+    fn reinforcement_count_follows_tick_count() {
+        let settings = BoardSettings {
+            player_count: 3,
+            spawn_interval: 0.5,
+            ..Default::default()
+        };
+        let params = SimulationParams::from_seed(7);
+        let period = spawn_period_ticks(&settings, &params);
+        assert_eq!(period, 15);
+
+        let mut app = App::new();
+        app.insert_resource(settings);
+        app.insert_resource(params);
+        app.init_resource::<SimulationRng>();
+        app.init_resource::<SpawnCompositionRng>();
+        app.init_resource::<NextUnitId>();
+        app.init_resource::<SimulationTick>();
+        app.add_systems(
+            Startup,
+            (setup_board, spawn_initial_units.after(setup_board)),
+        );
+        app.add_systems(
+            Update,
+            (
+                advance_simulation_tick,
+                spawn_reinforcements.after(advance_simulation_tick),
+            ),
+        );
+        for _ in 0..100 {
+            app.update();
+        }
+
+        let units = app.world_mut().query::<&Unit>().iter(app.world()).count();
+        assert_eq!(units, 3 * 2 + 3 * (100 / period as usize));
+    }
+
+    #[test]
+    fn reinforcements_stop_at_unit_cap() {
+        let mut app = App::new();
+        app.insert_resource(BoardSettings {
+            player_count: 3,
+            spawn_interval: 0.5,
+            max_units_per_player: 4,
+            ..Default::default()
+        });
+        app.insert_resource(SimulationParams::from_seed(7));
+        app.init_resource::<SimulationRng>();
+        app.init_resource::<SpawnCompositionRng>();
+        app.init_resource::<NextUnitId>();
+        app.init_resource::<SimulationTick>();
+        app.add_systems(
+            Startup,
+            (setup_board, spawn_initial_units.after(setup_board)),
+        );
+        app.add_systems(
+            Update,
+            (
+                advance_simulation_tick,
+                spawn_reinforcements.after(advance_simulation_tick),
+            ),
+        );
+        for _ in 0..100 {
+            app.update();
+        }
+
+        let mut per_player = [0usize; 3];
+        for unit in app.world_mut().query::<&Unit>().iter(app.world()) {
+            per_player[unit.player.0] += 1;
+        }
+        assert_eq!(per_player, [4; 3]);
+        assert!(!BoardSettings {
+            max_units_per_player: 0,
+            ..Default::default()
+        }
+        .at_unit_cap(10_000));
+    }
+
+    #[test]
+    fn outcome_waits_for_players_yet_to_field_a_unit() {
+        let run = |ticks: usize, layout: crate::scenario::ScenarioBuilder| {
+            let mut app = App::new();
+            app.insert_resource(BoardSettings {
+                spawn_interval: 0.5,
+                pylon_count: 0,
+                ..Default::default()
+            });
+            app.insert_resource(bevy::diagnostic::DiagnosticsStore::default());
+            layout.player_count(2).build(&mut app);
+            app.add_plugins((MinimalPlugins, crate::CoreGamePlugin));
+            app.update();
+            for _ in 0..ticks {
+                crate::run_simulation_tick(&mut app);
+            }
+            let world = app.world_mut();
+            let mut per_player = [0usize; 2];
+            for unit in world.query::<&Unit>().iter(world) {
+                per_player[unit.player.0] += 1;
+            }
+            (*world.resource::<GameOutcome>(), per_player)
+        };
+
+        // An empty board isn't a draw before the first wave arrives.
+        let empty = crate::scenario::ScenarioBuilder::new;
+        assert_eq!(run(1, empty()), (GameOutcome::InProgress, [0, 0]));
+        assert_eq!(run(15, empty()), (GameOutcome::InProgress, [1, 1]));
+
+        // Player 1 hasn't fielded a unit yet, so player 0 hasn't won.
+        let head_start = || {
+            crate::scenario::ScenarioBuilder::new().unit(
+                PlayerId(0),
+                UnitKind::Laser,
+                Vec2::new(200.0, 0.0),
+            )
+        };
+        assert_eq!(run(14, head_start()), (GameOutcome::InProgress, [1, 0]));
+        assert_eq!(run(15, head_start()), (GameOutcome::InProgress, [2, 1]));
+    }
+
+    #[test]
+    fn beam_colors_follow_the_firing_player() {
+        let colors: Vec<Color> = (0..MAX_PLAYERS)
+            .map(|idx| player_color(PlayerId(idx)))
+            .collect();
+        let beams: Vec<Color> = colors.iter().map(|&color| beam_color(color)).collect();
+        for (idx, beam) in beams.iter().enumerate() {
+            assert_ne!(*beam, colors[idx]);
+            assert!(beams[idx + 1..].iter().all(|other| other != beam));
+        }
+    }
+
+    #[test]
+    fn selection_modes_replace_add_and_subtract() {
+        let [a, b, c] = [1, 2, 3].map(Entity::from_raw);
+        let current = [a, b];
+        assert_eq!(SelectionMode::Replace.merge(&current, vec![c]), vec![c]);
+        assert!(SelectionMode::Replace
+            .merge(&current, Vec::new())
+            .is_empty());
+        assert_eq!(
+            SelectionMode::Add.merge(&current, vec![c, a]),
+            vec![a, b, c]
+        );
+        assert_eq!(SelectionMode::Add.merge(&current, Vec::new()), vec![a, b]);
+        assert_eq!(SelectionMode::Subtract.merge(&current, vec![a, c]), vec![b]);
+
+        let mut keys = ButtonInput::<KeyCode>::default();
+        assert_eq!(
+            SelectionMode::from_keys(Some(&keys)),
+            SelectionMode::Replace
+        );
+        keys.press(KeyCode::ShiftLeft);
+        assert_eq!(SelectionMode::from_keys(Some(&keys)), SelectionMode::Add);
+        keys.press(KeyCode::ControlRight);
+        assert_eq!(
+            SelectionMode::from_keys(Some(&keys)),
+            SelectionMode::Subtract
+        );
+        assert_eq!(SelectionMode::from_keys(None), SelectionMode::Replace);
+    }
+
+    #[test]
+    fn formation_targets_ring_the_anchor_in_unit_order() {
+        let anchor = Vec2::new(100.0, -50.0);
+        let units: Vec<Entity> = [5, 2, 9, 1, 7, 3, 8, 4].map(Entity::from_raw).to_vec();
+        let targets = assign_formation_targets(anchor, &units, FormationKind::Rings);
+
+        let assigned: Vec<Entity> = targets.iter().map(|(entity, _)| *entity).collect();
+        assert_eq!(assigned, units);
+        assert_eq!(targets[0].1, anchor);
+        for (_, target) in &targets[1..7] {
+            assert!((target.distance(anchor) - FORMATION_SPACING).abs() < 1e-3);
+        }
+        assert!((targets[1].1 - (anchor + Vec2::X * FORMATION_SPACING)).length() < 1e-3);
+        assert!((targets[7].1 - (anchor + Vec2::X * 2.0 * FORMATION_SPACING)).length() < 1e-3);
+        assert!(assign_formation_targets(anchor, &[], FormationKind::Rings).is_empty());
+    }
+
+    #[test]
+    fn targeting_policies_break_ties_deterministically() {
+        // Ids run opposite to entity indices, so an entity-index tie-break
+        // would pick differently.
+        let candidate = |index: u32, x: f32, health: f32, threat: f32| TargetCandidate {
+            entity: Entity::from_raw(index),
+            id: UnitId(u64::from(3 - index)),
+            position: Vec2::new(x, 0.0),
+            health,
+            threat,
+        };
+        let candidates = [
+            candidate(1, 100.0, 10.0, 5.0),
+            candidate(3, 100.0, 10.0, 5.0),
+            candidate(2, 200.0, 10.0, 9.0),
+            candidate(0, 900.0, 1.0, 99.0),
+        ];
+        let pick = |policy| {
+            select_target(policy, Vec2::ZERO, LASER_RANGE, candidates)
+                .map(|target| target.entity.index())
+        };
+        assert_eq!(pick(TargetingPolicy::Nearest), Some(3));
+        assert_eq!(pick(TargetingPolicy::LowestHealth), Some(3));
+        assert_eq!(pick(TargetingPolicy::HighestThreat), Some(2));
+    }
+
+    #[test]
+    fn grid_support_links_match_brute_force() {
+        let mut rng = StdRng::seed_from_u64(DEFAULT_SEED);
+        let snapshot: Vec<_> = (0..300)
+            .map(|idx| {
+                (
+                    Entity::from_raw(idx),
+                    UnitId(u64::from(299 - idx)),
+                    PlayerId(rng.gen_range(0..4)),
+                    Vec2::new(rng.gen_range(-800.0..800.0), rng.gen_range(-800.0..800.0)),
+                )
+            })
+            .collect();
+        let teams = Teams::from_alliances(&[vec![0, 2]]);
+
+        // Ids run opposite to entity indices; links are ordered by id.
+        let by_id: Vec<_> = snapshot.iter().rev().copied().collect();
+        let mut brute_force = Vec::new();
+        for (i, &(entity_a, _, player_a, pos_a)) in by_id.iter().enumerate() {
+            for &(entity_b, _, player_b, pos_b) in &by_id[i + 1..] {
+                if teams.allied(player_a, player_b)
+                    && pos_a.distance_squared(pos_b) <= LASER_HEAL_RANGE * LASER_HEAL_RANGE
+                {
+                    brute_force.push((entity_a, entity_b));
+                }
+            }
+        }
+
+        let grid = build_support_links(&snapshot, &teams, LASER_HEAL_RANGE);
+        assert!(!grid.is_empty());
+        assert_eq!(grid, brute_force);
+    }
+
+    #[test]
+    fn supply_components_ignore_snapshot_order() {
+        let mut rng = StdRng::seed_from_u64(DEFAULT_SEED);
+        let mut snapshot: Vec<_> = (0..120)
+            .map(|idx| {
+                (
+                    Entity::from_raw(idx),
+                    UnitId(u64::from(idx)),
+                    PlayerId(rng.gen_range(0..2)),
+                    Vec2::new(rng.gen_range(-500.0..500.0), rng.gen_range(-500.0..500.0)),
+                )
+            })
+            .collect();
+        let spawns = [
+            SpawnEntry {
+                player: PlayerId(0),
+                position: Vec2::new(-300.0, 0.0),
+            },
+            SpawnEntry {
+                player: PlayerId(1),
+                position: Vec2::new(300.0, 0.0),
+            },
+        ];
+        let teams = Teams::default();
+        let components = |snapshot: &[(Entity, UnitId, PlayerId, Vec2)]| {
+            let mut adjacency: HashMap<Entity, Vec<Entity>> = HashMap::default();
+            for (a, b) in build_support_links(snapshot, &teams, LASER_HEAL_RANGE) {
+                adjacency.entry(a).or_default().push(b);
+                adjacency.entry(b).or_default().push(a);
+            }
+            collect_supply_components(snapshot, &adjacency, &spawns, &teams).1
+        };
+
+        let forward = components(&snapshot);
+        snapshot.reverse();
+        assert!(!forward.is_empty());
+        assert_eq!(forward, components(&snapshot));
+    }
+
+    #[test]
+    fn supply_dot_lists_linked_units() {
+        let mut app = App::new();
+        app.insert_resource(BoardSettings {
+            reinforcements: false,
+            ..Default::default()
+        });
+        app.insert_resource(bevy::diagnostic::DiagnosticsStore::default());
+        crate::scenario::ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(560.0, 0.0))
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(560.0, 100.0))
+            .unit(PlayerId(1), UnitKind::Laser, Vec2::new(-200.0, 0.0))
+            .unit(PlayerId(1), UnitKind::Laser, Vec2::new(560.0, -100.0))
+            .pylon(Vec2::new(0.0, 600.0))
+            .build(&mut app);
+        app.add_plugins((MinimalPlugins, crate::CoreGamePlugin));
+        app.update();
+        crate::run_simulation_tick(&mut app);
+
+        let world = app.world_mut();
+        let mut units: Vec<UnitId> = world
+            .query_filtered::<&UnitId, With<Unit>>()
+            .iter(world)
+            .copied()
+            .collect();
+        units.sort();
+        let [a, b, loner, intruder] = units[..] else {
+            panic!("expected four units, got {units:?}");
+        };
+
+        let dot = export_supply_dot(world);
+        assert!(dot.starts_with("graph supply {"), "{dot}");
+        assert!(dot.contains(&format!("u{} -- u{};", a.0, b.0)), "{dot}");
+        assert!(!dot.contains(&format!("u{} --", loner.0)), "{dot}");
+        assert!(!dot.contains(&format!("-- u{};", loner.0)), "{dot}");
+        assert!(dot.contains(&format!("u{} [label=\"{}\", shape=box", a.0, a.0)));
+        assert!(dot.contains(&format!(
+            "u{} [label=\"{}\", shape=circle",
+            loner.0, loner.0
+        )));
+        assert!(
+            dot.contains(&format!("spawn0 -- u{} [style=dashed];", a.0)),
+            "{dot}"
+        );
+        // An enemy standing on the spawn isn't seeded by it.
+        assert!(
+            !dot.contains(&format!("-- u{} [style=dashed]", intruder.0)),
+            "{dot}"
+        );
+        assert!(dot.contains("p0 [label=\"pylon\", shape=diamond"), "{dot}");
+        assert_eq!(dot.matches("shape=diamond").count(), 1);
+    }
+
+    #[test]
+    fn zero_pylon_count_disables_pylons() {
+        let mut app = App::new();
+        app.insert_resource(BoardSettings {
+            player_count: 2,
+            pylon_count: 0,
+            ..Default::default()
+        });
+        app.insert_resource(bevy::diagnostic::DiagnosticsStore::default());
+        app.add_plugins((MinimalPlugins, crate::CoreGamePlugin));
+        app.update();
+        for _ in 0..5 {
+            crate::run_simulation_tick(&mut app);
+        }
+
+        let world = app.world_mut();
+        assert_eq!(world.query::<&Pylon>().iter(world).count(), 0);
+        let units: Vec<Entity> = world
+            .query_filtered::<Entity, With<Unit>>()
+            .iter(world)
+            .collect();
+        let supply = world.resource::<SupplyState>();
+        assert!(supply.supplied_count() > 0);
+        assert!(units.iter().all(|unit| supply.pylon_bonus(*unit) == 0.0));
+    }
+
+    /// Two player 0 units beside a pylon on their spawn, and a lone player 1
+    /// unit out of range on the far spawn.
+    fn capture_app(capturable: bool) -> App {
+        let mut app = App::new();
+        app.insert_resource(BoardSettings {
+            reinforcements: false,
+            ..Default::default()
+        });
+        app.insert_resource(BalanceSettings {
+            capturable_pylons: capturable,
+            ..Default::default()
+        });
+        app.insert_resource(bevy::diagnostic::DiagnosticsStore::default());
+        crate::scenario::ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(520.0, 0.0))
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(600.0, 0.0))
+            .unit(PlayerId(1), UnitKind::Laser, Vec2::new(-560.0, 0.0))
+            .pylon(Vec2::new(560.0, 0.0))
+            .build(&mut app);
+        app.add_plugins((MinimalPlugins, crate::CoreGamePlugin));
+        app.update();
+        app
+    }
+
+    fn step(app: &mut App, ticks: usize) {
+        for _ in 0..ticks {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(std::time::Duration::from_millis(100));
+            crate::run_simulation_tick(app);
+        }
+    }
+
+    fn pylon(app: &mut App) -> (Option<TeamId>, Option<(TeamId, f32)>, f32) {
+        let world = app.world_mut();
+        let pylon = world.query::<&Pylon>().single(world);
+        (pylon.owner, pylon.control, pylon.health)
+    }
+
+    /// Pylon bonus of every living unit, in entity order.
+    fn pylon_bonuses(app: &mut App) -> Vec<(PlayerId, f32)> {
+        let world = app.world_mut();
+        let mut units: Vec<(Entity, PlayerId)> = world
+            .query::<(Entity, &Unit)>()
+            .iter(world)
+            .map(|(entity, unit)| (entity, unit.player))
+            .collect();
+        units.sort_by_key(|(entity, _)| *entity);
+        let supply = world.resource::<SupplyState>();
+        units
+            .into_iter()
+            .map(|(entity, player)| (player, supply.pylon_bonus(entity)))
+            .collect()
+    }
+
+    #[test]
+    fn lone_team_captures_a_pylon() {
+        let mut app = capture_app(true);
+        step(&mut app, 10);
+        let (owner, control, _) = pylon(&mut app);
+        assert_eq!(owner, None);
+        let (team, progress) = control.expect("capture under way");
+        assert_eq!(team, TeamId(0));
+        assert!(progress > 0.0 && progress < 1.0, "{progress}");
+        let bonuses = pylon_bonuses(&mut app);
+        assert!(
+            bonuses.iter().all(|&(_, bonus)| bonus == 0.0),
+            "{bonuses:?}"
+        );
+
+        step(&mut app, 40);
+        let (owner, control, health) = pylon(&mut app);
+        assert_eq!(owner, Some(TeamId(0)));
+        assert_eq!(control, None);
+        assert_eq!(health, DEFAULT_PYLON_MAX_HEALTH);
+        let bonuses = pylon_bonuses(&mut app);
+        assert!(
+            bonuses
+                .iter()
+                .all(|&(player, bonus)| (player == PlayerId(0)) == (bonus > 0.0)),
+            "{bonuses:?}"
+        );
+    }
+
+    #[test]
+    fn enemies_shoot_captured_pylons_back_to_neutral() {
+        let mut app = capture_app(true);
+        step(&mut app, 50);
+        assert_eq!(pylon(&mut app).0, Some(TeamId(0)));
+
+        // Swap the garrison for a player 1 squad next to the pylon.
+        let world = app.world_mut();
+        let garrison: Vec<Entity> = world
+            .query::<(Entity, &Unit)>()
+            .iter(world)
+            .filter(|(_, unit)| unit.player == PlayerId(0))
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in garrison {
+            world.despawn(entity);
+        }
+        let raider = world
+            .query::<(Entity, &Unit)>()
+            .iter(world)
+            .find(|(_, unit)| unit.player == PlayerId(1))
+            .map(|(entity, _)| entity)
+            .unwrap();
+        *world.get_mut::<SimulationPosition>(raider).unwrap() =
+            SimulationPosition::new(Vec2::new(420.0, 0.0));
+        world.get_mut::<Unit>(raider).unwrap().rally_target = Vec2::new(420.0, 0.0);
+
+        step(&mut app, 10);
+        let (owner, _, health) = pylon(&mut app);
+        assert_eq!(owner, Some(TeamId(0)));
+        assert!(health < DEFAULT_PYLON_MAX_HEALTH);
+
+        // The pylon drifts; keep the raider on it.
+        for _ in 0..60 {
+            let world = app.world_mut();
+            let target = world
+                .query_filtered::<&SimulationPosition, With<Pylon>>()
+                .single(world)
+                .current;
+            world.get_mut::<Unit>(raider).unwrap().order_move(target);
+            step(&mut app, 10);
+        }
+        let (owner, control, _) = pylon(&mut app);
+        assert_eq!((owner, control), (Some(TeamId(1)), None));
+    }
+
+    #[test]
+    fn pylons_drift_in_the_simulation_schedule() {
+        let mut app = capture_app(false);
+        let world = app.world_mut();
+        let (start, drawn) = world
+            .query_filtered::<(&SimulationPosition, &Transform), With<Pylon>>()
+            .single(world);
+        let (start, drawn) = (start.current, drawn.translation);
+
+        step(&mut app, 10);
+        let world = app.world_mut();
+        let (position, transform) = world
+            .query_filtered::<(&SimulationPosition, &Transform), With<Pylon>>()
+            .single(world);
+        assert_ne!(position.current, start);
+        // Only `Update` moves the sprite.
+        assert_eq!(transform.translation, drawn);
+    }
+
+    #[test]
+    fn pylons_stay_shared_without_the_flag() {
+        let mut app = capture_app(false);
+        step(&mut app, 50);
+        assert_eq!(pylon(&mut app), (None, None, DEFAULT_PYLON_MAX_HEALTH));
+    }
+
+    #[test]
+    fn pylon_layout_ignores_player_count_and_prior_draws() {
+        let pylons = |player_count: usize, prior_draws: usize| {
+            let mut app = App::new();
+            app.insert_resource(BoardSettings {
+                player_count,
+                ..Default::default()
+            });
+            let mut rng = SimulationRng::new(DEFAULT_SEED);
+            for _ in 0..prior_draws {
+                rng.gen_range(0..=100);
+            }
+            app.insert_resource(rng);
+            app.init_resource::<SpawnCompositionRng>();
+            app.init_resource::<NextUnitId>();
+            app.add_systems(
+                Startup,
+                (
+                    setup_board,
+                    spawn_initial_units.after(setup_board),
+                    spawn_pylons.after(setup_board),
+                ),
+            );
+            app.update();
+            let world = app.world_mut();
+            let mut positions: Vec<Vec3> = world
+                .query_filtered::<&Transform, With<Pylon>>()
+                .iter(world)
+                .map(|transform| transform.translation)
+                .collect();
+            positions.sort_by(|a, b| a.z.total_cmp(&b.z));
+            positions
+        };
+
+        let baseline = pylons(2, 0);
+        assert_eq!(baseline.len(), DEFAULT_PYLON_COUNT);
+        assert_eq!(baseline, pylons(6, 0));
+        assert_eq!(baseline, pylons(3, 17));
+    }
+
+    #[test]
+    fn even_weights_split_draws_reproducibly() {
+        let even = [(0usize, 1), (1, 1), (2, 0)];
+        let counts = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut counts = [0usize; 3];
+            for _ in 0..1000 {
+                counts[weighted_choice(&even, &mut rng).unwrap()] += 1;
+            }
+            counts
+        };
+
+        let [first, second, unweighted] = counts(DEFAULT_SEED);
+        assert_eq!(counts(DEFAULT_SEED), [first, second, unweighted]);
+        assert!((450..=550).contains(&first), "{first} of 1000");
+        assert_eq!((first + second, unweighted), (1000, 0));
+
+        let mut rng = StdRng::seed_from_u64(DEFAULT_SEED);
+        assert_eq!(weighted_choice(&[(0usize, 0)], &mut rng), None);
+        assert_eq!(weighted_choice::<usize>(&[], &mut rng), None);
+    }
+
+    #[test]
+    fn rng_fork_is_reproducible_and_leaves_parent_untouched() {
+        let sample =
+            |rng: &mut StdRng| -> Vec<u32> { (0..5).map(|_| rng.gen_range(0..1000)).collect() };
+
+        let mut a = SimulationRng::new(42);
+        let mut b = SimulationRng::new(42);
+        a.gen_range(1..=20);
+        b.gen_range(1..=20);
+        assert_eq!(sample(&mut a.fork("ai")), sample(&mut b.fork("ai")));
+
+        assert_ne!(
+            sample(&mut SimulationRng::new(42).fork("ai")),
+            sample(&mut SimulationRng::new(42).fork("pylons"))
+        );
+
+        let mut plain = SimulationRng::new(42);
+        let mut forked = SimulationRng::new(42);
+        let _ = forked.fork("ai");
+        assert_eq!(plain.gen_range(1..=1000), forked.gen_range(1..=1000));
     }
 }
@@ -1,7 +1,17 @@
 //! Core Bevy game plugin composed of gameplay, UI, and diagnostics modules.
+//!
+//! The `render` feature (on by default, via `native`/`wasm`) adds the HUD,
+//! camera, mouse input, and draw-only systems. Build with
+//! `default-features = false, features = ["headless"]` for the simulation
+//! alone; features are additive, so `render` wins when both are enabled.
 
+pub mod battle;
+pub mod config;
 pub mod diagnostics;
 pub mod gameplay;
+pub mod scenario;
+pub mod summary;
+#[cfg(feature = "render")]
 pub mod ui;
 
 use bevy::ecs::schedule::ScheduleLabel;
@@ -9,17 +19,33 @@ use bevy::prelude::*;
 
 /// Schedule dedicated to deterministic simulation. Rendering hooks
 /// run in the default `Update`/`PostUpdate` stages.
+///
+/// `CoreGamePlugin` runs it once per `FixedUpdate` step; tests call
+/// [`run_simulation_tick`] to advance it directly.
 #[derive(ScheduleLabel, Hash, Debug, PartialEq, Eq, Clone)]
 pub struct SimulationSchedule;
 
+/// Advances the simulation by exactly one tick.
+pub fn run_simulation_tick(app: &mut App) {
+    app.world_mut().run_schedule(SimulationSchedule);
+}
+
+fn drive_simulation_schedule(world: &mut World) {
+    world.run_schedule(SimulationSchedule);
+}
+
 pub struct CoreGamePlugin;
 
 impl Plugin for CoreGamePlugin {
     fn build(&self, app: &mut App) {
+        app.init_schedule(SimulationSchedule)
+            .add_systems(FixedUpdate, drive_simulation_schedule);
         app.add_plugins((
             gameplay::GameplayPlugin,
-            ui::UiPlugin,
             diagnostics::DiagnosticsPlugin,
+            summary::MatchSummaryPlugin,
         ));
+        #[cfg(feature = "render")]
+        app.add_plugins(ui::UiPlugin);
     }
 }
@@ -0,0 +1,86 @@
+//! Declarative starting layouts for tests and sandboxes.
+//!
+//! ```ignore
+//! ScenarioBuilder::new()
+//!     .board_size(900.0)
+//!     .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-120.0, 40.0))
+//!     .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-120.0, -40.0))
+//!     .unit(PlayerId(1), UnitKind::Laser, Vec2::new(120.0, 0.0))
+//!     .pylon(Vec2::ZERO)
+//!     .build(&mut app);
+//! ```
+
+use bevy::prelude::*;
+
+use crate::gameplay::{BoardSettings, InitialLayout, PlayerId, UnitKind};
+
+/// Exact unit and pylon placements, replacing the automatic radial spawns.
+///
+/// Only the declared units and pylons exist at startup. Unless set with
+/// [`Self::player_count`], the board gets exactly as many players as the
+/// placements reference (at least [`MIN_PLAYERS`](crate::gameplay::MIN_PLAYERS)).
+#[derive(Clone, Debug, Default)]
+pub struct ScenarioBuilder {
+    board_size: Option<f32>,
+    player_count: Option<usize>,
+    units: Vec<(PlayerId, UnitKind, Vec2)>,
+    pylons: Vec<Vec2>,
+}
+
+impl ScenarioBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn board_size(mut self, board_size: f32) -> Self {
+        self.board_size = Some(board_size);
+        self
+    }
+
+    pub fn player_count(mut self, player_count: usize) -> Self {
+        self.player_count = Some(player_count);
+        self
+    }
+
+    pub fn unit(mut self, player: PlayerId, kind: UnitKind, position: Vec2) -> Self {
+        self.units.push((player, kind, position));
+        self
+    }
+
+    pub fn pylon(mut self, position: Vec2) -> Self {
+        self.pylons.push(position);
+        self
+    }
+
+    /// Writes the board overrides into `board` and returns the layout that
+    /// `Startup` will spawn.
+    pub fn apply(self, board: &mut BoardSettings) -> InitialLayout {
+        if let Some(board_size) = self.board_size {
+            board.board_size = board_size;
+        }
+        let referenced = self
+            .units
+            .iter()
+            .map(|(player, _, _)| player.0 + 1)
+            .max()
+            .unwrap_or(0);
+        board.player_count = self.player_count.unwrap_or(referenced);
+        *board = board.clone().sanitized();
+        InitialLayout {
+            units: Some(self.units),
+            pylons: Some(self.pylons),
+        }
+    }
+
+    /// Inserts the scenario's [`BoardSettings`] and [`InitialLayout`]. Call it
+    /// before the first `app.update()` so `Startup` spawns the layout.
+    pub fn build(self, app: &mut App) {
+        let mut board = app
+            .world()
+            .get_resource::<BoardSettings>()
+            .cloned()
+            .unwrap_or_default();
+        let layout = self.apply(&mut board);
+        app.insert_resource(board).insert_resource(layout);
+    }
+}
@@ -0,0 +1,172 @@
+//! Machine-readable match results for headless and CI runs.
+//!
+//! Off by default. Set `MATCH_SUMMARY_PATH` (or insert [`MatchSummarySettings`]
+//! before adding the plugins) and the game writes a JSON [`MatchSummary`] and
+//! exits once the [`GameOutcome`] is decided. `MATCH_MAX_TICKS`
+//! ([`DEFAULT_MATCH_MAX_TICKS`] unless set) caps
+//! [`BoardSettings::max_match_ticks`], so a stalemate ends in the board's draw
+//! and can't keep a CI run alive:
+//!
+//! ```json
+//! {
+//!   "seed": 42,
+//!   "final_tick": 1800,
+//!   "outcome": { "result": "victory", "winner": 1 },
+//!   "players": [
+//!     { "player": 0, "units_alive": 0, "shots": 57, "damage_dealt": 342.0, "kills": 6, "losses": 9 }
+//!   ]
+//! }
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::app::AppExit;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::gameplay::{
+    BoardSettings, GameOutcome, MatchStats, PlayerId, PlayerStats, SimulationParams, SimulationSet,
+    SimulationTick, Unit,
+};
+use crate::SimulationSchedule;
+
+pub const MATCH_SUMMARY_PATH_ENV: &str = "MATCH_SUMMARY_PATH";
+pub const MATCH_MAX_TICKS_ENV: &str = "MATCH_MAX_TICKS";
+/// Ten minutes at the default 30 Hz tick rate.
+pub const DEFAULT_MATCH_MAX_TICKS: u64 = 18_000;
+
+#[derive(Resource, Clone, Debug)]
+pub struct MatchSummarySettings {
+    /// Where the JSON summary is written.
+    pub path: PathBuf,
+    /// Caps [`BoardSettings::max_match_ticks`] at this many ticks; a board
+    /// cap that is already lower stays.
+    pub max_ticks: u64,
+}
+
+impl MatchSummarySettings {
+    /// Reads `MATCH_SUMMARY_PATH` and `MATCH_MAX_TICKS`; `None` when no path
+    /// is set.
+    pub fn from_env() -> Option<Self> {
+        let path = PathBuf::from(std::env::var_os(MATCH_SUMMARY_PATH_ENV)?);
+        let max_ticks = std::env::var(MATCH_MAX_TICKS_ENV)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MATCH_MAX_TICKS);
+        Some(Self { path, max_ticks })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MatchSummary {
+    pub seed: u64,
+    pub final_tick: u64,
+    pub outcome: GameOutcome,
+    pub players: Vec<PlayerSummary>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PlayerSummary {
+    pub player: usize,
+    pub units_alive: usize,
+    #[serde(flatten)]
+    pub stats: PlayerStats,
+}
+
+pub struct MatchSummaryPlugin;
+
+impl Plugin for MatchSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<MatchSummarySettings>() {
+            let Some(settings) = MatchSummarySettings::from_env() else {
+                return;
+            };
+            app.insert_resource(settings);
+        }
+        app.add_systems(Startup, cap_match_length).add_systems(
+            SimulationSchedule,
+            write_match_summary.after(SimulationSet::Outcome),
+        );
+    }
+}
+
+fn cap_match_length(settings: Res<MatchSummarySettings>, mut board: ResMut<BoardSettings>) {
+    if board.max_match_ticks == 0 || board.max_match_ticks > settings.max_ticks {
+        board.max_match_ticks = settings.max_ticks;
+    }
+}
+
+/// Match state a [`MatchSummary`] is built from.
+#[derive(SystemParam)]
+struct MatchState<'w> {
+    params: Res<'w, SimulationParams>,
+    board: Res<'w, BoardSettings>,
+    tick: Res<'w, SimulationTick>,
+    outcome: Res<'w, GameOutcome>,
+    stats: Res<'w, MatchStats>,
+}
+
+fn write_match_summary(
+    settings: Res<MatchSummarySettings>,
+    state: MatchState,
+    units: Query<&Unit>,
+    mut written: Local<bool>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let MatchState {
+        params,
+        board,
+        tick,
+        outcome,
+        stats,
+    } = state;
+    if *written || *outcome == GameOutcome::InProgress {
+        return;
+    }
+    *written = true;
+
+    let mut units_alive = vec![0; board.player_count];
+    for unit in units.iter() {
+        if let Some(count) = units_alive.get_mut(unit.player.0) {
+            *count += 1;
+        }
+    }
+    let summary = MatchSummary {
+        seed: params.seed,
+        final_tick: tick.0,
+        outcome: *outcome,
+        players: units_alive
+            .into_iter()
+            .enumerate()
+            .map(|(player, units_alive)| PlayerSummary {
+                player,
+                units_alive,
+                stats: stats.player(PlayerId(player)),
+            })
+            .collect(),
+    };
+
+    let result = serde_json::to_string_pretty(&summary)
+        .map_err(|err| err.to_string())
+        .and_then(|json| fs::write(&settings.path, json).map_err(|err| err.to_string()));
+    match result {
+        Ok(()) => {
+            info!(
+                target: "gameplay",
+                "Wrote match summary to {}",
+                settings.path.display()
+            );
+            exit.send(AppExit::Success);
+        }
+        Err(err) => {
+            error!(
+                target: "gameplay",
+                "Failed to write match summary to {}: {err}",
+                settings.path.display()
+            );
+            exit.send(AppExit::from_code(1));
+        }
+    }
+}
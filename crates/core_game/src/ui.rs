@@ -1,53 +1,335 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
-use crate::gameplay::SimulationParams;
+use crate::gameplay::{
+    player_color, BeamEffect, BoardSettings, ControlSettings, EventFeed, GameOutcome, PlayerId,
+    SimulationParams, SimulationTick, SupplyState, ThemeSettings, Unit, MAX_PLAYERS,
+};
+
+const HUD_TEXT_COLOR: Color = Color::srgb(0.86, 0.93, 1.0);
+const HUD_HELP: &str = "\n\nDrag LMB to select units (Shift adds, Ctrl removes), release to confirm. Right-click to move, Shift+right-click to queue waypoints. S stops, H holds position. F2 toggles range rings, F3 the action camera, F4 the event feed. 1-8 switch the controlled player, Shift+1-8 players 9-16.\nSet SANDBOX_SCENE=rts_board to load the RTS sandbox.";
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(ClearColor(Color::srgb_u8(8, 10, 24)))
+        let background = app
+            .world()
+            .get_resource::<ThemeSettings>()
+            .cloned()
+            .unwrap_or_default()
+            .background;
+        app.insert_resource(ClearColor(background))
+            .init_resource::<CameraSettings>()
             .add_systems(Startup, setup_ui)
-            .add_systems(Update, update_debug_hud);
+            .add_systems(
+                Update,
+                (
+                    update_debug_hud,
+                    toggle_camera_mode,
+                    toggle_event_feed,
+                    follow_action.after(toggle_camera_mode),
+                ),
+            );
+    }
+}
+
+/// Toggles [`CameraSettings::mode`] between manual and following the action.
+pub const CAMERA_MODE_KEY: KeyCode = KeyCode::F3;
+const DEFAULT_FOLLOW_RATE: f32 = 1.5;
+/// Toggles [`EventFeed::visible`].
+pub const EVENT_FEED_KEY: KeyCode = KeyCode::F4;
+/// Newest [`EventFeed`] entries the HUD shows.
+const FEED_LINES: usize = 6;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraMode {
+    /// The camera stays wherever it was put.
+    #[default]
+    Manual,
+    /// The camera drifts toward live beams, or toward the centroid of all
+    /// units when nobody is firing, for hands-off demos.
+    FollowAction,
+}
+
+/// How the 2D camera moves; presentation only, the simulation never reads it.
+#[derive(Resource, Clone, Debug)]
+pub struct CameraSettings {
+    pub mode: CameraMode,
+    /// How quickly `FollowAction` closes on its target: the fraction of the
+    /// remaining distance covered per second is `1 - e^-follow_rate`.
+    pub follow_rate: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            mode: CameraMode::default(),
+            follow_rate: DEFAULT_FOLLOW_RATE,
+        }
     }
 }
 
 #[derive(Component)]
 struct DebugHud;
 
+/// Sections of the debug HUD that are rewritten every frame.
+#[derive(Component, Clone, Copy)]
+enum HudSpan {
+    LocalPlayer,
+    UnitCount(usize),
+    Outcome,
+    Feed,
+}
+
 fn setup_ui(mut commands: Commands) {
     commands.spawn(Camera2d);
 
-    commands.spawn((
-        Text::new("Booting core game…"),
-        TextFont {
-            font_size: 18.0,
-            ..default()
-        },
-        TextColor(Color::srgb(0.86, 0.93, 1.0)),
-        Node {
-            position_type: PositionType::Absolute,
-            top: Val::Px(16.0),
-            left: Val::Px(16.0),
-            ..default()
-        },
-        DebugHud,
-    ));
+    let font = TextFont {
+        font_size: 18.0,
+        ..default()
+    };
+    commands
+        .spawn((
+            Text::new("Booting core game…"),
+            font.clone(),
+            TextColor(HUD_TEXT_COLOR),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(16.0),
+                left: Val::Px(16.0),
+                ..default()
+            },
+            DebugHud,
+        ))
+        .with_children(|hud| {
+            hud.spawn((
+                TextSpan::default(),
+                font.clone(),
+                TextColor(HUD_TEXT_COLOR),
+                HudSpan::LocalPlayer,
+            ));
+            hud.spawn((
+                TextSpan::new("\nunits:"),
+                font.clone(),
+                TextColor(HUD_TEXT_COLOR),
+            ));
+            for idx in 0..MAX_PLAYERS {
+                hud.spawn((
+                    TextSpan::default(),
+                    font.clone(),
+                    TextColor(player_color(PlayerId(idx))),
+                    HudSpan::UnitCount(idx),
+                ));
+            }
+            hud.spawn((
+                TextSpan::default(),
+                TextFont {
+                    font_size: 26.0,
+                    ..default()
+                },
+                TextColor(HUD_TEXT_COLOR),
+                HudSpan::Outcome,
+            ));
+            hud.spawn((
+                TextSpan::default(),
+                font.clone(),
+                TextColor(HUD_TEXT_COLOR),
+                HudSpan::Feed,
+            ));
+            hud.spawn((TextSpan::new(HUD_HELP), font, TextColor(HUD_TEXT_COLOR)));
+        });
+}
+
+fn toggle_camera_mode(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut settings: ResMut<CameraSettings>,
+) {
+    if keys.is_some_and(|keys| keys.just_pressed(CAMERA_MODE_KEY)) {
+        settings.mode = match settings.mode {
+            CameraMode::Manual => CameraMode::FollowAction,
+            CameraMode::FollowAction => CameraMode::Manual,
+        };
+    }
+}
+
+fn toggle_event_feed(keys: Option<Res<ButtonInput<KeyCode>>>, feed: Option<ResMut<EventFeed>>) {
+    if let (Some(keys), Some(mut feed)) = (keys, feed) {
+        if keys.just_pressed(EVENT_FEED_KEY) {
+            feed.visible = !feed.visible;
+        }
+    }
+}
+
+/// In [`CameraMode::FollowAction`], eases the camera toward the midpoint of
+/// the beams on screen, falling back to the unit centroid between volleys.
+/// Beams only exist with `RenderEffects` on, so without them the camera just
+/// tracks the armies.
+fn follow_action(
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    beams: Query<&Transform, (With<BeamEffect>, Without<Camera2d>)>,
+    units: Query<&Transform, (With<Unit>, Without<Camera2d>)>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    if settings.mode != CameraMode::FollowAction {
+        return;
+    }
+    let Some(target) = centroid(beams.iter()).or_else(|| centroid(units.iter())) else {
+        return;
+    };
+    let blend = 1.0 - (-settings.follow_rate * time.delta_secs()).exp();
+    for mut camera in cameras.iter_mut() {
+        let position = camera.translation.truncate().lerp(target, blend);
+        camera.translation.x = position.x;
+        camera.translation.y = position.y;
+    }
+}
+
+fn centroid<'a>(transforms: impl Iterator<Item = &'a Transform>) -> Option<Vec2> {
+    let (sum, count) = transforms.fold((Vec2::ZERO, 0.0), |(sum, count), transform| {
+        (sum + transform.translation.truncate(), count + 1.0)
+    });
+    (count > 0.0).then(|| sum / count)
+}
+
+/// Match state shown on the debug HUD. Every resource is optional so the HUD
+/// also runs in apps that leave parts of the simulation out.
+#[derive(SystemParam)]
+struct HudSources<'w> {
+    params: Option<Res<'w, SimulationParams>>,
+    board: Option<Res<'w, BoardSettings>>,
+    tick: Option<Res<'w, SimulationTick>>,
+    outcome: Option<Res<'w, GameOutcome>>,
+    supply: Option<Res<'w, SupplyState>>,
+    control: Option<Res<'w, ControlSettings>>,
+    feed: Option<Res<'w, EventFeed>>,
 }
 
 fn update_debug_hud(
     mut text: Query<&mut Text, With<DebugHud>>,
-    params: Option<Res<SimulationParams>>,
+    mut spans: Query<(&mut TextSpan, &mut TextColor, &HudSpan)>,
+    units: Query<&Unit>,
+    sources: HudSources,
     time: Res<Time>,
 ) {
+    let HudSources {
+        params,
+        board,
+        tick,
+        outcome,
+        supply,
+        control,
+        feed,
+    } = sources;
     if let Ok(mut text) = text.get_single_mut() {
         let (seed, fixed_dt) = params
             .map(|p| (p.seed, p.fixed_delta))
             .unwrap_or((0, 1.0 / 60.0));
+        let tick = tick.map(|t| t.0).unwrap_or(0);
+        let supplied = supply.map(|s| s.supplied_count()).unwrap_or(0);
         let content = format!(
-            "Core Game Sandbox\nseed: {seed}\nfixed Δt: {fixed_dt:.4}s\nframe Δt: {:.2}ms\n\nDrag LMB to select units, release to confirm. Right-click to move.\nSet SANDBOX_SCENE=rts_board to load the RTS sandbox.",
+            "Core Game Sandbox\nseed: {seed}\nfixed Δt: {fixed_dt:.4}s\nframe Δt: {:.2}ms\ntick: {tick}\nsupplied: {supplied}",
             time.delta_secs() * 1000.0
         );
         content.clone_into(&mut **text);
     }
+
+    let mut counts = [0usize; MAX_PLAYERS];
+    for unit in units.iter() {
+        if let Some(count) = counts.get_mut(unit.player.0) {
+            *count += 1;
+        }
+    }
+    let player_count = board.map(|b| b.player_count).unwrap_or(MAX_PLAYERS);
+    let outcome = outcome.map(|o| *o).unwrap_or_default();
+    let local_player = control.map(|c| c.local_player).unwrap_or(PlayerId(0));
+
+    for (mut span, mut color, section) in spans.iter_mut() {
+        let content = match *section {
+            HudSpan::LocalPlayer => {
+                color.0 = player_color(local_player);
+                format!("\ncontrolling: P{}", local_player.0)
+            }
+            HudSpan::UnitCount(idx) if idx < player_count => format!(" P{idx}: {}", counts[idx]),
+            HudSpan::UnitCount(_) => String::new(),
+            HudSpan::Outcome => match outcome {
+                GameOutcome::InProgress => String::new(),
+                GameOutcome::Victory(player) => {
+                    color.0 = player_color(player);
+                    format!("\n\nPlayer {} wins!", player.0)
+                }
+                GameOutcome::TeamVictory(team) => {
+                    color.0 = player_color(PlayerId(team.0));
+                    format!("\n\nTeam {} wins!", team.0)
+                }
+                GameOutcome::Draw => {
+                    color.0 = HUD_TEXT_COLOR;
+                    "\n\nDraw".to_string()
+                }
+            },
+            HudSpan::Feed => match feed.as_deref() {
+                Some(feed) if feed.visible => {
+                    let lines: String = feed
+                        .recent(FEED_LINES)
+                        .map(|entry| format!("\n{entry}"))
+                        .collect();
+                    if lines.is_empty() {
+                        lines
+                    } else {
+                        format!("\n{lines}")
+                    }
+                }
+                _ => String::new(),
+            },
+        };
+        if span.0 != content {
+            span.0 = content;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use std::time::Duration;
+
+    #[test]
+    fn follow_action_eases_toward_beams_only_when_enabled() {
+        let mut app = App::new();
+        app.init_resource::<CameraSettings>();
+        app.init_resource::<ButtonInput<KeyCode>>();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs(1));
+        app.insert_resource(time);
+        let camera = app.world_mut().spawn((Camera2d, Transform::default())).id();
+        let beam = || BeamEffect {
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+        };
+        app.world_mut()
+            .spawn((beam(), Transform::from_xyz(100.0, 0.0, 0.6)));
+        app.world_mut()
+            .spawn((beam(), Transform::from_xyz(300.0, 0.0, 0.6)));
+        let camera_x = |app: &App| app.world().get::<Transform>(camera).unwrap().translation.x;
+
+        app.world_mut().run_system_once(follow_action).unwrap();
+        assert_eq!(camera_x(&app), 0.0, "manual mode moved the camera");
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(CAMERA_MODE_KEY);
+        app.world_mut().run_system_once(toggle_camera_mode).unwrap();
+        assert_eq!(
+            app.world().resource::<CameraSettings>().mode,
+            CameraMode::FollowAction
+        );
+        app.world_mut().run_system_once(follow_action).unwrap();
+        let expected = 200.0 * (1.0 - (-DEFAULT_FOLLOW_RATE).exp());
+        assert!(
+            (camera_x(&app) - expected).abs() < 1e-3,
+            "{}",
+            camera_x(&app)
+        );
+    }
 }
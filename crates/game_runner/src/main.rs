@@ -1,7 +1,6 @@
 mod sandbox;
 
 use bevy::asset::AssetPlugin;
-use bevy::ecs::schedule::{Schedule, Schedules};
 use bevy::prelude::*;
 use bevy::window::{PresentMode, Window, WindowPlugin, WindowResolution};
 use core_game::CoreGamePlugin;
@@ -17,7 +16,6 @@ pub fn main() {
 
     let mut app = App::new();
     configure_default_plugins(&mut app);
-    register_simulation_schedule(&mut app);
     app.add_plugins((CoreGamePlugin, SandboxPlugin::default()));
     app.run();
 }
@@ -66,10 +64,3 @@ fn configure_default_plugins(app: &mut App) {
 
     app.add_plugins(plugins);
 }
-
-fn register_simulation_schedule(app: &mut App) {
-    let mut schedules = app.world_mut().resource_mut::<Schedules>();
-    if !schedules.contains(core_game::SimulationSchedule) {
-        schedules.insert(Schedule::new(core_game::SimulationSchedule));
-    }
-}
@@ -1,6 +1,11 @@
 use bevy::prelude::*;
+use core_game::gameplay::{BoardSettings, InitialLayout, PlayerId, UnitKind};
+use core_game::scenario::ScenarioBuilder;
+use serde::Deserialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+const SCENE_FILE: &str = "scene.ron";
 
 pub struct SandboxPlugin {
     root: PathBuf,
@@ -25,6 +30,7 @@ impl Plugin for SandboxPlugin {
     fn build(&self, app: &mut App) {
         let registry = SandboxRegistry::discover(self.root.clone());
         app.insert_resource(registry)
+            .add_systems(PreStartup, load_active_scene)
             .add_systems(Startup, log_sandboxes);
     }
 }
@@ -32,26 +38,39 @@ impl Plugin for SandboxPlugin {
 #[derive(Resource, Debug)]
 pub struct SandboxRegistry {
     pub root: PathBuf,
+    /// Sandboxes whose `scene.ron` parsed successfully.
     pub available: Vec<String>,
+    /// Sandboxes with a missing or malformed `scene.ron`, paired with the error.
+    pub invalid: Vec<(String, String)>,
     pub active: Option<String>,
+    /// Raw `SANDBOX_SCENE` value, kept so a bad request can be reported.
+    pub requested: Option<String>,
 }
 
 impl SandboxRegistry {
     fn discover(root: PathBuf) -> Self {
+        Self::discover_with(root, std::env::var("SANDBOX_SCENE").ok())
+    }
+
+    fn discover_with(root: PathBuf, requested: Option<String>) -> Self {
         let mut available = Vec::new();
+        let mut invalid = Vec::new();
         if let Ok(entries) = fs::read_dir(&root) {
             for entry in entries.flatten() {
                 if entry.file_type().map(|f| f.is_dir()).unwrap_or(false) {
                     if let Some(name) = entry.file_name().to_str() {
-                        available.push(name.to_string());
+                        match SceneDescriptor::from_path(&entry.path().join(SCENE_FILE)) {
+                            Ok(_) => available.push(name.to_string()),
+                            Err(err) => invalid.push((name.to_string(), err)),
+                        }
                     }
                 }
             }
         }
 
         available.sort();
-        let env_active = std::env::var("SANDBOX_SCENE").ok();
-        let active = env_active
+        invalid.sort();
+        let active = requested
             .as_ref()
             .and_then(|name| available.iter().find(|candidate| candidate == &name))
             .cloned();
@@ -59,12 +78,112 @@ impl SandboxRegistry {
         Self {
             root,
             available,
+            invalid,
             active,
+            requested,
+        }
+    }
+}
+
+/// Contents of `examples/systems/<feature>/scene.ron`. Every field is
+/// optional: omitted board size and player count keep the env-driven
+/// defaults, omitted `units` / `pylons` keep the default starter units and
+/// seeded pylon placement.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SceneDescriptor {
+    #[serde(default)]
+    pub board_size: Option<f32>,
+    #[serde(default)]
+    pub player_count: Option<usize>,
+    #[serde(default)]
+    pub units: Option<Vec<UnitPlacement>>,
+    #[serde(default)]
+    pub pylons: Option<Vec<[f32; 2]>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnitPlacement {
+    pub player: usize,
+    pub position: [f32; 2],
+}
+
+impl SceneDescriptor {
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        ron::from_str(&data).map_err(|err| err.to_string())
+    }
+
+    fn apply(&self, board: &mut BoardSettings) -> InitialLayout {
+        let mut scenario =
+            ScenarioBuilder::new().player_count(self.player_count.unwrap_or(board.player_count));
+        if let Some(board_size) = self.board_size {
+            scenario = scenario.board_size(board_size);
+        }
+        for unit in self.units.iter().flatten() {
+            scenario = scenario.unit(
+                PlayerId(unit.player),
+                UnitKind::Laser,
+                Vec2::from(unit.position),
+            );
+        }
+        for &pylon in self.pylons.iter().flatten() {
+            scenario = scenario.pylon(Vec2::from(pylon));
+        }
+        let layout = scenario.apply(board);
+        InitialLayout {
+            units: layout.units.filter(|_| self.units.is_some()),
+            pylons: layout.pylons.filter(|_| self.pylons.is_some()),
+        }
+    }
+}
+
+fn load_active_scene(
+    mut commands: Commands,
+    registry: Res<SandboxRegistry>,
+    mut board: ResMut<BoardSettings>,
+) {
+    let Some(active) = registry.active.as_deref() else {
+        return;
+    };
+    let path = registry.root.join(active).join(SCENE_FILE);
+    match SceneDescriptor::from_path(&path) {
+        Ok(scene) => {
+            commands.insert_resource(scene.apply(&mut board));
+            info!(target: "sandbox", "Loaded scene {}", path.display());
+        }
+        Err(err) => {
+            error!(
+                target: "sandbox",
+                "Failed to load {}: {err}; using env defaults",
+                path.display()
+            );
         }
     }
 }
 
 fn log_sandboxes(registry: Res<SandboxRegistry>) {
+    for (name, err) in &registry.invalid {
+        warn!(
+            target: "sandbox",
+            "Ignoring sandbox {name}: invalid {SCENE_FILE} ({err})"
+        );
+    }
+
+    if let (Some(requested), None) = (registry.requested.as_deref(), &registry.active) {
+        match registry.invalid.iter().find(|(name, _)| name == requested) {
+            Some((_, err)) => error!(
+                target: "sandbox",
+                "SANDBOX_SCENE={requested} has an invalid {SCENE_FILE}: {err}"
+            ),
+            None => error!(
+                target: "sandbox",
+                "SANDBOX_SCENE={requested} not found under {} (available: {:?})",
+                registry.root.display(),
+                registry.available
+            ),
+        }
+    }
+
     if registry.available.is_empty() {
         info!(
             target: "sandbox",
@@ -84,3 +203,77 @@ fn log_sandboxes(registry: Res<SandboxRegistry>) {
             .unwrap_or("none (set SANDBOX_SCENE)"),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_descriptor_overrides_board_and_layout() {
+        let scene: SceneDescriptor = ron::from_str(
+            "(board_size: Some(900.0), player_count: Some(20), units: Some([(player: 1, position: (5.0, -5.0))]))",
+        )
+        .expect("scene parses");
+        let mut board = BoardSettings::default();
+        let layout = scene.apply(&mut board);
+
+        assert_eq!(board.board_size, 900.0);
+        assert_eq!(board.player_count, core_game::gameplay::MAX_PLAYERS);
+        assert_eq!(
+            layout.units,
+            Some(vec![(PlayerId(1), UnitKind::Laser, Vec2::new(5.0, -5.0))])
+        );
+        assert!(layout.pylons.is_none());
+    }
+
+    #[test]
+    fn omitted_scene_fields_keep_env_settings() {
+        let scene: SceneDescriptor = ron::from_str("()").expect("scene parses");
+        let mut board = BoardSettings {
+            board_size: 1200.0,
+            player_count: 6,
+            ..Default::default()
+        };
+        let layout = scene.apply(&mut board);
+
+        assert_eq!((board.board_size, board.player_count), (1200.0, 6));
+        assert!(layout.units.is_none());
+        assert!(layout.pylons.is_none());
+    }
+
+    #[test]
+    fn discover_partitions_valid_and_invalid_scenes() {
+        let root = std::env::temp_dir().join(format!("sandbox-discover-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for name in ["good", "broken", "empty"] {
+            fs::create_dir_all(root.join(name)).unwrap();
+        }
+        fs::write(
+            root.join("good").join(SCENE_FILE),
+            "(board_size: Some(800.0), player_count: Some(2))",
+        )
+        .unwrap();
+        fs::write(root.join("broken").join(SCENE_FILE), "(board_size: )").unwrap();
+
+        let registry = SandboxRegistry::discover_with(root.clone(), Some("broken".into()));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(registry.available, vec!["good".to_string()]);
+        let invalid: Vec<&str> = registry
+            .invalid
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(invalid, vec!["broken", "empty"]);
+        assert!(registry.active.is_none());
+        assert_eq!(registry.requested.as_deref(), Some("broken"));
+    }
+
+    #[test]
+    fn example_scene_parses() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../examples/systems/rts_board")
+            .join(SCENE_FILE);
+        SceneDescriptor::from_path(&path).expect("example scene parses");
+    }
+}
@@ -0,0 +1,186 @@
+//! `validate --bundle`: one archive per LLM turn holding the prompt, response,
+//! diff, and a `guardrail.toml` at its root.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use guardrail_core::config::CONFIG_FILE_NAME;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BundleFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl BundleFormat {
+    fn from_path(path: &Path) -> Result<Self> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Ok(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Ok(Self::Zip)
+        } else {
+            anyhow::bail!(
+                "unsupported bundle {}; expected .tar, .tar.gz, .tgz, or .zip",
+                path.display()
+            )
+        }
+    }
+}
+
+/// An extracted bundle; the temporary directory is removed on drop.
+pub struct Bundle {
+    dir: TempDir,
+    archive: PathBuf,
+}
+
+impl Bundle {
+    /// Extracts `archive` into a fresh temporary directory; members are never
+    /// written outside it.
+    pub fn extract(archive: &Path) -> Result<Self> {
+        let format = BundleFormat::from_path(archive)?;
+        let file = File::open(archive)
+            .with_context(|| format!("failed to open bundle {}", archive.display()))?;
+        let dir = tempfile::Builder::new()
+            .prefix("guardrail-bundle-")
+            .tempdir()
+            .context("failed to create a directory for the bundle")?;
+        let reader = BufReader::new(file);
+        let unpacked = match format {
+            BundleFormat::Tar => tar::Archive::new(reader)
+                .unpack(dir.path())
+                .map_err(anyhow::Error::from),
+            BundleFormat::TarGz => tar::Archive::new(GzDecoder::new(reader))
+                .unpack(dir.path())
+                .map_err(anyhow::Error::from),
+            BundleFormat::Zip => zip::ZipArchive::new(reader)
+                .and_then(|mut zip| zip.extract(dir.path()))
+                .map_err(anyhow::Error::from),
+        };
+        unpacked.with_context(|| format!("malformed bundle {}", archive.display()))?;
+        Ok(Self {
+            dir,
+            archive: archive.to_path_buf(),
+        })
+    }
+
+    /// Directory the bundle's relative source paths resolve against.
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// The `guardrail.toml` at the bundle root.
+    pub fn config_path(&self) -> Result<PathBuf> {
+        let path = self.root().join(CONFIG_FILE_NAME);
+        if !path.is_file() {
+            anyhow::bail!(
+                "bundle {} has no {CONFIG_FILE_NAME} at its root",
+                self.archive.display()
+            );
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const CONFIG: &str = "[sources]\nprompt = \"prompt.md\"\nresponse = \"response.md\"\ndiff = \"patch.diff\"\n\n[analyzers]\nfmt = false\nclippy = false\ndeterministic = false\nbevy = false\ncomplexity = false\nsnapshots = false\n";
+
+    fn fixture(members: &[(&str, &str)]) -> (TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (name, contents) in members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn tar_gz_bundle_validates_with_sources_relative_to_its_root() {
+        let (_dir, archive) = fixture(&[
+            ("guardrail.toml", CONFIG),
+            ("prompt.md", "add a unit"),
+            ("response.md", "done"),
+            ("patch.diff", ""),
+        ]);
+        let bundle = Bundle::extract(&archive).unwrap();
+        let config_path = bundle.config_path().unwrap();
+        let options = guardrail_core::ValidationOptions::new(std::env::temp_dir(), "bundle")
+            .with_sources_root(bundle.root());
+
+        let report = guardrail_core::run_from_config(&config_path, options).unwrap();
+        assert_eq!(report.id, "bundle");
+        assert_eq!(report.source.prompt_path, bundle.root().join("prompt.md"));
+
+        let root = bundle.root().to_path_buf();
+        drop(bundle);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn zip_bundles_extract_too() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("run.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+        zip.start_file("guardrail.toml", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(CONFIG.as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let bundle = Bundle::extract(&archive).unwrap();
+        assert!(bundle.config_path().is_ok());
+    }
+
+    #[test]
+    fn missing_config_and_malformed_archives_are_reported() {
+        let (_dir, archive) = fixture(&[("nested/guardrail.toml", CONFIG)]);
+        let bundle = Bundle::extract(&archive).unwrap();
+        let err = bundle.config_path().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("has no guardrail.toml at its root"),
+            "{err}"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let garbage = dir.path().join("broken.tar.gz");
+        std::fs::write(&garbage, "not an archive").unwrap();
+        let err = Bundle::extract(&garbage).err().unwrap();
+        assert!(err.to_string().starts_with("malformed bundle"), "{err}");
+
+        let err = Bundle::extract(&dir.path().join("run.rar")).err().unwrap();
+        assert!(err.to_string().contains("unsupported bundle"), "{err}");
+        let err = Bundle::extract(&dir.path().join("missing.tgz"))
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().starts_with("failed to open bundle"),
+            "{err}"
+        );
+    }
+}
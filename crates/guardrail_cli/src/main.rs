@@ -1,10 +1,26 @@
+mod bundle;
+mod progress;
+mod trend;
+
 use std::fs;
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
+use bundle::Bundle;
 use chrono::Utc;
-use clap::{Args, Parser, Subcommand};
-use guardrail_core::{run_validations, GuardrailConfig, GuardrailReport, ValidationOptions};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use guardrail_core::report::index::INDEX_FILE_NAME;
+use guardrail_core::{
+    aggregate_reports, plan_from_config, run_doctor, run_from_config, update_index, CheckStatus,
+    DoctorOptions, GuardrailConfig, GuardrailError, GuardrailReport, RenderOptions,
+    ValidationOptions, ValidationPlan, Verbosity,
+};
+use progress::ProgressLayer;
+use tracing_subscriber::filter::{filter_fn, EnvFilter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
 #[derive(Parser)]
 #[command(version, about = "Validate LLM-generated changes against guardrails")]
@@ -15,12 +31,20 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Copy prompt/response/diff artifacts into a structured log directory.
+    /// Copy prompt/response/diff artifacts into a structured log directory,
+    /// normalizing the diff (see `guardrail_core::diff::normalize`).
     Ingest(IngestArgs),
     /// Run analyzers defined in a config file and emit a JSON report.
     Validate(ValidateArgs),
     /// Pretty-print an existing report.
     Report(ReportArgs),
+    /// Roll many reports up into per-analyzer rates and a score series.
+    Trend(TrendArgs),
+    /// Write or refresh `index.json` listing every report in a directory.
+    Index(IndexArgs),
+    /// Check that cargo, git, the workspace, and the report directory are
+    /// usable before a run; exits non-zero if anything required is missing.
+    Doctor(DoctorArgs),
 }
 
 #[derive(Args)]
@@ -37,33 +61,161 @@ struct IngestArgs {
 
 #[derive(Args)]
 struct ValidateArgs {
-    #[arg(long, default_value = "tools/llm_guardrail_cli/guardrail.example.toml")]
-    config: PathBuf,
+    /// Config file; defaults to the nearest `guardrail.toml` in the current
+    /// directory or its parents.
+    #[arg(long)]
+    config: Option<PathBuf>,
     #[arg(long)]
     id: Option<String>,
+    /// Apply the config's `[profiles.NAME]` overrides on top of its
+    /// top-level analyzer settings.
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+    /// Load and check the config, then list the analyzers that would run
+    /// without running them.
+    #[arg(long)]
+    dry_run: bool,
+    /// Diff the working tree against this git revision instead of reading
+    /// `sources.diff`; overrides `sources.diff_from_git`.
+    #[arg(long, value_name = "REV")]
+    diff_from_git: Option<String>,
+    /// Unified diff to validate, passed as the argument itself instead of a
+    /// file; `sources` may then be left out of the config.
+    #[arg(long, value_name = "DIFF", conflicts_with = "diff_from_git")]
+    diff_inline: Option<String>,
+    /// Archive (`.tar.gz`, `.tgz`, `.tar`, or `.zip`) holding the sources and
+    /// a `guardrail.toml` at its root. Source paths resolve inside it; the
+    /// extracted copy is removed afterwards.
+    #[arg(long, value_name = "ARCHIVE", conflicts_with = "config")]
+    bundle: Option<PathBuf>,
+    /// Write the report file as single-line JSON, like `report.compact = true`.
+    /// The copy printed to stdout stays pretty.
+    #[arg(long)]
+    compact: bool,
+    /// Earlier report JSON; findings it already had are demoted so only new
+    /// ones fail the run.
+    #[arg(long, value_name = "REPORT")]
+    baseline: Option<PathBuf>,
+    /// Don't draw per-analyzer progress on stderr. It is only drawn when
+    /// stderr is a terminal; the JSON report on stdout never includes it.
+    #[arg(long, short)]
+    quiet: bool,
 }
 
 #[derive(Args)]
 struct ReportArgs {
     #[arg(long)]
     input: PathBuf,
+    /// Output style; defaults to `github` when `GITHUB_ACTIONS=true`.
+    #[arg(long, value_enum)]
+    format: Option<ReportFormat>,
+    /// How much of each check's details to print.
+    #[arg(long, value_enum, default_value = "normal")]
+    verbosity: VerbosityArg,
+}
+
+#[derive(Args)]
+struct TrendArgs {
+    /// Glob matching the reports to aggregate, e.g. `reports/*.json`.
+    #[arg(long)]
+    glob: String,
+    /// Where to write the trend JSON; printed to stdout when omitted.
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct IndexArgs {
+    /// Directory scanned recursively for report JSON; the index is written
+    /// at its top.
+    #[arg(long, default_value = "reports")]
+    reports_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct DoctorArgs {
+    /// Config file; defaults to the nearest `guardrail.toml` in the current
+    /// directory or its parents.
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// Summary line, then each check with as much detail as `--verbosity`
+    /// asks for.
+    Text,
+    /// GitHub Actions `::error` / `::warning` annotations, then the `text`
+    /// output.
+    Github,
+    /// Summary and check table for pasting into a PR comment.
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum VerbosityArg {
+    /// Check names and statuses only.
+    Quiet,
+    /// First line of each check's details.
+    Normal,
+    /// Full details.
+    Full,
+}
+
+impl From<VerbosityArg> for Verbosity {
+    fn from(arg: VerbosityArg) -> Self {
+        match arg {
+            VerbosityArg::Quiet => Verbosity::Quiet,
+            VerbosityArg::Normal => Verbosity::Normal,
+            VerbosityArg::Full => Verbosity::Full,
+        }
+    }
 }
 
 fn main() -> Result<()> {
-    tracing_subscriber::fmt::try_init().ok();
     let cli = Cli::parse();
+    let progress = matches!(&cli.command, Commands::Validate(args) if !args.quiet && !args.dry_run)
+        && std::io::stderr().is_terminal();
+    init_tracing(progress);
     match cli.command {
         Commands::Ingest(args) => handle_ingest(args),
         Commands::Validate(args) => handle_validate(args),
         Commands::Report(args) => handle_report(args),
+        Commands::Trend(args) => handle_trend(args),
+        Commands::Index(args) => handle_index(args),
+        Commands::Doctor(args) => handle_doctor(args),
     }
 }
 
+/// Logs follow `RUST_LOG`; with `progress`, analyzer spans also drive the
+/// live status on stderr regardless of that filter.
+fn init_tracing(progress: bool) {
+    let logs = tracing_subscriber::fmt::layer().with_filter(EnvFilter::from_default_env());
+    let progress = progress.then(|| {
+        ProgressLayer::new(std::io::stderr())
+            .with_filter(filter_fn(|metadata| metadata.name() == "analyzer"))
+    });
+    tracing_subscriber::registry()
+        .with(logs)
+        .with(progress)
+        .try_init()
+        .ok();
+}
+
 fn handle_ingest(args: IngestArgs) -> Result<()> {
     fs::create_dir_all(&args.out_dir)?;
     copy_into(&args.prompt, &args.out_dir.join("prompt.md"))?;
     copy_into(&args.response, &args.out_dir.join("response.md"))?;
-    copy_into(&args.diff, &args.out_dir.join("patch.diff"))?;
+    let raw_diff = fs::read_to_string(&args.diff)
+        .with_context(|| format!("failed to read {}", args.diff.display()))?;
+    let diff = guardrail_core::diff::normalize(&raw_diff);
+    if !guardrail_core::diff::looks_like_unified_diff(&diff) {
+        tracing::warn!(
+            "{} doesn't look like a unified diff (no ---, +++, and @@ lines); stored anyway",
+            args.diff.display()
+        );
+    }
+    fs::write(args.out_dir.join("patch.diff"), diff)?;
 
     let metadata = serde_json::json!({
         "timestamp": Utc::now().to_rfc3339(),
@@ -80,40 +232,206 @@ fn handle_ingest(args: IngestArgs) -> Result<()> {
 }
 
 fn handle_validate(args: ValidateArgs) -> Result<()> {
-    let config_path = args.config;
-    let config = GuardrailConfig::from_path(&config_path)?;
-    config.validate_sources()?;
-
+    let bundle = args.bundle.as_deref().map(Bundle::extract).transpose()?;
+    let config_path = match (&bundle, args.config) {
+        (Some(bundle), _) => bundle.config_path()?,
+        (None, Some(path)) => path,
+        (None, None) => GuardrailConfig::discover(&std::env::current_dir()?)?,
+    };
     let run_id = args
         .id
         .unwrap_or_else(|| format!("run-{}", Utc::now().format("%Y%m%dT%H%M%S")));
-    let workspace_root = std::env::current_dir()?;
-    let options = ValidationOptions::new(workspace_root, run_id.clone());
+    let mut options = ValidationOptions::new(std::env::current_dir()?, run_id);
+    if let Some(rev) = args.diff_from_git {
+        options = options.with_diff_from_git(rev);
+    }
+    if let Some(diff) = args.diff_inline {
+        options = options.with_diff_inline(diff);
+    }
+    if let Some(bundle) = &bundle {
+        options = options.with_sources_root(bundle.root());
+    }
+    if args.compact {
+        options = options.with_compact_report();
+    }
+    if let Some(baseline) = args.baseline {
+        options = options.with_baseline(baseline);
+    }
+    if let Some(profile) = args.profile {
+        options = options.with_profile(profile);
+    }
+
+    if args.dry_run {
+        let plan = with_load_context(plan_from_config(&config_path, options), &config_path)?;
+        print_plan(&config_path, &plan);
+        return Ok(());
+    }
 
-    let report = run_validations(&config, &options)?;
+    let report = with_load_context(run_from_config(&config_path, options), &config_path)?;
     println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
 
-    if let Some(report_cfg) = config.report.as_ref() {
-        if let Some(parent) = report_cfg.path.parent() {
-            fs::create_dir_all(parent)?;
+/// Names the config when it couldn't be read, parsed, or validated; other
+/// failures already say which file or analyzer they concern.
+fn with_load_context<T>(
+    result: guardrail_core::Result<T>,
+    config_path: &std::path::Path,
+) -> Result<T> {
+    result.map_err(|err| {
+        let loading = matches!(
+            err,
+            GuardrailError::Read {
+                label: "config",
+                ..
+            } | GuardrailError::ConfigParse { .. }
+                | GuardrailError::InvalidConfig(_)
+        );
+        let err = anyhow::Error::from(err);
+        if loading {
+            err.context(format!("failed to load {}", config_path.display()))
+        } else {
+            err
         }
-        fs::write(&report_cfg.path, serde_json::to_string_pretty(&report)?)?;
-        println!("Report written to {}", report_cfg.path.display());
-    }
+    })
+}
 
-    Ok(())
+fn print_plan(config_path: &std::path::Path, plan: &ValidationPlan) {
+    println!("Plan for {}", config_path.display());
+    if plan.scope.packages.is_empty() && plan.scope.paths.is_empty() {
+        println!("scope: whole workspace");
+    } else {
+        let paths: Vec<String> = plan
+            .scope
+            .paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        println!(
+            "scope: packages [{}], paths [{}]",
+            plan.scope.packages.join(", "),
+            paths.join(", ")
+        );
+    }
+    if plan.generated_only {
+        println!("diff only touches generated files; no analyzers would run");
+        return;
+    }
+    for analyzer in &plan.analyzers {
+        let state = if analyzer.enabled {
+            "run"
+        } else {
+            "skip (disabled in config)"
+        };
+        let after = if analyzer.after.is_empty() {
+            String::new()
+        } else {
+            format!(" after {}", analyzer.after.join(", "))
+        };
+        println!(
+            "wave {} {:<14} {state}{after}: {}",
+            analyzer.wave, analyzer.key, analyzer.action
+        );
+    }
 }
 
 fn handle_report(args: ReportArgs) -> Result<()> {
     let data = fs::read_to_string(&args.input)?;
     let report: GuardrailReport = serde_json::from_str(&data)?;
+    let format = args.format.unwrap_or_else(|| {
+        if std::env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true") {
+            ReportFormat::Github
+        } else {
+            ReportFormat::Text
+        }
+    });
+    let opts = RenderOptions::new(args.verbosity.into());
+    match format {
+        ReportFormat::Text => print!("{}", report.render_text(&opts)),
+        ReportFormat::Github => {
+            for annotation in report.github_annotations() {
+                println!("{annotation}");
+            }
+            print!("{}", report.render_text(&opts));
+        }
+        ReportFormat::Markdown => print!("{}", report.render_markdown(&opts)),
+    }
+    Ok(())
+}
+
+fn handle_trend(args: TrendArgs) -> Result<()> {
+    let reports = trend::collect_reports(&args.glob, args.out.as_deref())?;
+    if reports.is_empty() {
+        anyhow::bail!("no reports match {}", args.glob);
+    }
+    let fingerprints: std::collections::BTreeSet<&str> = reports
+        .iter()
+        .map(|report| report.fingerprint.as_str())
+        .filter(|fingerprint| !fingerprint.is_empty())
+        .collect();
+    if fingerprints.len() > 1 {
+        tracing::warn!(
+            "reports ran under {} different configs (fingerprints differ); results may not be comparable",
+            fingerprints.len()
+        );
+    }
+
+    let trend = serde_json::to_string_pretty(&aggregate_reports(&reports))?;
+    match args.out {
+        Some(out) => {
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&out, trend)?;
+            println!(
+                "Trend over {} reports written to {}",
+                reports.len(),
+                out.display()
+            );
+        }
+        None => println!("{trend}"),
+    }
+    Ok(())
+}
+
+fn handle_index(args: IndexArgs) -> Result<()> {
+    let index = update_index(&args.reports_dir)?;
     println!(
-        "Report {} -> {:?} ({:.2})",
-        report.id, report.summary.status, report.summary.score
+        "Indexed {} reports in {}",
+        index.runs.len(),
+        args.reports_dir.join(INDEX_FILE_NAME).display()
     );
     Ok(())
 }
 
+fn handle_doctor(args: DoctorArgs) -> Result<()> {
+    let mut options = DoctorOptions::new(std::env::current_dir()?);
+    if let Some(config) = args.config {
+        options = options.with_config(config);
+    }
+    let checks = run_doctor(&options);
+    for check in &checks {
+        let status = match check.status {
+            CheckStatus::Pass => "ok",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Skipped => "skip",
+        };
+        println!("{status:<4}  {:<10}  {}", check.name, check.details);
+        if let Some(remediation) = &check.remediation {
+            println!("{:<16}fix: {remediation}", "");
+        }
+    }
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} prerequisites missing", checks.len());
+    }
+    Ok(())
+}
+
 fn copy_into(src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
     fs::copy(src, dst)
         .with_context(|| format!("failed to copy {} to {}", src.display(), dst.display()))?;
@@ -0,0 +1,201 @@
+//! Live per-analyzer status on stderr while `validate` runs, driven by the
+//! `analyzer` tracing spans `guardrail_core` opens around each analyzer.
+
+use std::fmt::Write as _;
+use std::io::Write;
+use std::sync::Mutex;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Span name `guardrail_core` uses for each analyzer run.
+const ANALYZER_SPAN: &str = "analyzer";
+
+#[derive(Default)]
+struct AnalyzerFields {
+    name: String,
+    status: Option<String>,
+    duration_ms: Option<u64>,
+}
+
+impl Visit for AnalyzerFields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "name" => self.name = value.to_string(),
+            "status" => self.status = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "duration_ms" {
+            self.duration_ms = Some(value);
+        }
+    }
+}
+
+/// One line per analyzer, in start order.
+struct Board<W> {
+    out: W,
+    lines: Vec<String>,
+    /// Lines printed by the last redraw, which the next one overwrites.
+    drawn: usize,
+}
+
+impl<W: Write> Board<W> {
+    fn redraw(&mut self) {
+        let mut frame = String::new();
+        if self.drawn > 0 {
+            let _ = write!(frame, "\x1b[{}A", self.drawn);
+        }
+        for line in &self.lines {
+            let _ = writeln!(frame, "\x1b[2K{line}");
+        }
+        self.drawn = self.lines.len();
+        let _ = self.out.write_all(frame.as_bytes());
+        let _ = self.out.flush();
+    }
+}
+
+/// Draws `running clippy…` when an analyzer starts and rewrites it to
+/// `✓ clippy (12.3s)` when it finishes. Analyzers running in parallel each
+/// keep their own line. Only meant for a terminal: it moves the cursor with
+/// ANSI escapes.
+pub struct ProgressLayer<W> {
+    board: Mutex<Board<W>>,
+}
+
+impl<W: Write> ProgressLayer<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            board: Mutex::new(Board {
+                out,
+                lines: Vec::new(),
+                drawn: 0,
+            }),
+        }
+    }
+}
+
+fn finished_line(fields: &AnalyzerFields) -> String {
+    let symbol = match fields.status.as_deref() {
+        Some("pass") => "✓",
+        Some("warn") => "!",
+        Some("skipped") => "-",
+        _ => "✗",
+    };
+    let seconds = fields.duration_ms.unwrap_or(0) as f64 / 1000.0;
+    format!("{symbol} {} ({seconds:.1}s)", fields.name)
+}
+
+/// Index of the span's line on the board.
+struct Slot(usize);
+
+impl<S, W> Layer<S> for ProgressLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: Write + Send + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != ANALYZER_SPAN {
+            return;
+        }
+        let mut fields = AnalyzerFields::default();
+        attrs.record(&mut fields);
+        let mut board = self.board.lock().unwrap_or_else(|err| err.into_inner());
+        board.lines.push(format!("running {}…", fields.name));
+        let slot = Slot(board.lines.len() - 1);
+        board.redraw();
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            extensions.insert(fields);
+            extensions.insert(slot);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<AnalyzerFields>() {
+                values.record(fields);
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let (Some(fields), Some(Slot(slot))) =
+            (extensions.get::<AnalyzerFields>(), extensions.get::<Slot>())
+        else {
+            return;
+        };
+        let mut board = self.board.lock().unwrap_or_else(|err| err.into_inner());
+        board.lines[*slot] = finished_line(fields);
+        board.redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn analyzer(name: &'static str) -> tracing::Span {
+        tracing::info_span!(
+            "analyzer",
+            name,
+            status = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        )
+    }
+
+    #[test]
+    fn overlapping_analyzers_are_redrawn_in_place() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::registry().with(ProgressLayer::new(captured.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let fmt = analyzer("fmt");
+            let clippy = analyzer("clippy");
+            clippy.record("status", "fail");
+            clippy.record("duration_ms", 12_345_u64);
+            drop(clippy);
+            fmt.record("status", "pass");
+            fmt.record("duration_ms", 400_u64);
+            drop(fmt);
+            let _unrelated = tracing::info_span!("other");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            output,
+            "\x1b[2Krunning fmt…\n\
+             \x1b[1A\x1b[2Krunning fmt…\n\x1b[2Krunning clippy…\n\
+             \x1b[2A\x1b[2Krunning fmt…\n\x1b[2K✗ clippy (12.3s)\n\
+             \x1b[2A\x1b[2K✓ fmt (0.4s)\n\x1b[2K✗ clippy (12.3s)\n"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! `trend`: the stored reports a glob matches, read for aggregation.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use guardrail_core::report::index::INDEX_FILE_NAME;
+use guardrail_core::GuardrailReport;
+
+/// Reads every report `pattern` matches. Skips `index.json`, the trend file
+/// at `out` (which usually sits in the same directory and would otherwise
+/// match on the next run), and, with a warning, any file that doesn't parse
+/// as a report.
+pub fn collect_reports(pattern: &str, out: Option<&Path>) -> Result<Vec<GuardrailReport>> {
+    let out = out.map(|out| fs::canonicalize(out).unwrap_or_else(|_| out.to_path_buf()));
+    let mut reports = Vec::new();
+    for path in glob::glob(pattern).with_context(|| format!("invalid glob {pattern}"))? {
+        let path = path?;
+        if path.file_name().is_some_and(|name| name == INDEX_FILE_NAME) {
+            continue;
+        }
+        if out.as_deref().is_some_and(|out| {
+            fs::canonicalize(&path).map_or(path == out, |canonical| canonical == out)
+        }) {
+            continue;
+        }
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        match serde_json::from_str::<GuardrailReport>(&data) {
+            Ok(report) => reports.push(report),
+            Err(err) => tracing::warn!("skipping {}: not a report ({err})", path.display()),
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use guardrail_core::aggregate_reports;
+
+    #[test]
+    fn trend_written_next_to_its_reports_is_not_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        for (id, timestamp) in [("a", "2025-03-01T00:00:00Z"), ("b", "2025-03-02T00:00:00Z")] {
+            let report = GuardrailReport::builder(id).timestamp(timestamp).build();
+            fs::write(
+                dir.path().join(format!("{id}.json")),
+                serde_json::to_string_pretty(&report).unwrap(),
+            )
+            .unwrap();
+        }
+        fs::write(dir.path().join("notes.json"), "{\"todo\": []}").unwrap();
+        let pattern = format!("{}/*.json", dir.path().display());
+        let out = dir.path().join("trend.json");
+
+        let mut trends = Vec::new();
+        for _ in 0..2 {
+            let reports = collect_reports(&pattern, Some(&out)).unwrap();
+            assert_eq!(reports.len(), 2);
+            let trend = serde_json::to_string_pretty(&aggregate_reports(&reports)).unwrap();
+            fs::write(&out, &trend).unwrap();
+            trends.push(trend);
+        }
+        assert_eq!(trends[0], trends[1]);
+    }
+}
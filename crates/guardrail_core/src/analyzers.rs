@@ -1,14 +1,199 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
 use walkdir::WalkDir;
 
-use crate::config::GuardrailConfig;
-use crate::report::{CheckResult, CheckStatus, GuardrailReport};
+use crate::config::{AnalyzerToggles, DiffSource, GuardrailConfig, ScopeConfig};
+use crate::diff;
+use crate::error::{read_file, walk_error, write_file, GuardrailError, Result};
+use crate::report::baseline::subtract_baseline;
+use crate::report::{CheckResult, CheckStatus, GuardrailReport, RedactOptions, RiskEntry};
+use crate::worktree::PatchedWorktree;
+
+/// Analyzer keys accepted in `[analyzers]` and `analyzers.pipeline`, in the
+/// order their checks appear in the report.
+pub const ANALYZER_KEYS: [&str; 6] = [
+    "fmt",
+    "clippy",
+    "deterministic",
+    "bevy",
+    "complexity",
+    "snapshots",
+];
+
+/// Variable the game reads its simulation seed from; see
+/// [`DeterminismConfig::seed`](crate::config::DeterminismConfig::seed).
+pub const SEED_ENV_VAR: &str = "SIMULATION_SEED";
+
+/// A source pattern that makes runs irreproducible, flagged by the
+/// deterministic scan unless listed in `disabled_rules`.
+pub struct DeterminismRule {
+    pub name: &'static str,
+    /// How offenders are introduced in the check details.
+    pub label: &'static str,
+    pub remediation: &'static str,
+    matches: fn(&str) -> bool,
+}
+
+impl DeterminismRule {
+    fn matches(&self, line: &str) -> bool {
+        (self.matches)(line)
+    }
+}
+
+/// The deterministic scan's rules, in report order. Matching is per line and
+/// textual, like the other scans.
+pub const DETERMINISM_RULES: &[DeterminismRule] = &[
+    DeterminismRule {
+        name: "thread_rng",
+        label: "thread_rng",
+        remediation:
+            "replace `thread_rng()` with a seeded `StdRng` (e.g. from `SimulationParams::seed`)",
+        matches: |line| line.contains("thread_rng("),
+    },
+    DeterminismRule {
+        name: "instant_now",
+        label: "Instant::now",
+        remediation: "count simulation ticks (or read `Time<Fixed>`) instead of the wall clock",
+        matches: |line| line.contains("Instant::now("),
+    },
+    DeterminismRule {
+        name: "system_time_now",
+        label: "SystemTime::now",
+        remediation:
+            "pass timestamps in from outside the simulation instead of reading the system clock",
+        matches: |line| line.contains("SystemTime::now("),
+    },
+    DeterminismRule {
+        name: "std_hash_map",
+        label: "std HashMap/HashSet",
+        remediation: "use BTreeMap/BTreeSet for ordered iteration, or Bevy's fixed-hasher HashMap",
+        matches: |line| {
+            (line.contains("std::collections::") || line.contains("hash_map::"))
+                && (line.contains("HashMap") || line.contains("HashSet"))
+        },
+    },
+    DeterminismRule {
+        name: "random_state",
+        label: "RandomState",
+        remediation:
+            "hash with a fixed `BuildHasher` (e.g. `BuildHasherDefault`) instead of `RandomState`",
+        matches: |line| line.contains("RandomState"),
+    },
+];
+
+/// `diff_path` recorded in reports whose diff was passed inline.
+pub const INLINE_DIFF_PATH: &str = "<inline>";
+
+/// Check name each analyzer reports under.
+pub(crate) fn check_name(key: &str) -> &'static str {
+    match key {
+        "fmt" => "fmt",
+        "clippy" => "clippy",
+        "deterministic" => "deterministic_seed_scan",
+        "complexity" => "complexity_scan",
+        "snapshots" => "snapshot_scan",
+        _ => "bevy_sandbox_checks",
+    }
+}
+
+/// Runs one analyzer; only the complexity scan contributes risks.
+fn run_analyzer(
+    key: &str,
+    config: &GuardrailConfig,
+    options: &ValidationOptions,
+) -> Result<(CheckResult, Vec<RiskEntry>)> {
+    let root = &options.workspace_root;
+    let command = CommandSettings {
+        program: &options.cargo,
+        capture: OutputCapture::for_analyzer(&config.analyzers, key),
+        env: child_env(config, options),
+    };
+    let mut check = match key {
+        "fmt" => run_fmt(root, &options.scope, &command)?,
+        "clippy" => run_clippy(root, &options.scope, &config.analyzers, &command)?,
+        "deterministic" => run_deterministic_scan(
+            root,
+            &options.scope,
+            config.analyzers.deterministic_exclude_tests(),
+            config.analyzers.deterministic_disabled_rules(),
+        )?,
+        "snapshots" => run_snapshot_scan(
+            root,
+            &snapshot_roots(&config.analyzers, &options.scope),
+            config.analyzers.snapshot_extension(),
+        )?,
+        "complexity" => {
+            let diff = read_diff(config, options)?;
+            return Ok(run_complexity_scan(
+                &diff,
+                config.analyzers.max_function_lines(),
+            ));
+        }
+        _ => run_bevy_checks(root)?,
+    };
+    if check.status == CheckStatus::Fail && check.remediation.is_none() {
+        check.remediation = remediation(key).map(str::to_string);
+    }
+    Ok((check, Vec::new()))
+}
+
+/// Variables set on every command analyzer's child process, on top of the
+/// ones guardrail inherited.
+fn child_env(config: &GuardrailConfig, options: &ValidationOptions) -> Vec<(&'static str, String)> {
+    let seed = options
+        .seed
+        .or(config.determinism.seed)
+        .map(|seed| (SEED_ENV_VAR, seed.to_string()));
+    let target_dir = options
+        .cargo_target_dir
+        .as_ref()
+        .map(|dir| ("CARGO_TARGET_DIR", dir.display().to_string()));
+    seed.into_iter().chain(target_dir).collect()
+}
+
+/// Fix suggestion attached to a failing analyzer's check.
+fn remediation(key: &str) -> Option<&'static str> {
+    match key {
+        "fmt" => Some("run `cargo fmt --all`"),
+        "clippy" => Some("run `cargo clippy --fix`, then fix the remaining lints by hand"),
+        "snapshots" => Some(
+            "run `cargo insta review` to accept or reject the pending snapshots, then commit the `.snap` files",
+        ),
+        _ => None,
+    }
+}
 
 pub struct ValidationOptions {
     pub workspace_root: PathBuf,
     pub run_id: String,
+    pub scope: ValidationScope,
+    /// Overrides `sources.diff_from_git`.
+    pub diff_from_git: Option<String>,
+    /// Diff text to validate instead of `sources.diff` or
+    /// `sources.diff_from_git`; reported as [`INLINE_DIFF_PATH`].
+    pub diff_inline: Option<String>,
+    /// Base for relative `sources` paths, which are otherwise read relative to
+    /// the current directory.
+    pub sources_root: Option<PathBuf>,
+    /// Forces `report.compact`.
+    pub compact_report: bool,
+    /// Overrides `[determinism] seed`.
+    pub seed: Option<u64>,
+    /// Earlier report whose findings are demoted; see [`subtract_baseline`].
+    pub baseline: Option<PathBuf>,
+    /// `[profiles.<name>]` to apply; see [`GuardrailConfig::resolve_profile`].
+    pub profile: Option<String>,
+    /// `CARGO_TARGET_DIR` for the cargo analyzers; unset, they inherit
+    /// guardrail's. [`run_from_config`] points it at the original build
+    /// directory when the analyzers run in a patch worktree.
+    pub cargo_target_dir: Option<PathBuf>,
+    /// Program run for the `fmt` and `clippy` analyzers.
+    pub cargo: String,
 }
 
 impl ValidationOptions {
@@ -16,78 +201,785 @@ impl ValidationOptions {
         Self {
             workspace_root,
             run_id: run_id.into(),
+            scope: ValidationScope::default(),
+            diff_from_git: None,
+            diff_inline: None,
+            sources_root: None,
+            compact_report: false,
+            seed: None,
+            baseline: None,
+            profile: None,
+            cargo_target_dir: None,
+            cargo: "cargo".into(),
+        }
+    }
+
+    pub fn with_scope(mut self, scope: ValidationScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    pub fn with_diff_from_git(mut self, rev: impl Into<String>) -> Self {
+        self.diff_from_git = Some(rev.into());
+        self
+    }
+
+    pub fn with_diff_inline(mut self, diff: impl Into<String>) -> Self {
+        self.diff_inline = Some(diff.into());
+        self
+    }
+
+    pub fn with_sources_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.sources_root = Some(root.into());
+        self
+    }
+
+    pub fn with_compact_report(mut self) -> Self {
+        self.compact_report = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_baseline(mut self, path: impl Into<PathBuf>) -> Self {
+        self.baseline = Some(path.into());
+        self
+    }
+
+    pub fn with_profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    pub fn with_cargo_target_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cargo_target_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_cargo(mut self, program: impl Into<String>) -> Self {
+        self.cargo = program.into();
+        self
+    }
+}
+
+/// [`ScopeConfig`] with packages and paths filled in from each other, so cargo
+/// analyzers and source scans cover the same crates. Empty means the whole
+/// workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationScope {
+    pub packages: Vec<String>,
+    /// Relative to the workspace root.
+    pub paths: Vec<PathBuf>,
+}
+
+impl ValidationScope {
+    /// Maps each configured package to its manifest directory and each
+    /// configured path that holds a package manifest to its package name.
+    pub fn resolve(config: &ScopeConfig, workspace_root: &Path) -> Result<Self> {
+        let mut scope = Self {
+            packages: config.packages.clone(),
+            paths: config.paths.clone(),
+        };
+        if scope.packages.is_empty() && scope.paths.is_empty() {
+            return Ok(scope);
+        }
+
+        let manifests = package_manifests(workspace_root)?;
+        for package in &config.packages {
+            let (_, dir) = manifests
+                .iter()
+                .find(|(name, _)| name == package)
+                .ok_or_else(|| {
+                    GuardrailError::InvalidConfig(format!(
+                        "scope package {package} not found in workspace"
+                    ))
+                })?;
+            if !scope.paths.contains(dir) {
+                scope.paths.push(dir.clone());
+            }
+        }
+        for path in &config.paths {
+            if !workspace_root.join(path).exists() {
+                return Err(GuardrailError::InvalidConfig(format!(
+                    "scope path {} does not exist",
+                    path.display()
+                )));
+            }
+            if let Some((name, _)) = manifests.iter().find(|(_, dir)| dir == path) {
+                if !scope.packages.contains(name) {
+                    scope.packages.push(name.clone());
+                }
+            }
+        }
+        Ok(scope)
+    }
+
+    /// `-p <pkg>` for each scoped package.
+    fn package_args(&self) -> Vec<String> {
+        self.packages
+            .iter()
+            .flat_map(|package| ["-p".to_string(), package.clone()])
+            .collect()
+    }
+}
+
+/// `(package name, workspace-relative directory)` for every package manifest.
+fn package_manifests(workspace_root: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut manifests = Vec::new();
+    for entry in WalkDir::new(workspace_root)
+        .into_iter()
+        .filter_entry(|e| filter_entry(e.path()))
+    {
+        let entry = entry.map_err(walk_error(workspace_root))?;
+        if entry.file_name() != "Cargo.toml" {
+            continue;
+        }
+        let contents = read_file("manifest", entry.path())?;
+        let manifest: toml::Value =
+            toml::from_str(&contents).map_err(|source| GuardrailError::ManifestParse {
+                path: entry.path().to_path_buf(),
+                source,
+            })?;
+        let name = manifest
+            .get("package")
+            .and_then(|package| package.get("name"))
+            .and_then(|name| name.as_str());
+        if let (Some(name), Some(dir)) = (name, entry.path().parent()) {
+            let dir = dir.strip_prefix(workspace_root).unwrap_or(dir);
+            manifests.push((name.to_string(), dir.to_path_buf()));
+        }
+    }
+    Ok(manifests)
+}
+
+/// Loads the config at `config_path`, checks its sources exist, runs the
+/// analyzers, and writes the report to `report.path` when configured
+/// (pretty-printed unless `report.compact` or
+/// [`ValidationOptions::compact_report`] is set). The config's `[scope]`
+/// applies unless `options` already carries a scope. With
+/// [`ValidationOptions::baseline`], findings already in that report are
+/// demoted after redaction, so a baseline written with the same config
+/// matches path for path.
+pub fn run_from_config(config_path: &Path, options: ValidationOptions) -> Result<GuardrailReport> {
+    let (config, options) = load_config(config_path, options)?;
+    let (config, options, _worktree) = prepare_sources(config, options)?;
+    let baseline = options
+        .baseline
+        .as_deref()
+        .map(|path| -> Result<GuardrailReport> {
+            serde_json::from_str(&read_file("baseline", path)?).map_err(|source| {
+                GuardrailError::ReportParse {
+                    label: "baseline",
+                    path: path.to_path_buf(),
+                    source,
+                }
+            })
+        })
+        .transpose()?;
+    let mut report = run_validations(&config, &options)?;
+    if let Some(report_cfg) = config.report.as_ref() {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+        report.redact(&RedactOptions {
+            workspace_root: report_cfg
+                .redact_paths
+                .then(|| options.workspace_root.clone()),
+            home: home.filter(|_| report_cfg.redact_home).map(PathBuf::from),
+        });
+    }
+    if let Some(baseline) = &baseline {
+        let demoted = subtract_baseline(&mut report, baseline);
+        tracing::info!("{demoted} findings matched the baseline");
+    }
+    if let Some(report_cfg) = config.report.as_ref() {
+        write_report(
+            &report_cfg.path,
+            &report,
+            report_cfg.compact || options.compact_report,
+        )
+        .map_err(|source| GuardrailError::Write {
+            path: report_cfg.path.clone(),
+            source,
+        })?;
+        tracing::info!("Report written to {}", report_cfg.path.display());
+    }
+    Ok(report)
+}
+
+fn write_report(path: &Path, report: &GuardrailReport, compact: bool) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    if compact {
+        serde_json::to_writer(&mut file, report)?;
+    } else {
+        serde_json::to_writer_pretty(&mut file, report)?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Same loading and checks as [`run_from_config`], but only reports what would
+/// run. Nothing is written: a patch series is read as is rather than
+/// applied, so no worktree is created and no `combined.diff` written, and
+/// `diff_from_git` is kept in memory instead of saved as `git.diff`.
+pub fn plan_from_config(config_path: &Path, options: ValidationOptions) -> Result<ValidationPlan> {
+    let (config, mut options) = load_config(config_path, options)?;
+    if let Some(rev) = config
+        .sources
+        .diff_from_git
+        .as_ref()
+        .filter(|_| options.diff_inline.is_none())
+    {
+        options.diff_inline = Some(diff::from_git(&options.workspace_root, rev)?);
+    }
+    plan_validations(&config, &options)
+}
+
+/// Loads the config with the profile and source overrides from `options`
+/// applied, checks its sources exist, and resolves the scope.
+fn load_config(
+    config_path: &Path,
+    mut options: ValidationOptions,
+) -> Result<(GuardrailConfig, ValidationOptions)> {
+    let mut config = GuardrailConfig::from_path(config_path)?
+        .resolve_profile(options.profile.as_deref())?
+        .config;
+    if let Some(rev) = options.diff_from_git.clone() {
+        config.sources.diff_from_git = Some(rev);
+    }
+    if let Some(root) = &options.sources_root {
+        config.sources.rebase(root);
+    }
+    config
+        .sources
+        .ensure_exists(options.diff_inline.is_some())?;
+    if options.scope == ValidationScope::default() {
+        options.scope = ValidationScope::resolve(&config.scope, &options.workspace_root)?;
+    }
+    Ok((config, options))
+}
+
+/// Saves `git diff <sources.diff_from_git>` as `git.diff` next to `prompt`
+/// and makes it the diff source.
+fn capture_git_diff(
+    mut config: GuardrailConfig,
+    options: ValidationOptions,
+) -> Result<(GuardrailConfig, ValidationOptions)> {
+    if let Some(rev) = config
+        .sources
+        .diff_from_git
+        .as_ref()
+        .filter(|_| options.diff_inline.is_none())
+    {
+        let captured = diff::from_git(&options.workspace_root, rev)?;
+        let path = config
+            .sources
+            .prompt
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join("git.diff");
+        write_file(&path, captured)?;
+        config.sources.diff = DiffSource::Single(path);
+    }
+    Ok((config, options))
+}
+
+/// Settles where the diff comes from before a run: captures `diff_from_git`,
+/// and applies a [`DiffSource::Series`] to a [`PatchedWorktree`], which must
+/// outlive the run. The analyzers then run in the worktree's copy of the
+/// workspace root, building into the original target directory, and the
+/// combined diff is saved as `combined.diff` next to `prompt` and reported as
+/// the diff source.
+fn prepare_sources(
+    config: GuardrailConfig,
+    options: ValidationOptions,
+) -> Result<(GuardrailConfig, ValidationOptions, Option<PatchedWorktree>)> {
+    let (mut config, mut options) = capture_git_diff(config, options)?;
+    let mut worktree = None;
+    if let DiffSource::Series(patches) = config.sources.diff.clone() {
+        if options.diff_inline.is_none() {
+            let patched = PatchedWorktree::apply(&options.workspace_root, &patches)?;
+            let path = config
+                .sources
+                .prompt
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join("combined.diff");
+            write_file(&path, patched.combined_diff()?)?;
+            tracing::info!(
+                "Applied {} patches in {}",
+                patches.len(),
+                patched.path().display()
+            );
+            config.sources.diff = DiffSource::Single(path);
+            if options.cargo_target_dir.is_none() {
+                options.cargo_target_dir = Some(match std::env::var_os("CARGO_TARGET_DIR") {
+                    Some(dir) => std::env::current_dir().unwrap_or_default().join(dir),
+                    None => options.workspace_root.join("target"),
+                });
+            }
+            options.workspace_root = patched.workspace_root();
+            worktree = Some(patched);
         }
     }
+    Ok((config, options, worktree))
+}
+
+/// What [`run_validations`] would do, without running anything.
+#[derive(Debug, Clone)]
+pub struct ValidationPlan {
+    pub scope: ValidationScope,
+    /// The diff only touches generated files, so no analyzer would run.
+    pub generated_only: bool,
+    /// In execution order: by wave, then report order.
+    pub analyzers: Vec<PlannedAnalyzer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedAnalyzer {
+    pub key: &'static str,
+    pub check: &'static str,
+    /// 1-based; analyzers sharing a wave run in parallel.
+    pub wave: usize,
+    pub enabled: bool,
+    /// Analyzers that must pass first.
+    pub after: Vec<String>,
+    /// Command or scan the analyzer performs, with scope applied.
+    pub action: String,
+}
+
+pub fn plan_validations(
+    config: &GuardrailConfig,
+    options: &ValidationOptions,
+) -> Result<ValidationPlan> {
+    let catalog = config.analyzer_plan();
+    let mut analyzers = Vec::new();
+    for (wave, keys) in config.analyzers.pipeline_waves()?.into_iter().enumerate() {
+        for key in keys {
+            let entry = catalog
+                .iter()
+                .find(|entry| entry.key == key)
+                .expect("the catalog covers every analyzer key");
+            analyzers.push(PlannedAnalyzer {
+                key,
+                check: entry.check,
+                wave: wave + 1,
+                enabled: entry.enabled,
+                after: config
+                    .analyzers
+                    .prerequisites(key)
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                action: describe_analyzer(key, config, options),
+            });
+        }
+    }
+    Ok(ValidationPlan {
+        scope: options.scope.clone(),
+        generated_only: generated_only_check(config, options)?.is_some(),
+        analyzers,
+    })
+}
+
+fn describe_analyzer(key: &str, config: &GuardrailConfig, options: &ValidationOptions) -> String {
+    let (toggles, scope) = (&config.analyzers, &options.scope);
+    match key {
+        "complexity" => format!(
+            "flag functions added by the diff that span more than {} lines",
+            toggles.max_function_lines()
+        ),
+        "fmt" | "clippy" => {
+            let args = if key == "fmt" {
+                fmt_args(scope)
+            } else {
+                clippy_args(scope, toggles)
+            };
+            let merged = match OutputCapture::for_analyzer(toggles, key) {
+                OutputCapture::Separate => "",
+                OutputCapture::Combined => " (stdout and stderr combined)",
+            };
+            let env: String = child_env(config, options)
+                .iter()
+                .map(|(key, value)| format!("{key}={value} "))
+                .collect();
+            format!("{env}{} {}{merged}", options.cargo, args.join(" "))
+        }
+        "deterministic" => {
+            let tests = if toggles.deterministic_exclude_tests() {
+                " (excluding tests)"
+            } else {
+                ""
+            };
+            let rules: Vec<&str> = active_determinism_rules(toggles.deterministic_disabled_rules())
+                .map(|rule| rule.name)
+                .collect();
+            format!(
+                "scan *.rs for {} under {}{tests}",
+                rules.join(", "),
+                display_roots(&scope.paths)
+            )
+        }
+        "snapshots" => format!(
+            "flag *.{} files under {}",
+            toggles.snapshot_extension(),
+            display_roots(&snapshot_roots(toggles, scope))
+        ),
+        _ => "check FixedUpdate/SimulationParams in core_game and SandboxPlugin in game_runner"
+            .into(),
+    }
 }
 
 pub fn run_validations(
     config: &GuardrailConfig,
     options: &ValidationOptions,
 ) -> Result<GuardrailReport> {
-    let mut checks = Vec::new();
-    let toggles = &config.analyzers;
+    let risks = Mutex::new(Vec::new());
+    let checks = match generated_only_check(config, options)? {
+        Some(check) => vec![check],
+        None => execute_pipeline(&config.analyzers, |key| {
+            let (check, found) = run_analyzer(key, config, options)?;
+            risks.lock().unwrap().extend(found);
+            Ok(check)
+        })?,
+    };
 
-    if toggles.fmt_enabled() {
-        checks.push(run_fmt(&options.workspace_root)?);
+    let mut source = config.source_info();
+    if options.diff_inline.is_some() {
+        source.diff_path = INLINE_DIFF_PATH.into();
     }
-
-    if toggles.clippy_enabled() {
-        checks.push(run_clippy(&options.workspace_root)?);
+    let mut report =
+        GuardrailReport::new(options.run_id.clone(), source, checks, "Guardrail CLI MVP");
+    report.risks = risks.into_inner().unwrap();
+    report.fingerprint = config.fingerprint();
+    if config
+        .report
+        .as_ref()
+        .is_some_and(|report| report.embed_config)
+    {
+        report.config_snapshot = Some(config.snapshot()?);
     }
+    Ok(report)
+}
 
-    if toggles.deterministic_enabled() {
-        checks.push(run_deterministic_scan(&options.workspace_root)?);
+/// Replaces the analyzer suite with one passing check when every file in the
+/// diff matches `sources.generated_globs`.
+fn generated_only_check(
+    config: &GuardrailConfig,
+    options: &ValidationOptions,
+) -> Result<Option<CheckResult>> {
+    let globs = &config.sources.generated_globs;
+    if globs.is_empty() {
+        return Ok(None);
     }
+    let diff = read_diff(config, options)?;
+    let files = diff::changed_files(&diff);
+    if !diff::generated_only(&files, globs)? {
+        return Ok(None);
+    }
+    let files: Vec<String> = files
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect();
+    Ok(Some(CheckResult {
+        name: "generated_only".into(),
+        status: CheckStatus::Pass,
+        details: format!(
+            "Diff only touches generated files; analyzers skipped:\n{}",
+            files.join("\n")
+        ),
+        log_path: None,
+        remediation: None,
+    }))
+}
+
+/// The diff under review: [`ValidationOptions::diff_inline`] when given,
+/// otherwise the contents of `sources.diff`, passed through
+/// [`diff::normalize`]. [`run_from_config`] replaces a series with its
+/// combined diff first; unapplied, as in a plan, the patches are read back to
+/// back, which still lists every file they touch.
+fn read_diff(config: &GuardrailConfig, options: &ValidationOptions) -> Result<String> {
+    let raw = match (&options.diff_inline, &config.sources.diff) {
+        (Some(diff), _) => diff.clone(),
+        (None, DiffSource::Single(path)) => read_file("diff", path)?,
+        (None, DiffSource::Series(patches)) => {
+            let mut combined = String::new();
+            for patch in patches {
+                combined.push_str(&diff::normalize(&read_file("patch", patch)?));
+            }
+            combined
+        }
+    };
+    Ok(diff::normalize(&raw))
+}
+
+/// Runs enabled analyzers wave by wave (see [`AnalyzerToggles::pipeline_waves`]),
+/// in parallel within a wave. Disabled analyzers, and analyzers whose
+/// prerequisite failed (or was itself skipped for that reason), are reported as
+/// skipped instead of run.
+fn execute_pipeline<F>(toggles: &AnalyzerToggles, run: F) -> Result<Vec<CheckResult>>
+where
+    F: Fn(&'static str) -> Result<CheckResult> + Sync,
+{
+    let mut results: HashMap<&str, CheckResult> = HashMap::new();
+    let mut blocked: HashSet<&str> = HashSet::new();
+    for wave in toggles.pipeline_waves()? {
+        let mut runnable = Vec::new();
+        for key in wave {
+            if !toggles.enabled(key) {
+                results.insert(key, skipped(key, "disabled in config".into()));
+                continue;
+            }
+            let failed = toggles
+                .prerequisites(key)
+                .into_iter()
+                .find(|prerequisite| blocked.contains(prerequisite));
+            match failed {
+                Some(prerequisite) => {
+                    blocked.insert(key);
+                    let reason = format!("prerequisite failed: {}", check_name(prerequisite));
+                    results.insert(key, skipped(key, reason));
+                }
+                None => runnable.push(key),
+            }
+        }
 
-    if toggles.bevy_enabled() {
-        checks.push(run_bevy_checks(&options.workspace_root)?);
+        // Spawned threads don't inherit the caller's subscriber or span, so
+        // hand both over explicitly.
+        let dispatch = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+        let parent = tracing::Span::current();
+        let outcomes: Vec<(&str, Result<CheckResult>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = runnable
+                .iter()
+                .map(|&key| {
+                    let (dispatch, parent, run) = (&dispatch, &parent, &run);
+                    let handle = scope.spawn(move || {
+                        tracing::dispatcher::with_default(dispatch, || {
+                            parent.in_scope(|| run_traced(key, run))
+                        })
+                    });
+                    (key, handle)
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|(key, handle)| {
+                    let outcome = handle.join().unwrap_or_else(|_| {
+                        Err(GuardrailError::AnalyzerPanicked {
+                            analyzer: key.to_string(),
+                        })
+                    });
+                    (key, outcome)
+                })
+                .collect()
+        });
+        for (key, outcome) in outcomes {
+            let check = outcome?;
+            if check.status == CheckStatus::Fail {
+                blocked.insert(key);
+            }
+            results.insert(key, check);
+        }
     }
 
-    let report = GuardrailReport::new(
-        options.run_id.clone(),
-        config.source_info(),
-        checks,
-        "Guardrail CLI MVP",
+    Ok(ANALYZER_KEYS
+        .iter()
+        .filter_map(|key| results.remove(key))
+        .collect())
+}
+
+/// [`run_guarded`] inside an `analyzer` span carrying the analyzer's name and,
+/// once it finishes, its status and duration.
+fn run_traced<F>(key: &'static str, run: &F) -> Result<CheckResult>
+where
+    F: Fn(&'static str) -> Result<CheckResult>,
+{
+    let span = tracing::info_span!(
+        "analyzer",
+        name = key,
+        status = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
     );
-    Ok(report)
+    let _entered = span.enter();
+    tracing::debug!("analyzer started");
+    let start = Instant::now();
+    let outcome = run_guarded(key, run);
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let status = match &outcome {
+        Ok(check) => format!("{:?}", check.status).to_lowercase(),
+        Err(_) => "error".to_string(),
+    };
+    span.record("status", status.as_str());
+    span.record("duration_ms", duration_ms);
+    tracing::info!(status = status.as_str(), duration_ms, "analyzer finished");
+    outcome
 }
 
-fn run_fmt(workspace_root: &Path) -> Result<CheckResult> {
+/// Runs one analyzer, turning a panic into a failed check so the rest of the
+/// report still completes.
+fn run_guarded<F>(key: &'static str, run: &F) -> Result<CheckResult>
+where
+    F: Fn(&'static str) -> Result<CheckResult>,
+{
+    std::panic::catch_unwind(AssertUnwindSafe(|| run(key))).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".into());
+        Ok(CheckResult {
+            name: check_name(key).into(),
+            status: CheckStatus::Fail,
+            details: format!("analyzer panicked: {message}"),
+            log_path: None,
+            remediation: None,
+        })
+    })
+}
+
+fn skipped(key: &str, reason: String) -> CheckResult {
+    CheckResult {
+        name: check_name(key).into(),
+        status: CheckStatus::Skipped,
+        details: reason,
+        log_path: None,
+        remediation: None,
+    }
+}
+
+fn fmt_args(scope: &ValidationScope) -> Vec<String> {
+    let mut args = vec!["fmt".to_string()];
+    if scope.packages.is_empty() {
+        args.push("--all".into());
+    } else {
+        args.extend(scope.package_args());
+    }
+    args.extend(["--".into(), "--check".into()]);
+    args
+}
+
+fn clippy_args(scope: &ValidationScope, toggles: &AnalyzerToggles) -> Vec<String> {
+    let mut args = vec!["clippy".to_string()];
+    args.extend(scope.package_args());
+    args.extend(["--all-targets", "--all-features", "--"].map(String::from));
+    args.extend(toggles.clippy_lint_args());
+    args
+}
+
+/// How `fmt` and `clippy` invoke cargo. They are never retried.
+struct CommandSettings<'a> {
+    program: &'a str,
+    capture: OutputCapture,
+    env: Vec<(&'static str, String)>,
+}
+
+fn run_fmt(
+    workspace_root: &Path,
+    scope: &ValidationScope,
+    command: &CommandSettings,
+) -> Result<CheckResult> {
     run_command(
         "fmt",
         workspace_root,
-        "cargo",
-        ["fmt", "--all", "--", "--check"],
+        command.program,
+        fmt_args(scope),
+        RetryPolicy::NONE,
+        command.capture,
+        &command.env,
     )
 }
 
-fn run_clippy(workspace_root: &Path) -> Result<CheckResult> {
+fn run_clippy(
+    workspace_root: &Path,
+    scope: &ValidationScope,
+    toggles: &AnalyzerToggles,
+    command: &CommandSettings,
+) -> Result<CheckResult> {
     run_command(
         "clippy",
         workspace_root,
-        "cargo",
-        [
-            "clippy",
-            "--all-targets",
-            "--all-features",
-            "--",
-            "-D",
-            "warnings",
-        ],
+        command.program,
+        clippy_args(scope, toggles),
+        RetryPolicy::NONE,
+        command.capture,
+        &command.env,
     )
 }
 
+/// How a command analyzer's output becomes its check details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputCapture {
+    /// Stdout, then stderr under a `--- stderr ---` marker.
+    Separate,
+    /// Both streams written to one file, so lines keep the order the command
+    /// printed them in (a clippy error next to its context).
+    Combined,
+}
+
+impl OutputCapture {
+    fn for_analyzer(toggles: &AnalyzerToggles, key: &str) -> Self {
+        if toggles.combined_output(key) {
+            Self::Combined
+        } else {
+            Self::Separate
+        }
+    }
+}
+
+/// Reruns a command that exits nonzero or outlives `timeout`, for transient
+/// failures such as a registry fetch. Meant for custom analyzers; the cargo
+/// analyzers run once with [`RetryPolicy::NONE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RetryPolicy {
+    retries: u32,
+    backoff: Duration,
+    /// Bound on each attempt; a command still running is killed and the
+    /// attempt counts as failed.
+    timeout: Option<Duration>,
+}
+
+impl RetryPolicy {
+    const NONE: Self = Self {
+        retries: 0,
+        backoff: Duration::ZERO,
+        timeout: None,
+    };
+}
+
 fn run_command(
     name: &str,
     workspace_root: &Path,
     cmd: &str,
-    args: impl IntoIterator<Item = &'static str>,
+    args: impl IntoIterator<Item = String>,
+    retry: RetryPolicy,
+    capture: OutputCapture,
+    env: &[(&str, String)],
 ) -> Result<CheckResult> {
-    let output = std::process::Command::new(cmd)
-        .args(args)
-        .current_dir(workspace_root)
-        .output()
-        .with_context(|| format!("{name} command failed to start"))?;
+    let args: Vec<String> = args.into_iter().collect();
+    let mut attempts = 0;
+    let (output, timed_out) = loop {
+        attempts += 1;
+        let mut command = std::process::Command::new(cmd);
+        command
+            .args(&args)
+            .current_dir(workspace_root)
+            .envs(env.iter().map(|(key, value)| (key, value)));
+        let (output, timed_out) =
+            run_attempt(&mut command, capture, retry.timeout).map_err(|source| {
+                GuardrailError::AnalyzerSpawn {
+                    analyzer: name.to_string(),
+                    source,
+                }
+            })?;
+        if (output.status.success() && !timed_out) || attempts > retry.retries {
+            break (output, timed_out);
+        }
+        std::thread::sleep(retry.backoff);
+    };
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -99,58 +991,290 @@ fn run_command(
         details.push_str(stderr.trim());
     }
 
-    let status = if output.status.success() {
+    let status = if output.status.success() && !timed_out {
         CheckStatus::Pass
     } else {
         CheckStatus::Fail
     };
+    if let Some(timeout) = retry.timeout.filter(|_| timed_out) {
+        if !details.is_empty() {
+            details.push('\n');
+        }
+        details.push_str(&format!("(timed out after {}ms)", timeout.as_millis()));
+    }
+    if attempts > 1 {
+        if !details.is_empty() {
+            details.push('\n');
+        }
+        details.push_str(&format!("(after {attempts} attempts)"));
+    }
 
     Ok(CheckResult {
         name: name.to_string(),
         status,
         details,
         log_path: None,
+        remediation: None,
     })
 }
 
-fn run_deterministic_scan(workspace_root: &Path) -> Result<CheckResult> {
-    let mut offenders = Vec::new();
+/// Runs `command` once with its output in temporary files, killing it if it
+/// outlives `timeout`; the flag says whether it did. With
+/// [`OutputCapture::Combined`] stdout and stderr share one file, and so a
+/// file offset, so it holds the output in the order it was written; it comes
+/// back as `stdout` with `stderr` empty.
+fn run_attempt(
+    command: &mut std::process::Command,
+    capture: OutputCapture,
+    timeout: Option<Duration>,
+) -> std::io::Result<(std::process::Output, bool)> {
+    let mut stdout_log = tempfile::tempfile()?;
+    let mut stderr_log = match capture {
+        OutputCapture::Separate => Some(tempfile::tempfile()?),
+        OutputCapture::Combined => None,
+    };
+    let stderr = match &stderr_log {
+        Some(log) => log.try_clone()?,
+        None => stdout_log.try_clone()?,
+    };
+    let mut child = command
+        .stdin(std::process::Stdio::null())
+        .stdout(stdout_log.try_clone()?)
+        .stderr(stderr)
+        .spawn()?;
+    let (status, timed_out) = match timeout {
+        None => (child.wait()?, false),
+        Some(timeout) => {
+            let started = Instant::now();
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    break (status, false);
+                }
+                if started.elapsed() >= timeout {
+                    child.kill()?;
+                    break (child.wait()?, true);
+                }
+                std::thread::sleep(Duration::from_millis(10).min(timeout));
+            }
+        }
+    };
+    let read = |log: &mut std::fs::File| -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        log.seek(SeekFrom::Start(0))?;
+        log.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    };
+    let output = std::process::Output {
+        status,
+        stdout: read(&mut stdout_log)?,
+        stderr: match stderr_log.as_mut() {
+            Some(log) => read(log)?,
+            None => Vec::new(),
+        },
+    };
+    Ok((output, timed_out))
+}
+
+/// Drops every item marked `#[cfg(test)]`: from the attribute to the end of
+/// the item's brace block, or to its `;` for items without a body. Braces are
+/// matched textually, so braces inside strings or comments can end the skip
+/// early or late.
+fn strip_cfg_test_items(contents: &str) -> String {
+    const ATTRIBUTE: &str = "#[cfg(test)]";
+    let mut kept = String::with_capacity(contents.len());
+    let mut rest = contents;
+    while let Some(start) = rest.find(ATTRIBUTE) {
+        kept.push_str(&rest[..start]);
+        let item = &rest[start + ATTRIBUTE.len()..];
+        let Some(open) = item.find(['{', ';']) else {
+            return kept;
+        };
+        if item.as_bytes()[open] == b';' {
+            rest = &item[open + 1..];
+            continue;
+        }
+        let mut depth = 0usize;
+        let mut end = item.len();
+        for (idx, ch) in item[open..].char_indices() {
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = open + idx + 1;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        rest = &item[end..];
+    }
+    kept.push_str(rest);
+    kept
+}
+
+/// Whether `path` (relative to the workspace) lives in a `tests/` or
+/// `benches/` directory.
+fn is_test_path(path: &Path) -> bool {
+    path.components()
+        .any(|component| matches!(component.as_os_str().to_str(), Some("tests" | "benches")))
+}
+
+/// Workspace-relative directories a scan covers; empty means the whole
+/// workspace.
+fn display_roots(paths: &[PathBuf]) -> String {
+    if paths.is_empty() {
+        return ".".into();
+    }
+    let roots: Vec<String> = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    roots.join(", ")
+}
+
+/// Absolute directories to walk for `paths`, or the workspace root when it is
+/// empty.
+fn scan_roots(workspace_root: &Path, paths: &[PathBuf]) -> Vec<PathBuf> {
+    if paths.is_empty() {
+        vec![workspace_root.to_path_buf()]
+    } else {
+        paths.iter().map(|path| workspace_root.join(path)).collect()
+    }
+}
+
+/// `analyzers.snapshots.dirs`, falling back to the validation scope.
+fn snapshot_roots(toggles: &AnalyzerToggles, scope: &ValidationScope) -> Vec<PathBuf> {
+    let dirs = toggles.snapshot_dirs();
+    if dirs.is_empty() {
+        scope.paths.clone()
+    } else {
+        dirs.to_vec()
+    }
+}
+
+fn active_determinism_rules(
+    disabled: &[String],
+) -> impl Iterator<Item = &'static DeterminismRule> + '_ {
+    DETERMINISM_RULES
+        .iter()
+        .filter(|rule| !disabled.iter().any(|name| name == rule.name))
+}
+
+/// Fails with each file that matches one of the [`DETERMINISM_RULES`] not in
+/// `disabled_rules`, grouped by rule, and the matched rules' fixes as the
+/// remediation.
+fn run_deterministic_scan(
+    workspace_root: &Path,
+    scope: &ValidationScope,
+    exclude_tests: bool,
+    disabled_rules: &[String],
+) -> Result<CheckResult> {
+    let rules: Vec<&DeterminismRule> = active_determinism_rules(disabled_rules).collect();
+    let mut offenders: Vec<Vec<String>> = vec![Vec::new(); rules.len()];
     let guardrail_core_root = workspace_root.join("crates").join("guardrail_core");
-    for entry in WalkDir::new(workspace_root)
+    let entries = scan_roots(workspace_root, &scope.paths)
         .into_iter()
-        .filter_entry(|e| filter_entry(e.path()))
-    {
+        .flat_map(|root| {
+            WalkDir::new(&root)
+                .into_iter()
+                .filter_entry(|e| filter_entry(e.path()))
+                .map(move |entry| entry.map_err(walk_error(&root)))
+        });
+    for entry in entries {
         let entry = entry?;
         let path = entry.path();
         if path.starts_with(&guardrail_core_root) {
             continue;
         }
         if path.extension().is_some_and(|ext| ext == "rs") {
-            let contents = std::fs::read_to_string(path)?;
-            if contents.contains("thread_rng()") || contents.contains("thread_rng(") {
-                offenders.push(
-                    path.strip_prefix(workspace_root)
-                        .unwrap()
-                        .display()
-                        .to_string(),
-                );
+            let relative = path.strip_prefix(workspace_root).unwrap();
+            if exclude_tests && is_test_path(relative) {
+                continue;
+            }
+            let mut contents = read_file("source file", path)?;
+            if exclude_tests {
+                contents = strip_cfg_test_items(&contents);
+            }
+            for (rule, files) in rules.iter().zip(&mut offenders) {
+                if contents.lines().any(|line| rule.matches(line)) {
+                    files.push(relative.display().to_string());
+                }
             }
         }
     }
 
-    if offenders.is_empty() {
+    let found: Vec<(&DeterminismRule, Vec<String>)> = rules
+        .into_iter()
+        .zip(offenders)
+        .filter(|(_, files)| !files.is_empty())
+        .collect();
+    if found.is_empty() {
         Ok(CheckResult {
             name: "deterministic_seed_scan".into(),
             status: CheckStatus::Pass,
-            details: "No non-deterministic RNG usage detected".into(),
+            details: "No non-deterministic RNG, clock, or hashing usage detected".into(),
             log_path: None,
+            remediation: None,
         })
     } else {
+        let details: Vec<String> = found
+            .iter()
+            .map(|(rule, files)| format!("Found {} usage in:\n{}", rule.label, files.join("\n")))
+            .collect();
+        let fixes: Vec<&str> = found.iter().map(|(rule, _)| rule.remediation).collect();
         Ok(CheckResult {
             name: "deterministic_seed_scan".into(),
             status: CheckStatus::Fail,
-            details: format!("Found thread_rng usage in:\n{}", offenders.join("\n")),
+            details: details.join("\n\n"),
             log_path: None,
+            remediation: Some(fixes.join("; ")),
+        })
+    }
+}
+
+/// Fails when any file under `dirs` (workspace-relative; the whole workspace
+/// when empty) ends in `.{extension}`, i.e. an `insta` snapshot nobody
+/// accepted or rejected.
+fn run_snapshot_scan(
+    workspace_root: &Path,
+    dirs: &[PathBuf],
+    extension: &str,
+) -> Result<CheckResult> {
+    let suffix = format!(".{extension}");
+    let mut pending = Vec::new();
+    for root in scan_roots(workspace_root, dirs) {
+        let entries = WalkDir::new(&root)
+            .into_iter()
+            .filter_entry(|e| filter_entry(e.path()));
+        for entry in entries {
+            let entry = entry.map_err(walk_error(&root))?;
+            if entry.file_type().is_file() && entry.file_name().to_string_lossy().ends_with(&suffix)
+            {
+                let path = entry.path();
+                let relative = path.strip_prefix(workspace_root).unwrap_or(path);
+                pending.push(relative.display().to_string());
+            }
+        }
+    }
+    pending.sort();
+
+    if pending.is_empty() {
+        Ok(CheckResult {
+            name: "snapshot_scan".into(),
+            status: CheckStatus::Pass,
+            details: format!("No pending *{suffix} snapshots"),
+            log_path: None,
+            remediation: None,
+        })
+    } else {
+        Ok(CheckResult {
+            name: "snapshot_scan".into(),
+            status: CheckStatus::Fail,
+            details: format!("Found pending snapshots:\n{}", pending.join("\n")),
+            log_path: None,
+            remediation: None,
         })
     }
 }
@@ -179,6 +1303,7 @@ fn run_bevy_checks(workspace_root: &Path) -> Result<CheckResult> {
             status: CheckStatus::Pass,
             details: "FixedUpdate + sandbox wiring detected".into(),
             log_path: None,
+            remediation: None,
         })
     } else {
         Ok(CheckResult {
@@ -186,10 +1311,56 @@ fn run_bevy_checks(workspace_root: &Path) -> Result<CheckResult> {
             status: CheckStatus::Fail,
             details: missing.join("\n"),
             log_path: None,
+            remediation: None,
         })
     }
 }
 
+/// Warns about functions the diff adds that exceed `max_lines`, with one
+/// medium-severity risk each. See [`diff::added_functions`] for what the
+/// heuristic can miss.
+fn run_complexity_scan(diff: &str, max_lines: usize) -> (CheckResult, Vec<RiskEntry>) {
+    let oversized: Vec<diff::AddedFunction> = diff::added_functions(diff)
+        .into_iter()
+        .filter(|function| function.lines > max_lines)
+        .collect();
+    let risks: Vec<RiskEntry> = oversized
+        .iter()
+        .map(|function| RiskEntry {
+            category: "complexity".into(),
+            description: format!(
+                "fn {} at {}:{} spans {} lines (limit {max_lines})",
+                function.name,
+                function.path.display(),
+                function.line,
+                function.lines
+            ),
+            severity: "medium".into(),
+            recommended_action: Some("Split the function into smaller helpers".into()),
+        })
+        .collect();
+
+    let check = if risks.is_empty() {
+        CheckResult {
+            name: "complexity_scan".into(),
+            status: CheckStatus::Pass,
+            details: format!("No added function exceeds {max_lines} lines"),
+            log_path: None,
+            remediation: None,
+        }
+    } else {
+        let details: Vec<&str> = risks.iter().map(|risk| risk.description.as_str()).collect();
+        CheckResult {
+            name: "complexity_scan".into(),
+            status: CheckStatus::Warn,
+            details: details.join("\n"),
+            log_path: None,
+            remediation: None,
+        }
+    };
+    (check, risks)
+}
+
 fn dir_contains_token(dir: &Path, token: &str) -> bool {
     for entry in WalkDir::new(dir).into_iter().flatten() {
         let path = entry.path();
@@ -217,3 +1388,1001 @@ fn filter_entry(path: &Path) -> bool {
     }
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DEFAULT_MAX_FUNCTION_LINES, DEFAULT_SNAPSHOT_EXTENSION};
+
+    fn check(key: &str, status: CheckStatus) -> CheckResult {
+        CheckResult {
+            name: check_name(key).into(),
+            status,
+            details: String::new(),
+            log_path: None,
+            remediation: None,
+        }
+    }
+
+    #[test]
+    fn failed_prerequisite_skips_dependents_transitively() {
+        let toggles: AnalyzerToggles = toml::from_str(
+            "[[pipeline]]\nname = \"fmt\"\nthen = [\"clippy\"]\n\n[[pipeline]]\nname = \"clippy\"\nthen = [\"bevy\"]\n",
+        )
+        .unwrap();
+        let ran = std::sync::Mutex::new(Vec::new());
+        let checks = execute_pipeline(&toggles, |key| {
+            ran.lock().unwrap().push(key);
+            let status = if key == "fmt" {
+                CheckStatus::Fail
+            } else {
+                CheckStatus::Pass
+            };
+            Ok(check(key, status))
+        })
+        .unwrap();
+
+        let mut ran = ran.into_inner().unwrap();
+        ran.sort_unstable();
+        assert_eq!(ran, vec!["complexity", "deterministic", "fmt", "snapshots"]);
+        let statuses: Vec<(&str, CheckStatus)> = checks
+            .iter()
+            .map(|check| (check.name.as_str(), check.status.clone()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("fmt", CheckStatus::Fail),
+                ("clippy", CheckStatus::Skipped),
+                ("deterministic_seed_scan", CheckStatus::Pass),
+                ("bevy_sandbox_checks", CheckStatus::Skipped),
+                ("complexity_scan", CheckStatus::Pass),
+                ("snapshot_scan", CheckStatus::Pass),
+            ]
+        );
+        assert_eq!(checks[1].details, "prerequisite failed: fmt");
+        assert_eq!(checks[3].details, "prerequisite failed: clippy");
+    }
+
+    #[test]
+    fn scoped_scan_ignores_offenders_outside_scope() {
+        let root = std::env::temp_dir().join(format!("guardrail-scope-{}", std::process::id()));
+        for (krate, source) in [
+            ("scoped", "fn ok() {}\n"),
+            ("other", "let r = thread_rng();\n"),
+        ] {
+            let src = root.join("crates").join(krate).join("src");
+            std::fs::create_dir_all(&src).unwrap();
+            std::fs::write(
+                root.join("crates").join(krate).join("Cargo.toml"),
+                format!("[package]\nname = \"{krate}\"\n"),
+            )
+            .unwrap();
+            std::fs::write(src.join("lib.rs"), source).unwrap();
+        }
+
+        let whole = run_deterministic_scan(&root, &ValidationScope::default(), false, &[]).unwrap();
+        assert_eq!(whole.status, CheckStatus::Fail);
+
+        let config = ScopeConfig {
+            packages: vec!["scoped".into()],
+            paths: Vec::new(),
+        };
+        let scope = ValidationScope::resolve(&config, &root).unwrap();
+        assert_eq!(scope.paths, vec![PathBuf::from("crates/scoped")]);
+        let scoped = run_deterministic_scan(&root, &scope, false, &[]).unwrap();
+        assert_eq!(scoped.status, CheckStatus::Pass);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn exclude_tests_ignores_thread_rng_in_test_code() {
+        let root = std::env::temp_dir().join(format!("guardrail-tests-{}", std::process::id()));
+        let krate = root.join("crates").join("sim");
+        std::fs::create_dir_all(krate.join("src")).unwrap();
+        std::fs::create_dir_all(krate.join("tests")).unwrap();
+        std::fs::write(
+            krate.join("src").join("lib.rs"),
+            "pub fn roll(rng: &mut StdRng) -> u32 { rng.gen() }\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn fuzz() {\n        let mut rng = thread_rng();\n        if true { rng.gen::<u32>(); }\n    }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            krate.join("tests").join("props.rs"),
+            "#[test]\nfn prop() { let _ = thread_rng(); }\n",
+        )
+        .unwrap();
+
+        let scope = ValidationScope::default();
+        let strict = run_deterministic_scan(&root, &scope, false, &[]).unwrap();
+        assert_eq!(strict.status, CheckStatus::Fail);
+        assert!(strict.details.contains("lib.rs"), "{}", strict.details);
+        assert!(strict.details.contains("props.rs"), "{}", strict.details);
+        let lenient = run_deterministic_scan(&root, &scope, true, &[]).unwrap();
+        assert_eq!(lenient.status, CheckStatus::Pass, "{}", lenient.details);
+
+        std::fs::write(
+            krate.join("src").join("lib.rs"),
+            "#[cfg(test)]\nuse rand::thread_rng;\n\npub fn seed() -> u64 { thread_rng().gen() }\n",
+        )
+        .unwrap();
+        let lenient = run_deterministic_scan(&root, &scope, true, &[]).unwrap();
+        assert_eq!(lenient.status, CheckStatus::Fail);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn determinism_rules_flag_their_fixture_and_can_be_disabled() {
+        let root = std::env::temp_dir().join(format!("guardrail-rules-{}", std::process::id()));
+        let src = root.join("crates").join("sim").join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let scope = ValidationScope::default();
+        let fixtures = [
+            (
+                "thread_rng",
+                include_str!("../tests/fixtures/determinism/thread_rng.rs"),
+            ),
+            (
+                "instant_now",
+                include_str!("../tests/fixtures/determinism/instant_now.rs"),
+            ),
+            (
+                "system_time_now",
+                include_str!("../tests/fixtures/determinism/system_time_now.rs"),
+            ),
+            (
+                "std_hash_map",
+                include_str!("../tests/fixtures/determinism/std_hash_map.rs"),
+            ),
+            (
+                "random_state",
+                include_str!("../tests/fixtures/determinism/random_state.rs"),
+            ),
+        ];
+        assert_eq!(fixtures.len(), DETERMINISM_RULES.len());
+
+        for (rule, (name, source)) in DETERMINISM_RULES.iter().zip(fixtures) {
+            assert_eq!(rule.name, name);
+            std::fs::write(src.join("lib.rs"), source).unwrap();
+            let check = run_deterministic_scan(&root, &scope, false, &[]).unwrap();
+            assert_eq!(check.status, CheckStatus::Fail, "{name}");
+            assert_eq!(
+                check.details,
+                format!("Found {} usage in:\ncrates/sim/src/lib.rs", rule.label)
+            );
+            assert_eq!(check.remediation.as_deref(), Some(rule.remediation));
+
+            let disabled = run_deterministic_scan(&root, &scope, false, &[name.into()]).unwrap();
+            assert_eq!(disabled.status, CheckStatus::Pass, "{}", disabled.details);
+        }
+
+        std::fs::write(
+            src.join("lib.rs"),
+            include_str!("../tests/fixtures/determinism/clean.rs"),
+        )
+        .unwrap();
+        let clean = run_deterministic_scan(&root, &scope, false, &[]).unwrap();
+        assert_eq!(clean.status, CheckStatus::Pass, "{}", clean.details);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn example_config_passes_the_deterministic_scan_of_this_workspace() {
+        let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let config = GuardrailConfig::from_path(
+            &workspace.join("tools/llm_guardrail_cli/guardrail.example.toml"),
+        )
+        .unwrap();
+        assert!(config.analyzers.deterministic_enabled());
+        let scope = ValidationScope::resolve(&config.scope, &workspace).unwrap();
+        let check = run_deterministic_scan(
+            &workspace,
+            &scope,
+            config.analyzers.deterministic_exclude_tests(),
+            config.analyzers.deterministic_disabled_rules(),
+        )
+        .unwrap();
+        assert_eq!(check.status, CheckStatus::Pass, "{}", check.details);
+    }
+
+    #[test]
+    fn run_from_config_writes_the_configured_report() {
+        let root = std::env::temp_dir().join(format!("guardrail-run-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        for source in ["prompt.md", "response.md", "patch.diff"] {
+            std::fs::write(root.join(source), "").unwrap();
+        }
+        let report_path = root.join("reports").join("latest.json");
+        let config = format!(
+            "[sources]\nprompt = {:?}\nresponse = {:?}\ndiff = {:?}\n\n[analyzers]\nfmt = false\nclippy = false\ndeterministic = false\nbevy = false\ncomplexity = false\nsnapshots = false\n\n[report]\npath = {:?}\n",
+            root.join("prompt.md"),
+            root.join("response.md"),
+            root.join("patch.diff"),
+            report_path,
+        );
+        let config_path = root.join("guardrail.toml");
+        std::fs::write(&config_path, config).unwrap();
+
+        let report = run_from_config(
+            &config_path,
+            ValidationOptions::new(root.clone(), "embedded"),
+        )
+        .unwrap();
+        assert_eq!(report.id, "embedded");
+        assert_eq!(report.summary.checks.skipped, ANALYZER_KEYS.len());
+        let written: GuardrailReport =
+            serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
+        assert_eq!(written.id, "embedded");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn patch_series_runs_in_the_worktree_copy_of_a_nested_workspace() {
+        let repo = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .current_dir(repo.path())
+                .args([
+                    "-c",
+                    "user.name=guardrail",
+                    "-c",
+                    "user.email=guardrail@example.com",
+                ])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?}");
+        };
+        let root = repo.path().join("game");
+        std::fs::create_dir_all(root.join("llm")).unwrap();
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&["init", "--quiet"]);
+        git(&["add", "game/lib.rs"]);
+        git(&["commit", "--quiet", "-m", "initial"]);
+        std::fs::write(
+            root.join("llm").join("1.diff"),
+            "--- a/game/lib.rs\n+++ b/game/lib.rs\n@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n",
+        )
+        .unwrap();
+        let config_path = root.join("guardrail.toml");
+        std::fs::write(
+            &config_path,
+            format!(
+                "[sources]\nprompt = {:?}\ndiff = [{:?}]\n\n[analyzers]\nfmt = false\nclippy = false\ndeterministic = false\nbevy = false\nsnapshots = false\n",
+                root.join("llm").join("prompt.md"),
+                root.join("llm").join("1.diff"),
+            ),
+        )
+        .unwrap();
+        std::fs::write(root.join("llm").join("prompt.md"), "").unwrap();
+        let worktrees = || {
+            let listed = std::process::Command::new("git")
+                .current_dir(repo.path())
+                .args(["worktree", "list"])
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&listed.stdout).lines().count()
+        };
+
+        let plan =
+            plan_from_config(&config_path, ValidationOptions::new(root.clone(), "plan")).unwrap();
+        assert!(!plan.generated_only);
+        assert!(!root.join("llm").join("combined.diff").exists());
+        assert_eq!(worktrees(), 1);
+
+        let (config, options) =
+            load_config(&config_path, ValidationOptions::new(root.clone(), "run")).unwrap();
+        let (config, options, worktree) = prepare_sources(config, options).unwrap();
+        let worktree = worktree.unwrap();
+        assert_eq!(options.workspace_root, worktree.path().join("game"));
+        assert_eq!(
+            std::fs::read_to_string(options.workspace_root.join("lib.rs")).unwrap(),
+            "fn a() {}\nfn b() {}\n"
+        );
+        let target_dir = match std::env::var_os("CARGO_TARGET_DIR") {
+            Some(dir) => std::env::current_dir().unwrap().join(dir),
+            None => root.join("target"),
+        };
+        assert!(child_env(&config, &options)
+            .contains(&("CARGO_TARGET_DIR", target_dir.display().to_string())));
+        assert!(root.join("llm").join("combined.diff").is_file());
+        drop(worktree);
+        assert_eq!(worktrees(), 1);
+    }
+
+    #[test]
+    fn dry_run_keeps_the_git_diff_in_memory() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .current_dir(root)
+                .args([
+                    "-c",
+                    "user.name=guardrail",
+                    "-c",
+                    "user.email=guardrail@example.com",
+                ])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?}");
+        };
+        std::fs::create_dir_all(root.join("llm")).unwrap();
+        std::fs::write(root.join("llm").join("prompt.md"), "").unwrap();
+        std::fs::write(root.join("Cargo.lock"), "version = 3\n").unwrap();
+        git(&["init", "--quiet"]);
+        git(&["add", "Cargo.lock"]);
+        git(&["commit", "--quiet", "-m", "initial"]);
+        std::fs::write(root.join("Cargo.lock"), "version = 4\n").unwrap();
+        let config_path = root.join("guardrail.toml");
+        std::fs::write(
+            &config_path,
+            "[sources]\nprompt = \"llm/prompt.md\"\ndiff_from_git = \"HEAD\"\ngenerated_globs = [\"*.lock\"]\n",
+        )
+        .unwrap();
+        let options = || ValidationOptions::new(root.to_path_buf(), "plan").with_sources_root(root);
+
+        let plan = plan_from_config(&config_path, options()).unwrap();
+        assert!(plan.generated_only);
+        assert!(!root.join("llm").join("git.diff").exists());
+
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&["add", "-N", "lib.rs"]);
+        let plan = plan_from_config(&config_path, options()).unwrap();
+        assert!(!plan.generated_only);
+        assert!(!root.join("llm").join("git.diff").exists());
+    }
+
+    #[test]
+    fn inline_diff_replaces_the_sources_section() {
+        let root = std::env::temp_dir().join(format!("guardrail-inline-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let config_path = root.join("guardrail.toml");
+        std::fs::write(
+            &config_path,
+            "[analyzers]\nfmt = false\nclippy = false\ndeterministic = false\nbevy = false\nsnapshots = false\nmax_function_lines = 20\n",
+        )
+        .unwrap();
+
+        let err = run_from_config(&config_path, ValidationOptions::new(root.clone(), "none"))
+            .unwrap_err();
+        assert!(
+            matches!(&err, GuardrailError::InvalidConfig(message) if message.contains("diff")),
+            "{err}"
+        );
+
+        let diff = include_str!("../tests/fixtures/long_function.diff");
+        let report = run_from_config(
+            &config_path,
+            ValidationOptions::new(root.clone(), "inline").with_diff_inline(diff),
+        )
+        .unwrap();
+        assert_eq!(report.source.diff_path, Path::new(INLINE_DIFF_PATH));
+        let complexity = report
+            .checks
+            .iter()
+            .find(|check| check.name == "complexity_scan")
+            .unwrap();
+        assert_eq!(complexity.status, CheckStatus::Warn);
+        assert_eq!(report.risks.len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn compact_reports_are_one_line_and_round_trip() {
+        let root = std::env::temp_dir().join(format!("guardrail-compact-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        for source in ["prompt.md", "response.md", "patch.diff"] {
+            std::fs::write(root.join(source), "").unwrap();
+        }
+        let config = |report: &Path, compact: bool| {
+            format!(
+                "[sources]\nprompt = {:?}\nresponse = {:?}\ndiff = {:?}\n\n[analyzers]\nfmt = false\nclippy = false\n\n[report]\npath = {:?}\ncompact = {compact}\n",
+                root.join("prompt.md"),
+                root.join("response.md"),
+                root.join("patch.diff"),
+                report,
+            )
+        };
+        let run = |name: &str, compact_config: bool, options: ValidationOptions| {
+            let report_path = root.join(format!("{name}.json"));
+            let config_path = root.join(format!("{name}.toml"));
+            std::fs::write(&config_path, config(&report_path, compact_config)).unwrap();
+            let report = run_from_config(&config_path, options).unwrap();
+            (report, std::fs::read_to_string(report_path).unwrap())
+        };
+
+        let (report, written) = run("config", true, ValidationOptions::new(root.clone(), "c"));
+        assert!(!written.contains('\n'), "{written}");
+        let parsed: GuardrailReport = serde_json::from_str(&written).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&report).unwrap()
+        );
+
+        let (_, written) = run(
+            "flag",
+            false,
+            ValidationOptions::new(root.clone(), "f").with_compact_report(),
+        );
+        assert!(!written.contains('\n'), "{written}");
+        let (_, written) = run("pretty", false, ValidationOptions::new(root.clone(), "p"));
+        assert!(written.contains("\n  \"id\": \"p\""), "{written}");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn failing_deterministic_scan_suggests_a_fix() {
+        let root = std::env::temp_dir().join(format!("guardrail-fix-{}", std::process::id()));
+        let src = root.join("crates").join("sim").join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "pub fn seed() {}\n").unwrap();
+        let config: GuardrailConfig =
+            toml::from_str("[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n").unwrap();
+        let options = ValidationOptions::new(root.clone(), "fix");
+
+        let (passing, _) = run_analyzer("deterministic", &config, &options).unwrap();
+        assert_eq!(passing.status, CheckStatus::Pass);
+        assert_eq!(passing.remediation, None);
+
+        std::fs::write(src.join("lib.rs"), "pub fn seed() { thread_rng(); }\n").unwrap();
+        let (failing, _) = run_analyzer("deterministic", &config, &options).unwrap();
+        assert_eq!(failing.status, CheckStatus::Fail);
+        let fix = failing
+            .remediation
+            .clone()
+            .expect("failing scan carries a fix");
+        assert!(fix.contains("seeded `StdRng`"), "{fix}");
+
+        let report = GuardrailReport::builder("fix").check(failing).build();
+        assert_eq!(report.next_actions.len(), 1);
+        assert!(report.next_actions[0]
+            .description
+            .starts_with("deterministic_seed_scan: replace"));
+
+        assert_eq!(remediation("fmt"), Some("run `cargo fmt --all`"));
+        assert!(remediation("clippy").is_some_and(|fix| fix.contains("cargo clippy --fix")));
+        assert_eq!(remediation("bevy"), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn plan_lists_analyzers_in_execution_order_without_running_them() {
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[analyzers]\nbevy = false\n\n[[analyzers.pipeline]]\nname = \"fmt\"\nthen = [\"clippy\"]\n",
+        )
+        .unwrap();
+        let options = ValidationOptions::new(PathBuf::from("/nonexistent"), "plan").with_scope(
+            ValidationScope {
+                packages: vec!["core_game".into()],
+                paths: vec![PathBuf::from("crates/core_game")],
+            },
+        );
+
+        let plan = plan_validations(&config, &options).unwrap();
+        assert!(!plan.generated_only);
+        let order: Vec<(&str, usize, bool)> = plan
+            .analyzers
+            .iter()
+            .map(|analyzer| (analyzer.key, analyzer.wave, analyzer.enabled))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("fmt", 1, true),
+                ("deterministic", 1, true),
+                ("bevy", 1, false),
+                ("complexity", 1, true),
+                ("snapshots", 1, true),
+                ("clippy", 2, true),
+            ]
+        );
+        assert_eq!(plan.analyzers[5].after, vec!["fmt".to_string()]);
+        assert_eq!(
+            plan.analyzers[5].action,
+            "cargo clippy -p core_game --all-targets --all-features -- -D warnings"
+        );
+        assert_eq!(
+            plan.analyzers[1].action,
+            "scan *.rs for thread_rng, instant_now, system_time_now, std_hash_map, random_state under crates/core_game"
+        );
+        assert_eq!(
+            plan.analyzers[4].action,
+            "flag *.snap.new files under crates/core_game"
+        );
+    }
+
+    #[test]
+    fn profile_option_applies_the_named_profile() {
+        let root = std::env::temp_dir().join(format!("guardrail-profile-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let config_path = root.join("guardrail.toml");
+        std::fs::write(
+            &config_path,
+            "[analyzers]\nbevy = false\n\n[profiles.fast.analyzers]\nclippy = false\ndeterministic = false\n\n[profiles.full.analyzers]\nbevy = true\n",
+        )
+        .unwrap();
+        let enabled = |profile: Option<&str>| -> Result<Vec<&'static str>> {
+            let mut options = ValidationOptions::new(root.clone(), "profile").with_diff_inline("");
+            if let Some(profile) = profile {
+                options = options.with_profile(profile);
+            }
+            let plan = plan_from_config(&config_path, options)?;
+            Ok(plan
+                .analyzers
+                .iter()
+                .filter(|analyzer| analyzer.enabled)
+                .map(|analyzer| analyzer.key)
+                .collect())
+        };
+
+        assert_eq!(
+            enabled(None).unwrap(),
+            ["fmt", "clippy", "deterministic", "complexity", "snapshots"]
+        );
+        assert_eq!(
+            enabled(Some("fast")).unwrap(),
+            ["fmt", "complexity", "snapshots"]
+        );
+        assert_eq!(enabled(Some("full")).unwrap(), ANALYZER_KEYS);
+        let err = enabled(Some("nightly")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown profile `nightly`; defined profiles: fast, full"
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn clippy_command_carries_configured_lint_levels() {
+        let command = |toggles: &str| {
+            let toggles: AnalyzerToggles = toml::from_str(toggles).unwrap();
+            clippy_args(&ValidationScope::default(), &toggles).join(" ")
+        };
+        assert_eq!(
+            command(""),
+            "clippy --all-targets --all-features -- -D warnings"
+        );
+        assert_eq!(
+            command(
+                "[clippy]\ndeny = [\"warnings\", \"clippy::unwrap_used\"]\nallow = [\"clippy::too_many_arguments\"]\nextra_args = [\"-W\", \"clippy::pedantic\"]\n"
+            ),
+            "clippy --all-targets --all-features -- -D warnings -D clippy::unwrap_used -A clippy::too_many_arguments -W clippy::pedantic"
+        );
+        assert_eq!(
+            command("[clippy]\nallow = [\"dead_code\"]\n"),
+            "clippy --all-targets --all-features -- -D warnings -A dead_code"
+        );
+        assert_eq!(
+            command("[clippy]\ndeny = []\n"),
+            "clippy --all-targets --all-features --"
+        );
+        assert_eq!(command("[clippy]\nenabled = false\n"), command(""));
+    }
+
+    #[test]
+    fn snapshot_scan_flags_pending_snapshots() {
+        let root = std::env::temp_dir().join(format!("guardrail-snap-{}", std::process::id()));
+        let snapshots = root
+            .join("crates")
+            .join("sim")
+            .join("tests")
+            .join("snapshots");
+        std::fs::create_dir_all(&snapshots).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(snapshots.join("sim__replay.snap"), "").unwrap();
+        std::fs::write(root.join("target").join("stale.snap.new"), "").unwrap();
+
+        let clean = run_snapshot_scan(&root, &[], DEFAULT_SNAPSHOT_EXTENSION).unwrap();
+        assert_eq!(clean.status, CheckStatus::Pass, "{}", clean.details);
+
+        std::fs::write(snapshots.join("sim__replay.snap.new"), "").unwrap();
+        let pending = run_snapshot_scan(&root, &[], DEFAULT_SNAPSHOT_EXTENSION).unwrap();
+        assert_eq!(pending.status, CheckStatus::Fail);
+        let expected = Path::new("crates/sim/tests/snapshots/sim__replay.snap.new");
+        assert_eq!(
+            pending.details,
+            format!("Found pending snapshots:\n{}", expected.display())
+        );
+
+        let elsewhere =
+            run_snapshot_scan(&root, &[PathBuf::from("docs")], DEFAULT_SNAPSHOT_EXTENSION).unwrap();
+        assert_eq!(elsewhere.status, CheckStatus::Pass);
+        let inline = run_snapshot_scan(&root, &[], "pending-snap").unwrap();
+        assert_eq!(inline.status, CheckStatus::Pass);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn complexity_scan_flags_long_added_functions() {
+        let diff = include_str!("../tests/fixtures/long_function.diff");
+        let (check, risks) = run_complexity_scan(diff, DEFAULT_MAX_FUNCTION_LINES);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert_eq!(risks.len(), 1);
+        assert!(
+            risks[0]
+                .description
+                .starts_with("fn resolve_everything at crates/core_game/src/battle.rs:7 spans"),
+            "{}",
+            risks[0].description
+        );
+
+        let (check, risks) = run_complexity_scan(diff, 500);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(risks.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_command_retries_until_success() {
+        let root = std::env::temp_dir().join(format!("guardrail-retry-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let flaky = || {
+            vec![
+                "-c".to_string(),
+                "if [ -f attempted ]; then echo fetched; else touch attempted; exit 1; fi".into(),
+            ]
+        };
+
+        let once = run_command(
+            "asset_lint",
+            &root,
+            "sh",
+            flaky(),
+            RetryPolicy::NONE,
+            OutputCapture::Separate,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(once.status, CheckStatus::Fail);
+        std::fs::remove_file(root.join("attempted")).unwrap();
+
+        let retry = RetryPolicy {
+            retries: 2,
+            backoff: Duration::from_millis(1),
+            timeout: None,
+        };
+        let check = run_command(
+            "asset_lint",
+            &root,
+            "sh",
+            flaky(),
+            retry,
+            OutputCapture::Separate,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(check.details, "fetched\n(after 2 attempts)");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn each_attempt_is_bounded_by_the_timeout() {
+        let root = tempfile::tempdir().unwrap();
+        let hangs_once = || {
+            vec![
+                "-c".to_string(),
+                "if [ -f attempted ]; then echo done; else touch attempted; exec sleep 5; fi"
+                    .into(),
+            ]
+        };
+        let policy = |retries| RetryPolicy {
+            retries,
+            backoff: Duration::ZERO,
+            timeout: Some(Duration::from_millis(200)),
+        };
+
+        let hung = run_command(
+            "asset_lint",
+            root.path(),
+            "sh",
+            hangs_once(),
+            policy(0),
+            OutputCapture::Separate,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(hung.status, CheckStatus::Fail);
+        assert_eq!(hung.details, "(timed out after 200ms)");
+        std::fs::remove_file(root.path().join("attempted")).unwrap();
+
+        let check = run_command(
+            "asset_lint",
+            root.path(),
+            "sh",
+            hangs_once(),
+            policy(1),
+            OutputCapture::Combined,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(check.details, "done\n(after 2 attempts)");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cargo_analyzers_are_not_retried() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let cargo = root.path().join("flaky-cargo");
+        std::fs::write(
+            &cargo,
+            "#!/bin/sh\nif [ -f attempted ]; then echo \"$@\"; else touch attempted; exit 1; fi\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[analyzers]\nclippy = false\ndeterministic = false\nbevy = false\ncomplexity = false\nsnapshots = false\n",
+        )
+        .unwrap();
+        let options = ValidationOptions::new(root.path().to_path_buf(), "flaky")
+            .with_cargo(cargo.display().to_string());
+
+        let report = run_validations(&config, &options).unwrap();
+        let fmt = report
+            .checks
+            .iter()
+            .find(|check| check.name == "fmt")
+            .unwrap();
+        assert_eq!(fmt.status, CheckStatus::Fail, "{}", fmt.details);
+        assert!(!fmt.details.contains("attempts"), "{}", fmt.details);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn combined_capture_keeps_stdout_and_stderr_interleaved() {
+        let interleaved = || {
+            vec![
+                "-c".to_string(),
+                "echo 'error: unused variable'; echo '  --> src/lib.rs:3:9' >&2; echo 'error: aborting'; exit 1".into(),
+            ]
+        };
+        let root = std::env::temp_dir();
+
+        let separate = run_command(
+            "clippy",
+            &root,
+            "sh",
+            interleaved(),
+            RetryPolicy::NONE,
+            OutputCapture::Separate,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            separate.details,
+            "error: unused variable\nerror: aborting\n--- stderr ---\n--> src/lib.rs:3:9"
+        );
+
+        let combined = run_command(
+            "clippy",
+            &root,
+            "sh",
+            interleaved(),
+            RetryPolicy::NONE,
+            OutputCapture::Combined,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(combined.status, CheckStatus::Fail);
+        assert_eq!(
+            combined.details,
+            "error: unused variable\n  --> src/lib.rs:3:9\nerror: aborting"
+        );
+
+        let toggles: AnalyzerToggles = toml::from_str("combined_output = [\"clippy\"]\n").unwrap();
+        assert_eq!(
+            OutputCapture::for_analyzer(&toggles, "clippy"),
+            OutputCapture::Combined
+        );
+        assert_eq!(
+            OutputCapture::for_analyzer(&toggles, "fmt"),
+            OutputCapture::Separate
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clippy_sees_the_configured_seed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let cargo = root.path().join("seed-cargo");
+        std::fs::write(&cargo, format!("#!/bin/sh\necho ${SEED_ENV_VAR}\n")).unwrap();
+        std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[determinism]\nseed = 1234\n",
+        )
+        .unwrap();
+        let seen = |seed: Option<u64>| {
+            let mut options = ValidationOptions::new(root.path().to_path_buf(), "seeded")
+                .with_cargo(cargo.display().to_string());
+            options.seed = seed;
+            let (check, _) = run_analyzer("clippy", &config, &options).unwrap();
+            check.details
+        };
+
+        assert_eq!(seen(None), "1234");
+        assert_eq!(seen(Some(7)), "7");
+    }
+
+    #[test]
+    fn walk_failures_name_the_walked_root() {
+        let missing = tempfile::tempdir().unwrap().path().join("missing");
+        let err = package_manifests(&missing).unwrap_err();
+        assert!(
+            matches!(&err, GuardrailError::Walk { root, .. } if *root == missing),
+            "{err}"
+        );
+        assert!(err.to_string().contains(&missing.display().to_string()));
+    }
+
+    #[test]
+    fn panicking_analyzer_fails_without_losing_the_report() {
+        let toggles: AnalyzerToggles =
+            toml::from_str("[[pipeline]]\nname = \"clippy\"\nthen = [\"bevy\"]\n").unwrap();
+        let checks = execute_pipeline(&toggles, |key| {
+            if key == "clippy" {
+                panic!("malformed diff hunk");
+            }
+            Ok(check(key, CheckStatus::Pass))
+        })
+        .unwrap();
+
+        assert_eq!(checks.len(), ANALYZER_KEYS.len());
+        assert_eq!(checks[1].status, CheckStatus::Fail);
+        assert_eq!(checks[1].details, "analyzer panicked: malformed diff hunk");
+        assert_eq!(checks[3].details, "prerequisite failed: clippy");
+        let report = GuardrailReport::builder("panic").checks(checks).build();
+        assert_eq!(report.summary.status, crate::report::ReportStatus::Fail);
+        assert_eq!(report.summary.checks.pass, 4);
+    }
+
+    #[test]
+    fn disabled_analyzers_are_reported_as_skipped() {
+        let toggles: AnalyzerToggles =
+            toml::from_str("clippy = false\ndeterministic = false\n").unwrap();
+        let checks = execute_pipeline(&toggles, |key| Ok(check(key, CheckStatus::Pass))).unwrap();
+
+        let statuses: Vec<(&str, CheckStatus, &str)> = checks
+            .iter()
+            .map(|check| {
+                (
+                    check.name.as_str(),
+                    check.status.clone(),
+                    check.details.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("fmt", CheckStatus::Pass, ""),
+                ("clippy", CheckStatus::Skipped, "disabled in config"),
+                (
+                    "deterministic_seed_scan",
+                    CheckStatus::Skipped,
+                    "disabled in config"
+                ),
+                ("bevy_sandbox_checks", CheckStatus::Pass, ""),
+                ("complexity_scan", CheckStatus::Pass, ""),
+                ("snapshot_scan", CheckStatus::Pass, ""),
+            ]
+        );
+    }
+
+    /// Collects `name=status` for every closed `analyzer` span.
+    #[derive(Clone, Default)]
+    struct SpanRecorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    #[derive(Default)]
+    struct SpanFields(Vec<(String, String)>);
+
+    impl tracing::field::Visit for SpanFields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for SpanRecorder
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = SpanFields::default();
+            attrs.record(&mut fields);
+            ctx.span(id).unwrap().extensions_mut().insert(fields);
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let span = ctx.span(id).unwrap();
+            let mut extensions = span.extensions_mut();
+            if let Some(fields) = extensions.get_mut::<SpanFields>() {
+                values.record(fields);
+            }
+        }
+
+        fn on_close(&self, id: tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+            let span = ctx.span(&id).unwrap();
+            if span.name() != "analyzer" {
+                return;
+            }
+            let extensions = span.extensions();
+            let field = |name: &str| {
+                extensions
+                    .get::<SpanFields>()
+                    .and_then(|fields| fields.0.iter().find(|(key, _)| key == name))
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_default()
+            };
+            assert!(!field("duration_ms").is_empty(), "duration recorded");
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}={}", field("name"), field("status")));
+        }
+    }
+
+    #[test]
+    fn each_analyzer_runs_inside_a_span() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let recorder = SpanRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let toggles: AnalyzerToggles = toml::from_str("clippy = false\n").unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            execute_pipeline(&toggles, |key| {
+                let status = if key == "fmt" {
+                    CheckStatus::Warn
+                } else {
+                    CheckStatus::Pass
+                };
+                Ok(check(key, status))
+            })
+            .unwrap();
+        });
+
+        let mut spans = recorder.0.lock().unwrap().clone();
+        spans.sort();
+        assert_eq!(
+            spans,
+            vec![
+                "bevy=pass",
+                "complexity=pass",
+                "deterministic=pass",
+                "fmt=warn",
+                "snapshots=pass",
+            ]
+        );
+    }
+}
@@ -1,102 +1,862 @@
-use std::fs;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-use anyhow::{self, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+use crate::error::{read_file, GuardrailError, Result};
+
+/// Default for `analyzers.max_function_lines`.
+pub const DEFAULT_MAX_FUNCTION_LINES: usize = 100;
+
+/// Default for `analyzers.snapshots.extension`: pending `insta` snapshots.
+pub const DEFAULT_SNAPSHOT_EXTENSION: &str = "snap.new";
+
+/// Lint levels clippy runs with when `[analyzers.clippy]` doesn't set `deny`.
+pub const DEFAULT_CLIPPY_DENY: &[&str] = &["warnings"];
+
+/// Characters rejected in `[analyzers.clippy]` arguments. Arguments go
+/// straight to the process without a shell, so these only ever appear by
+/// mistake, e.g. a quoted `"-W clippy::pedantic"` meant as two arguments.
+const SHELL_METACHARACTERS: &[char] = &[
+    '|', '&', ';', '$', '`', '<', '>', '(', ')', '{', '}', '\\', '\'', '"', '*', '?', '!', '#',
+];
+
+/// File name [`GuardrailConfig::discover`] looks for.
+pub const CONFIG_FILE_NAME: &str = "guardrail.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuardrailConfig {
+    /// May be omitted when the diff is passed inline (see
+    /// [`crate::ValidationOptions::diff_inline`]).
+    #[serde(default)]
     pub sources: SourceConfig,
     #[serde(default)]
     pub analyzers: AnalyzerToggles,
     #[serde(default)]
     pub report: Option<ReportConfig>,
     #[serde(default)]
+    pub scope: ScopeConfig,
+    #[serde(default)]
+    pub determinism: DeterminismConfig,
+    #[serde(default)]
     pub targets: Option<TargetConfig>,
     #[serde(default)]
     pub telemetry: Option<TelemetryConfig>,
+    /// Named sets of analyzer overrides, e.g. `[profiles.fast.analyzers]`;
+    /// see [`GuardrailConfig::resolve_profile`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// One `[profiles.<name>]` table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// Applied over the top-level `[analyzers]`; see
+    /// [`AnalyzerToggles::overlay`].
+    #[serde(default)]
+    pub analyzers: AnalyzerToggles,
+}
+
+/// A config with at most one profile applied, ready to run.
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    /// The profile that was applied, if any.
+    pub profile: Option<String>,
+    /// The merged config; its `profiles` table is emptied.
+    pub config: GuardrailConfig,
 }
 
 impl GuardrailConfig {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let data = fs::read_to_string(path)?;
-        let cfg: GuardrailConfig = toml::from_str(&data)?;
+        let data = read_file("config", path)?;
+        let cfg: GuardrailConfig =
+            toml::from_str(&data).map_err(|source| GuardrailError::ConfigParse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        cfg.validate().map_err(|err| match err {
+            GuardrailError::InvalidConfig(message) => {
+                GuardrailError::InvalidConfig(format!("{}: {message}", path.display()))
+            }
+            other => other,
+        })?;
         Ok(cfg)
     }
 
+    /// Rejects `[analyzers.retry]` (the cargo analyzers never retry) and
+    /// analyzer names that don't match a known analyzer (listing every
+    /// offender and the valid names), then pipeline cycles.
+    pub fn validate(&self) -> Result<()> {
+        if self.analyzers.unknown.contains_key("retry") {
+            return Err(GuardrailError::InvalidConfig(
+                "analyzers.retry: `fmt` and `clippy` are not retried; retries are reserved for \
+                 custom analyzers"
+                    .to_string(),
+            ));
+        }
+        let known = crate::analyzers::ANALYZER_KEYS;
+        let mut unknown: Vec<String> = self
+            .analyzers
+            .unknown
+            .keys()
+            .map(|key| format!("`{key}` (analyzers.{key})"))
+            .collect();
+        for stage in &self.analyzers.pipeline {
+            for key in std::iter::once(&stage.name).chain(&stage.then) {
+                let entry = format!("`{key}` (analyzers.pipeline)");
+                if !known.contains(&key.as_str()) && !unknown.contains(&entry) {
+                    unknown.push(entry);
+                }
+            }
+        }
+        for key in &self.analyzers.combined_output {
+            let entry = format!("`{key}` (analyzers.combined_output)");
+            if !known.contains(&key.as_str()) && !unknown.contains(&entry) {
+                unknown.push(entry);
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(GuardrailError::InvalidConfig(format!(
+                "config references unknown analyzers: {}; valid names: {}",
+                unknown.join(", "),
+                known.join(", ")
+            )));
+        }
+        let rules: Vec<&str> = crate::analyzers::DETERMINISM_RULES
+            .iter()
+            .map(|rule| rule.name)
+            .collect();
+        let unknown_rules: Vec<String> = self
+            .analyzers
+            .deterministic_disabled_rules()
+            .iter()
+            .filter(|rule| !rules.contains(&rule.as_str()))
+            .map(|rule| format!("`{rule}`"))
+            .collect();
+        if !unknown_rules.is_empty() {
+            return Err(GuardrailError::InvalidConfig(format!(
+                "analyzers.deterministic.disabled_rules has unknown rules: {}; valid names: {}",
+                unknown_rules.join(", "),
+                rules.join(", ")
+            )));
+        }
+        if let Some(ClippyToggle::Options(options)) = &self.analyzers.clippy {
+            let lists = [
+                ("deny", options.deny.as_deref().unwrap_or_default()),
+                ("allow", options.allow.as_slice()),
+                ("extra_args", options.extra_args.as_slice()),
+            ];
+            for (list, args) in lists {
+                let unsafe_args: Vec<String> = args
+                    .iter()
+                    .filter(|arg| {
+                        arg.is_empty()
+                            || arg.contains(SHELL_METACHARACTERS)
+                            || arg.contains(char::is_whitespace)
+                    })
+                    .map(|arg| format!("{arg:?}"))
+                    .collect();
+                if !unsafe_args.is_empty() {
+                    return Err(GuardrailError::InvalidConfig(format!(
+                        "analyzers.clippy.{list} has empty arguments or ones with whitespace or shell metacharacters: {}; list each argument separately, unquoted",
+                        unsafe_args.join(", ")
+                    )));
+                }
+            }
+        }
+        self.analyzers.pipeline_waves()?;
+        for name in self.profiles.keys() {
+            self.resolve_profile(Some(name))?
+                .config
+                .validate()
+                .map_err(|err| match err {
+                    GuardrailError::InvalidConfig(message) => {
+                        GuardrailError::InvalidConfig(format!("profiles.{name}: {message}"))
+                    }
+                    other => other,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Applies the named profile's analyzer overrides over the top-level
+    /// ones. `None` keeps the top-level config as is; a name with no
+    /// `[profiles.<name>]` table is an error listing the defined profiles.
+    pub fn resolve_profile(&self, name: Option<&str>) -> Result<ResolvedConfig> {
+        let mut config = self.clone();
+        config.profiles.clear();
+        let Some(name) = name else {
+            return Ok(ResolvedConfig {
+                profile: None,
+                config,
+            });
+        };
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            let defined: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            GuardrailError::InvalidConfig(format!(
+                "unknown profile `{name}`; defined profiles: {}",
+                if defined.is_empty() {
+                    "none".to_string()
+                } else {
+                    defined.join(", ")
+                }
+            ))
+        })?;
+        config.analyzers = self.analyzers.overlay(&profile.analyzers);
+        Ok(ResolvedConfig {
+            profile: Some(name.to_string()),
+            config,
+        })
+    }
+
+    /// Walks up from `start` to the nearest directory containing
+    /// [`CONFIG_FILE_NAME`], the way cargo finds `Cargo.toml`.
+    pub fn discover(start: &Path) -> Result<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| GuardrailError::ConfigNotFound {
+                start: start.to_path_buf(),
+            })
+    }
+
+    /// The config with analyzer defaults filled in, as JSON, for embedding in
+    /// reports. Deserializes back into an equivalent `GuardrailConfig`.
+    pub fn snapshot(&self) -> Result<serde_json::Value> {
+        let mut effective = self.clone();
+        let toggles = &mut effective.analyzers;
+        toggles.fmt = Some(self.analyzers.fmt_enabled());
+        toggles.clippy = Some(match &self.analyzers.clippy {
+            Some(ClippyToggle::Options(options)) => ClippyToggle::Options(ClippyOptions {
+                enabled: Some(self.analyzers.clippy_enabled()),
+                deny: Some(self.analyzers.clippy_deny()),
+                ..options.clone()
+            }),
+            _ => ClippyToggle::Enabled(self.analyzers.clippy_enabled()),
+        });
+        let enabled = self.analyzers.deterministic_enabled();
+        let exclude_tests = self.analyzers.deterministic_exclude_tests();
+        let disabled_rules = self.analyzers.deterministic_disabled_rules().to_vec();
+        toggles.deterministic = Some(if exclude_tests || !disabled_rules.is_empty() {
+            DeterministicToggle::Options(DeterministicOptions {
+                enabled: Some(enabled),
+                exclude_tests,
+                disabled_rules,
+            })
+        } else {
+            DeterministicToggle::Enabled(enabled)
+        });
+        toggles.bevy = Some(self.analyzers.bevy_enabled());
+        toggles.complexity = Some(self.analyzers.complexity_enabled());
+        toggles.snapshots = Some(SnapshotToggle::Options(SnapshotOptions {
+            enabled: Some(self.analyzers.snapshots_enabled()),
+            extension: Some(self.analyzers.snapshot_extension().to_string()),
+            dirs: self.analyzers.snapshot_dirs().to_vec(),
+        }));
+        toggles.max_function_lines = Some(self.analyzers.max_function_lines());
+        Ok(serde_json::to_value(effective)?)
+    }
+
+    /// Identifies what a run checked: the crate version, each analyzer's
+    /// effective toggle, the pipeline, thresholds, scope, seed, and generated
+    /// globs.
+    /// Inputs (prompt/response/diff paths) and output settings are left out,
+    /// so reports with equal fingerprints are comparable. FNV-1a over a
+    /// canonical JSON encoding keeps it stable across Rust releases.
+    pub fn fingerprint(&self) -> String {
+        let toggles: Vec<(&str, bool)> = crate::analyzers::ANALYZER_KEYS
+            .iter()
+            .map(|&key| (key, self.analyzers.enabled(key)))
+            .collect();
+        let pipeline: Vec<(&str, &[String])> = self
+            .analyzers
+            .pipeline
+            .iter()
+            .map(|stage| (stage.name.as_str(), stage.then.as_slice()))
+            .collect();
+        let canonical = serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "analyzers": toggles,
+            "pipeline": pipeline,
+            "max_function_lines": self.analyzers.max_function_lines(),
+            "exclude_tests": self.analyzers.deterministic_exclude_tests(),
+            "disabled_rules": self.analyzers.deterministic_disabled_rules(),
+            "clippy_args": self.analyzers.clippy_lint_args(),
+            "snapshot_extension": self.analyzers.snapshot_extension(),
+            "snapshot_dirs": self.analyzers.snapshot_dirs(),
+            "scope": self.scope,
+            "seed": self.determinism.seed,
+            "generated_globs": self.sources.generated_globs,
+        })
+        .to_string();
+        crate::report::fnv1a_hex(&canonical)
+    }
+
+    /// Every built-in analyzer, in report order, with whether this config
+    /// runs it and the settings it would run with. Nothing is executed; see
+    /// [`crate::plan_validations`] for waves and resolved commands.
+    pub fn analyzer_plan(&self) -> Vec<AnalyzerPlanEntry> {
+        let toggles = &self.analyzers;
+        crate::analyzers::ANALYZER_KEYS
+            .iter()
+            .map(|&key| {
+                let (category, settings) = match key {
+                    "fmt" => (
+                        AnalyzerCategory::Command,
+                        serde_json::json!({
+                            "combined_output": toggles.combined_output(key),
+                        }),
+                    ),
+                    "clippy" => (
+                        AnalyzerCategory::Command,
+                        serde_json::json!({
+                            "combined_output": toggles.combined_output(key),
+                            "lint_args": toggles.clippy_lint_args(),
+                        }),
+                    ),
+                    "deterministic" => (
+                        AnalyzerCategory::SourceScan,
+                        serde_json::json!({
+                            "exclude_tests": toggles.deterministic_exclude_tests(),
+                            "disabled_rules": toggles.deterministic_disabled_rules(),
+                        }),
+                    ),
+                    "complexity" => (
+                        AnalyzerCategory::DiffScan,
+                        serde_json::json!({
+                            "max_function_lines": toggles.max_function_lines(),
+                        }),
+                    ),
+                    "snapshots" => (
+                        AnalyzerCategory::SourceScan,
+                        serde_json::json!({
+                            "extension": toggles.snapshot_extension(),
+                            "dirs": toggles.snapshot_dirs(),
+                        }),
+                    ),
+                    _ => (AnalyzerCategory::SourceScan, serde_json::json!({})),
+                };
+                AnalyzerPlanEntry {
+                    key,
+                    check: crate::analyzers::check_name(key),
+                    enabled: toggles.enabled(key),
+                    category,
+                    settings,
+                }
+            })
+            .collect()
+    }
+
     pub fn source_info(&self) -> crate::report::SourceInfo {
         crate::report::SourceInfo {
             prompt_path: self.sources.prompt.clone(),
             response_path: self.sources.response.clone(),
-            diff_path: self.sources.diff.clone(),
+            diff_path: self.sources.diff.report_path(),
             spec_refs: self.sources.spec_refs.clone().unwrap_or_default(),
         }
     }
 
     pub fn validate_sources(&self) -> Result<()> {
-        self.sources.ensure_exists()
+        self.sources.ensure_exists(false)
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SourceConfig {
+    /// Only recorded in the report; may be omitted.
+    #[serde(default)]
     pub prompt: PathBuf,
+    /// Only recorded in the report; may be omitted.
+    #[serde(default)]
     pub response: PathBuf,
-    pub diff: PathBuf,
+    /// Captured diff, or a list of patches validated as one transaction (see
+    /// [`DiffSource::Series`]); may be omitted when `diff_from_git` is set or
+    /// the diff is passed inline.
+    #[serde(default)]
+    pub diff: DiffSource,
+    /// Generate the diff with `git diff <rev>` in the workspace root instead
+    /// of reading `diff`. The output is saved as `git.diff` next to `prompt`
+    /// and reported as the diff source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_from_git: Option<String>,
     #[serde(default)]
     pub spec_refs: Option<Vec<String>>,
+    /// Globs for generated files (`*.lock`, `target/**`); a diff touching
+    /// only these skips the analyzers.
+    #[serde(default)]
+    pub generated_globs: Vec<String>,
+}
+
+/// `sources.diff`: one path, or a list of patches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DiffSource {
+    Single(PathBuf),
+    /// Patches an agent produced one after another, each against the tree the
+    /// previous ones left. They are applied in order to a throwaway checkout
+    /// of `HEAD` (see [`crate::worktree::PatchedWorktree`]) and the analyzers
+    /// run against the result, so only the cumulative change is judged.
+    Series(Vec<PathBuf>),
+}
+
+impl Default for DiffSource {
+    fn default() -> Self {
+        DiffSource::Single(PathBuf::new())
+    }
+}
+
+impl DiffSource {
+    /// Configured paths, in application order; empty when unset.
+    pub fn paths(&self) -> &[PathBuf] {
+        match self {
+            DiffSource::Single(path) if path.as_os_str().is_empty() => &[],
+            DiffSource::Single(path) => std::slice::from_ref(path),
+            DiffSource::Series(paths) => paths,
+        }
+    }
+
+    /// The path a report names as its diff: the single diff, or the last
+    /// patch of a series.
+    pub fn report_path(&self) -> PathBuf {
+        match self {
+            DiffSource::Single(path) => path.clone(),
+            DiffSource::Series(paths) => paths.last().cloned().unwrap_or_default(),
+        }
+    }
 }
 
 impl SourceConfig {
-    fn ensure_exists(&self) -> Result<()> {
-        for (label, path) in [
-            ("prompt", &self.prompt),
-            ("response", &self.response),
-            ("diff", &self.diff),
-        ] {
+    /// Every configured path must exist, and the diff must come from
+    /// somewhere: `diff`, `diff_from_git`, or inline content.
+    pub(crate) fn ensure_exists(&self, inline_diff: bool) -> Result<()> {
+        let from_file = !inline_diff && self.diff_from_git.is_none();
+        if from_file && self.diff.paths().is_empty() {
+            return Err(GuardrailError::InvalidConfig(
+                "Source diff not configured; set sources.diff or sources.diff_from_git, or pass the diff inline".into(),
+            ));
+        }
+        let diffs = self
+            .diff
+            .paths()
+            .iter()
+            .filter(|_| from_file)
+            .map(|path| ("diff", path));
+        for (label, path) in [("prompt", &self.prompt), ("response", &self.response)]
+            .into_iter()
+            .filter(|(_, path)| !path.as_os_str().is_empty())
+            .chain(diffs)
+        {
             if !path.exists() {
-                anyhow::bail!("Source {label} missing at {}", path.display());
+                return Err(GuardrailError::SourceMissing {
+                    label,
+                    path: path.clone(),
+                });
             }
         }
         Ok(())
     }
+
+    /// Joins relative `prompt`, `response`, and `diff` paths onto `root`.
+    pub fn rebase(&mut self, root: &Path) {
+        let diffs = match &mut self.diff {
+            DiffSource::Single(path) => std::slice::from_mut(path),
+            DiffSource::Series(paths) => paths.as_mut_slice(),
+        };
+        for path in [&mut self.prompt, &mut self.response]
+            .into_iter()
+            .chain(diffs)
+        {
+            if path.is_relative() && !path.as_os_str().is_empty() {
+                *path = root.join(&*path);
+            }
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct AnalyzerToggles {
     #[serde(default)]
     pub fmt: Option<bool>,
     #[serde(default)]
-    pub clippy: Option<bool>,
+    pub clippy: Option<ClippyToggle>,
     #[serde(default)]
-    pub deterministic: Option<bool>,
+    pub deterministic: Option<DeterministicToggle>,
     #[serde(default)]
     pub bevy: Option<bool>,
+    #[serde(default)]
+    pub complexity: Option<bool>,
+    #[serde(default)]
+    pub snapshots: Option<SnapshotToggle>,
+    /// Longest added function the complexity scan accepts, in lines.
+    #[serde(default)]
+    pub max_function_lines: Option<usize>,
+    /// Command analyzers (`fmt`, `clippy`) whose stdout and stderr are
+    /// captured as one stream in the order they were written, instead of
+    /// stdout followed by stderr.
+    #[serde(default)]
+    pub combined_output: Vec<String>,
+    /// Ordering constraints: each stage's `then` analyzers run only after
+    /// `name` finishes, and are skipped if it fails.
+    #[serde(default)]
+    pub pipeline: Vec<PipelineStage>,
+    /// Keys under `[analyzers]` that aren't a known toggle, e.g. typos.
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, toml::Value>,
+}
+
+/// `clippy = false`, or an `[analyzers.clippy]` table to choose lint levels.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ClippyToggle {
+    Enabled(bool),
+    Options(ClippyOptions),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ClippyOptions {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Lints or lint groups passed as `-D <lint>`; [`DEFAULT_CLIPPY_DENY`]
+    /// when unset, nothing when empty.
+    #[serde(default)]
+    pub deny: Option<Vec<String>>,
+    /// Lints passed as `-A <lint>`, after the denies so they win.
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Further arguments for clippy itself, after the lint levels, e.g.
+    /// `["-W", "clippy::pedantic"]`.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// `deterministic = false`, or an `[analyzers.deterministic]` table when the
+/// scan needs options.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum DeterministicToggle {
+    Enabled(bool),
+    Options(DeterministicOptions),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeterministicOptions {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Skip files under `tests/` or `benches/` and `#[cfg(test)]` items, for
+    /// property tests that don't need reproducibility.
+    #[serde(default)]
+    pub exclude_tests: bool,
+    /// Rules from [`DETERMINISM_RULES`](crate::analyzers::DETERMINISM_RULES)
+    /// to leave out, e.g. `instant_now` where wall-clock timing is the point.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_rules: Vec<String>,
+}
+
+/// `snapshots = false`, or an `[analyzers.snapshots]` table to change which
+/// files count as pending snapshots and where to look for them.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum SnapshotToggle {
+    Enabled(bool),
+    Options(SnapshotOptions),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotOptions {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// File suffix of a pending snapshot, with or without the leading dot.
+    #[serde(default)]
+    pub extension: Option<String>,
+    /// Directories to search, relative to the workspace root. Empty means the
+    /// validation scope.
+    #[serde(default)]
+    pub dirs: Vec<PathBuf>,
+}
+
+/// One analyzer as [`GuardrailConfig::analyzer_plan`] resolves it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalyzerPlanEntry {
+    /// Name under `[analyzers]`.
+    pub key: &'static str,
+    /// Name of the check it reports.
+    pub check: &'static str,
+    pub enabled: bool,
+    pub category: AnalyzerCategory,
+    /// The analyzer's options with defaults filled in; empty for analyzers
+    /// without any.
+    pub settings: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyzerCategory {
+    /// Runs a cargo command in the workspace.
+    Command,
+    /// Reads source files under the validation scope.
+    SourceScan,
+    /// Reads only the diff.
+    DiffScan,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PipelineStage {
+    pub name: String,
+    #[serde(default)]
+    pub then: Vec<String>,
 }
 
 impl AnalyzerToggles {
+    /// `overrides` applied over `self`: every toggle or setting it sets
+    /// replaces ours, and its `combined_output` and `pipeline` replace ours
+    /// unless empty.
+    pub fn overlay(&self, overrides: &AnalyzerToggles) -> AnalyzerToggles {
+        let mut unknown = self.unknown.clone();
+        unknown.extend(overrides.unknown.clone());
+        AnalyzerToggles {
+            fmt: overrides.fmt.or(self.fmt),
+            clippy: overrides.clippy.clone().or_else(|| self.clippy.clone()),
+            deterministic: overrides
+                .deterministic
+                .clone()
+                .or_else(|| self.deterministic.clone()),
+            bevy: overrides.bevy.or(self.bevy),
+            complexity: overrides.complexity.or(self.complexity),
+            snapshots: overrides
+                .snapshots
+                .clone()
+                .or_else(|| self.snapshots.clone()),
+            max_function_lines: overrides.max_function_lines.or(self.max_function_lines),
+            combined_output: if overrides.combined_output.is_empty() {
+                self.combined_output.clone()
+            } else {
+                overrides.combined_output.clone()
+            },
+            pipeline: if overrides.pipeline.is_empty() {
+                self.pipeline.clone()
+            } else {
+                overrides.pipeline.clone()
+            },
+            unknown,
+        }
+    }
+
     pub fn fmt_enabled(&self) -> bool {
         self.fmt.unwrap_or(true)
     }
     pub fn clippy_enabled(&self) -> bool {
-        self.clippy.unwrap_or(true)
+        match &self.clippy {
+            Some(ClippyToggle::Enabled(enabled)) => *enabled,
+            Some(ClippyToggle::Options(options)) => options.enabled.unwrap_or(true),
+            None => true,
+        }
+    }
+    /// Lints clippy denies; [`DEFAULT_CLIPPY_DENY`] unless configured.
+    pub fn clippy_deny(&self) -> Vec<String> {
+        match &self.clippy {
+            Some(ClippyToggle::Options(ClippyOptions {
+                deny: Some(deny), ..
+            })) => deny.clone(),
+            _ => DEFAULT_CLIPPY_DENY
+                .iter()
+                .map(|lint| lint.to_string())
+                .collect(),
+        }
+    }
+    /// Arguments after `--` in the clippy command: `-D` for each denied lint,
+    /// `-A` for each allowed one, then `extra_args`.
+    pub fn clippy_lint_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for lint in self.clippy_deny() {
+            args.extend(["-D".to_string(), lint]);
+        }
+        if let Some(ClippyToggle::Options(options)) = &self.clippy {
+            for lint in &options.allow {
+                args.extend(["-A".to_string(), lint.clone()]);
+            }
+            args.extend(options.extra_args.iter().cloned());
+        }
+        args
     }
     pub fn deterministic_enabled(&self) -> bool {
-        self.deterministic.unwrap_or(true)
+        match &self.deterministic {
+            Some(DeterministicToggle::Enabled(enabled)) => *enabled,
+            Some(DeterministicToggle::Options(options)) => options.enabled.unwrap_or(true),
+            None => true,
+        }
+    }
+    /// Whether the deterministic scan ignores test code; off by default.
+    pub fn deterministic_exclude_tests(&self) -> bool {
+        matches!(
+            &self.deterministic,
+            Some(DeterministicToggle::Options(options)) if options.exclude_tests
+        )
+    }
+    /// Determinism rules the scan skips; none by default.
+    pub fn deterministic_disabled_rules(&self) -> &[String] {
+        match &self.deterministic {
+            Some(DeterministicToggle::Options(options)) => &options.disabled_rules,
+            _ => &[],
+        }
     }
     pub fn bevy_enabled(&self) -> bool {
         self.bevy.unwrap_or(true)
     }
+    pub fn complexity_enabled(&self) -> bool {
+        self.complexity.unwrap_or(true)
+    }
+    pub fn snapshots_enabled(&self) -> bool {
+        match &self.snapshots {
+            Some(SnapshotToggle::Enabled(enabled)) => *enabled,
+            Some(SnapshotToggle::Options(options)) => options.enabled.unwrap_or(true),
+            None => true,
+        }
+    }
+    /// Suffix the snapshot scan flags, without the leading dot.
+    pub fn snapshot_extension(&self) -> &str {
+        match &self.snapshots {
+            Some(SnapshotToggle::Options(SnapshotOptions {
+                extension: Some(extension),
+                ..
+            })) => extension.trim_start_matches('.'),
+            _ => DEFAULT_SNAPSHOT_EXTENSION,
+        }
+    }
+    pub fn snapshot_dirs(&self) -> &[PathBuf] {
+        match &self.snapshots {
+            Some(SnapshotToggle::Options(options)) => &options.dirs,
+            _ => &[],
+        }
+    }
+    pub fn max_function_lines(&self) -> usize {
+        self.max_function_lines
+            .unwrap_or(DEFAULT_MAX_FUNCTION_LINES)
+    }
+
+    /// Whether `key`'s command output is captured as one interleaved stream.
+    pub fn combined_output(&self, key: &str) -> bool {
+        self.combined_output.iter().any(|name| name == key)
+    }
+
+    /// Toggle lookup by analyzer key (see [`crate::analyzers::ANALYZER_KEYS`]).
+    pub fn enabled(&self, key: &str) -> bool {
+        match key {
+            "fmt" => self.fmt_enabled(),
+            "clippy" => self.clippy_enabled(),
+            "deterministic" => self.deterministic_enabled(),
+            "bevy" => self.bevy_enabled(),
+            "complexity" => self.complexity_enabled(),
+            "snapshots" => self.snapshots_enabled(),
+            _ => false,
+        }
+    }
+
+    /// Analyzers that must finish before `key` runs.
+    pub fn prerequisites(&self, key: &str) -> Vec<&str> {
+        self.pipeline
+            .iter()
+            .filter(|stage| stage.then.iter().any(|next| next == key))
+            .map(|stage| stage.name.as_str())
+            .collect()
+    }
+
+    /// Groups every analyzer into waves that can run in parallel: each wave
+    /// only depends on earlier ones. Within a wave, analyzers keep report
+    /// order. Fails on unknown analyzer names or dependency cycles.
+    pub fn pipeline_waves(&self) -> Result<Vec<Vec<&'static str>>> {
+        let known = crate::analyzers::ANALYZER_KEYS;
+        for stage in &self.pipeline {
+            for key in std::iter::once(&stage.name).chain(&stage.then) {
+                if !known.contains(&key.as_str()) {
+                    return Err(GuardrailError::InvalidConfig(format!(
+                        "analyzers.pipeline references unknown analyzer `{key}` (known: {})",
+                        known.join(", ")
+                    )));
+                }
+            }
+        }
+
+        let mut pending: HashMap<&str, HashSet<&str>> = known
+            .iter()
+            .map(|&key| (key, self.prerequisites(key).into_iter().collect()))
+            .collect();
+        let mut waves = Vec::new();
+        while !pending.is_empty() {
+            let wave: Vec<&'static str> = known
+                .iter()
+                .copied()
+                .filter(|key| pending.get(key).is_some_and(HashSet::is_empty))
+                .collect();
+            if wave.is_empty() {
+                let mut cycle: Vec<&str> = pending.keys().copied().collect();
+                cycle.sort_unstable();
+                return Err(GuardrailError::InvalidConfig(format!(
+                    "analyzers.pipeline has a dependency cycle among: {}",
+                    cycle.join(", ")
+                )));
+            }
+            for key in &wave {
+                pending.remove(key);
+            }
+            for prerequisites in pending.values_mut() {
+                for key in &wave {
+                    prerequisites.remove(key);
+                }
+            }
+            waves.push(wave);
+        }
+        Ok(waves)
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReportConfig {
     pub path: PathBuf,
     #[serde(default)]
     pub include_logs: bool,
+    /// Rewrite paths under the workspace root as relative paths.
+    #[serde(default)]
+    pub redact_paths: bool,
+    /// Rewrite remaining paths under the home directory as `~/...`.
+    #[serde(default)]
+    pub redact_home: bool,
+    /// Embed the effective config in the report as `config_snapshot`.
+    #[serde(default)]
+    pub embed_config: bool,
+    /// Write the report file as single-line JSON instead of pretty-printed.
+    #[serde(default)]
+    pub compact: bool,
+}
+
+/// Restricts analyzers to part of the workspace. Empty means everything.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct ScopeConfig {
+    /// Cargo package names, passed to cargo analyzers as `-p <pkg>`.
+    #[serde(default)]
+    pub packages: Vec<String>,
+    /// Workspace-relative directories the source scans walk.
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+/// Pins the randomness analyzers' child processes see.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct DeterminismConfig {
+    /// Exported as `SIMULATION_SEED` to the `fmt` and `clippy` commands,
+    /// replacing any value guardrail itself was started with. Guardrail runs
+    /// no tests, so this reaches build scripts and proc macros clippy
+    /// compiles, not test binaries. Unset, children inherit the environment
+    /// and the game falls back to its own default seed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct TargetConfig {
     #[serde(default)]
     pub platforms: Option<Vec<String>>,
@@ -104,10 +864,333 @@ pub struct TargetConfig {
     pub checklist_refs: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct TelemetryConfig {
     #[serde(default)]
     pub enable_trace: Option<bool>,
     #[serde(default)]
     pub trace_filter: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn toggles(pipeline: &str) -> AnalyzerToggles {
+        toml::from_str(pipeline).expect("toggles parse")
+    }
+
+    #[test]
+    fn discover_walks_up_to_nearest_config() {
+        let root = std::env::temp_dir().join(format!("guardrail-discover-{}", std::process::id()));
+        let nested = root.join("crates").join("game").join("src");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert!(matches!(
+            GuardrailConfig::discover(&nested),
+            Err(GuardrailError::ConfigNotFound { start }) if start == nested
+        ));
+
+        fs::write(root.join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            GuardrailConfig::discover(&nested).unwrap(),
+            root.join(CONFIG_FILE_NAME)
+        );
+
+        let closer = root.join("crates").join(CONFIG_FILE_NAME);
+        fs::write(&closer, "").unwrap();
+        assert_eq!(GuardrailConfig::discover(&nested).unwrap(), closer);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn load_failures_are_categorized() {
+        let root = std::env::temp_dir().join(format!("guardrail-errors-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join(CONFIG_FILE_NAME);
+
+        let err = GuardrailConfig::from_path(&path).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                GuardrailError::Read {
+                    label: "config",
+                    ..
+                }
+            ),
+            "{err}"
+        );
+        assert!(
+            err.to_string().starts_with("failed to read config "),
+            "{err}"
+        );
+
+        fs::write(&path, "[sources\n").unwrap();
+        let err = GuardrailConfig::from_path(&path).unwrap_err();
+        assert!(matches!(err, GuardrailError::ConfigParse { .. }), "{err}");
+        assert!(
+            err.to_string().contains(&path.display().to_string()),
+            "{err}"
+        );
+
+        fs::write(
+            &path,
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[analyzers]\nclipy = false\n",
+        )
+        .unwrap();
+        let err = GuardrailConfig::from_path(&path).unwrap_err();
+        assert!(matches!(err, GuardrailError::InvalidConfig(_)), "{err}");
+        assert!(err.to_string().contains("`clipy`"), "{err}");
+
+        fs::write(
+            &path,
+            "[sources]\nprompt = \"missing.md\"\nresponse = \"r\"\ndiff = \"d\"\n",
+        )
+        .unwrap();
+        let err = GuardrailConfig::from_path(&path)
+            .unwrap()
+            .validate_sources()
+            .unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                GuardrailError::SourceMissing {
+                    label: "prompt",
+                    ..
+                }
+            ),
+            "{err}"
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn diff_may_be_a_list_of_patches() {
+        let mut config: GuardrailConfig =
+            toml::from_str("[sources]\ndiff = [\"1.diff\", \"2.diff\"]\n").unwrap();
+        config.sources.rebase(Path::new("agent"));
+        assert_eq!(
+            config.sources.diff,
+            DiffSource::Series(vec!["agent/1.diff".into(), "agent/2.diff".into()])
+        );
+        assert_eq!(
+            config.source_info().diff_path,
+            PathBuf::from("agent/2.diff")
+        );
+        let err = config.validate_sources().unwrap_err();
+        assert!(
+            matches!(err, GuardrailError::SourceMissing { label: "diff", .. }),
+            "{err}"
+        );
+
+        let empty: GuardrailConfig = toml::from_str("[sources]\ndiff = []\n").unwrap();
+        let err = empty.validate_sources().unwrap_err();
+        assert!(err.to_string().contains("not configured"), "{err}");
+    }
+
+    #[test]
+    fn analyzer_plan_lists_every_analyzer_with_its_state() {
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[analyzers]\nclippy = false\nmax_function_lines = 40\n",
+        )
+        .unwrap();
+        let plan = config.analyzer_plan();
+
+        let keys: Vec<&str> = plan.iter().map(|entry| entry.key).collect();
+        assert_eq!(keys, crate::analyzers::ANALYZER_KEYS);
+        let clippy = &plan[1];
+        assert_eq!((clippy.key, clippy.enabled), ("clippy", false));
+        assert_eq!(clippy.category, AnalyzerCategory::Command);
+        assert!(plan
+            .iter()
+            .filter(|entry| entry.key != "clippy")
+            .all(|entry| entry.enabled));
+
+        let complexity = plan.iter().find(|entry| entry.key == "complexity").unwrap();
+        assert_eq!(complexity.check, "complexity_scan");
+        assert_eq!(
+            complexity.settings,
+            serde_json::json!({ "max_function_lines": 40 })
+        );
+    }
+
+    #[test]
+    fn pipeline_waves_follow_dependencies() {
+        let toggles = toggles(
+            "[[pipeline]]\nname = \"fmt\"\nthen = [\"clippy\"]\n\n[[pipeline]]\nname = \"clippy\"\nthen = [\"bevy\"]\n",
+        );
+        assert_eq!(
+            toggles.pipeline_waves().unwrap(),
+            vec![
+                vec!["fmt", "deterministic", "complexity", "snapshots"],
+                vec!["clippy"],
+                vec!["bevy"]
+            ]
+        );
+    }
+
+    #[test]
+    fn pipeline_cycles_and_unknown_names_are_rejected() {
+        let cyclic = toggles(
+            "[[pipeline]]\nname = \"fmt\"\nthen = [\"clippy\"]\n\n[[pipeline]]\nname = \"clippy\"\nthen = [\"fmt\"]\n",
+        );
+        let err = cyclic.pipeline_waves().unwrap_err().to_string();
+        assert!(err.contains("cycle among: clippy, fmt"), "{err}");
+
+        let unknown = toggles("[[pipeline]]\nname = \"build\"\nthen = [\"clippy\"]\n");
+        let err = unknown.pipeline_waves().unwrap_err().to_string();
+        assert!(err.contains("unknown analyzer `build`"), "{err}");
+    }
+
+    #[test]
+    fn unknown_disabled_determinism_rules_fail_validation() {
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[analyzers.deterministic]\ndisabled_rules = [\"instant_now\", \"hashmap\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.analyzers.deterministic_disabled_rules(),
+            ["instant_now", "hashmap"]
+        );
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "analyzers.deterministic.disabled_rules has unknown rules: `hashmap`; valid names: thread_rng, instant_now, system_time_now, std_hash_map, random_state"
+        );
+    }
+
+    #[test]
+    fn fingerprint_tracks_analyzer_config_but_not_inputs() {
+        let parse =
+            |toml: &str| -> GuardrailConfig { toml::from_str(toml).expect("config parses") };
+        let base = "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n";
+
+        let first = parse(base).fingerprint();
+        assert_eq!(first.len(), 16);
+        assert_eq!(parse(base).fingerprint(), first);
+
+        let other_inputs = parse("[sources]\nprompt = \"a\"\nresponse = \"b\"\ndiff = \"c\"\n");
+        assert_eq!(other_inputs.fingerprint(), first);
+
+        let toggled = parse(&format!("{base}\n[analyzers]\nclippy = false\n"));
+        assert_ne!(toggled.fingerprint(), first);
+        let explicit_default = parse(&format!("{base}\n[analyzers]\nclippy = true\n"));
+        assert_eq!(explicit_default.fingerprint(), first);
+        let lenient = parse(&format!(
+            "{base}\n[analyzers.deterministic]\nexclude_tests = true\n"
+        ));
+        assert!(lenient.analyzers.deterministic_enabled());
+        assert_ne!(lenient.fingerprint(), first);
+        let inline = parse(&format!(
+            "{base}\n[analyzers.snapshots]\nextension = \".pending-snap\"\n"
+        ));
+        assert!(inline.analyzers.snapshots_enabled());
+        assert_eq!(inline.analyzers.snapshot_extension(), "pending-snap");
+        assert_ne!(inline.fingerprint(), first);
+    }
+
+    #[test]
+    fn snapshot_round_trips_into_an_equivalent_config() {
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\ngenerated_globs = [\"*.lock\"]\n\n[analyzers]\nclippy = false\n\n[[analyzers.pipeline]]\nname = \"fmt\"\nthen = [\"bevy\"]\n\n[scope]\npackages = [\"core_game\"]\n\n[report]\npath = \"reports/latest.json\"\nembed_config = true\n",
+        )
+        .expect("config parses");
+
+        let snapshot = config.snapshot().unwrap();
+        assert_eq!(snapshot["analyzers"]["fmt"], serde_json::json!(true));
+        let restored: GuardrailConfig = serde_json::from_value(snapshot).unwrap();
+        restored.validate().unwrap();
+        assert!(!restored.analyzers.clippy_enabled());
+        assert_eq!(restored.analyzers.prerequisites("bevy"), vec!["fmt"]);
+        assert_eq!(
+            restored.analyzers.max_function_lines,
+            Some(DEFAULT_MAX_FUNCTION_LINES)
+        );
+        assert_eq!(restored.sources.generated_globs, vec!["*.lock".to_string()]);
+        assert_eq!(restored.scope.packages, vec!["core_game".to_string()]);
+        assert!(restored.report.is_some_and(|report| report.embed_config));
+    }
+
+    #[test]
+    fn profiles_override_only_what_they_set() {
+        let config: GuardrailConfig = toml::from_str(
+            "[analyzers]\nclippy = false\nmax_function_lines = 40\n\n[analyzers.deterministic]\nexclude_tests = true\n\n[profiles.ci.analyzers]\nclippy = true\ndeterministic = false\n",
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let top = config.resolve_profile(None).unwrap();
+        assert_eq!(top.profile, None);
+        assert!(top.config.profiles.is_empty());
+        assert!(!top.config.analyzers.clippy_enabled());
+
+        let ci = config.resolve_profile(Some("ci")).unwrap();
+        assert_eq!(ci.profile.as_deref(), Some("ci"));
+        let analyzers = &ci.config.analyzers;
+        assert!(analyzers.clippy_enabled());
+        assert!(!analyzers.deterministic_enabled());
+        assert_eq!(analyzers.max_function_lines(), 40);
+        assert_ne!(ci.config.fingerprint(), top.config.fingerprint());
+
+        let typo: GuardrailConfig =
+            toml::from_str("[profiles.ci.analyzers]\nclipy = false\n").unwrap();
+        let err = typo.validate().unwrap_err().to_string();
+        assert!(err.starts_with("profiles.ci: "), "{err}");
+        assert!(err.contains("`clipy`"), "{err}");
+    }
+
+    #[test]
+    fn clippy_arguments_with_shell_metacharacters_fail_validation() {
+        let parse =
+            |toml: &str| -> GuardrailConfig { toml::from_str(toml).expect("config parses") };
+        let config = parse(
+            "[analyzers.clippy]\nenabled = false\nallow = [\"clippy::needless_range_loop\"]\n",
+        );
+        config.validate().unwrap();
+        assert!(!config.analyzers.clippy_enabled());
+
+        let err = parse("[analyzers.clippy]\nallow = [\"dead_code\", \"unused; rm -rf /\"]\n")
+            .validate()
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "analyzers.clippy.allow has empty arguments or ones with whitespace or shell metacharacters: \"unused; rm -rf /\"; list each argument separately, unquoted"
+        );
+        let err = parse("[analyzers.clippy]\nextra_args = [\"-W clippy::pedantic\", \"$(id)\"]\n")
+            .validate()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("\"-W clippy::pedantic\", \"$(id)\""), "{err}");
+    }
+
+    #[test]
+    fn validate_lists_every_unknown_analyzer_name() {
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[analyzers]\nclipy = false\n\n[[analyzers.pipeline]]\nname = \"fmt\"\nthen = [\"lint\", \"bevy\"]\n",
+        )
+        .expect("config parses");
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "config references unknown analyzers: `clipy` (analyzers.clipy), `lint` (analyzers.pipeline); valid names: fmt, clippy, deterministic, bevy, complexity, snapshots"
+        );
+    }
+
+    #[test]
+    fn retries_for_cargo_analyzers_are_rejected() {
+        let config: GuardrailConfig = toml::from_str(
+            "[sources]\nprompt = \"p\"\nresponse = \"r\"\ndiff = \"d\"\n\n[analyzers.retry.clippy]\nretries = 2\n",
+        )
+        .expect("config parses");
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "analyzers.retry: `fmt` and `clippy` are not retried; retries are reserved for custom analyzers"
+        );
+    }
+}
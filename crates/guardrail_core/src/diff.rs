@@ -0,0 +1,384 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use glob::Pattern;
+
+use crate::error::{GuardrailError, Result};
+
+/// `raw` as a plain unified diff: CRLF line endings become LF and, when the
+/// diff arrived wrapped in markdown code fences (```` ```diff ````, as LLM
+/// responses tend to), only the lines inside fences are kept, dropping the
+/// fences and any prose around them. Non-empty output ends with a newline.
+/// Anything else passes through unchanged; check the result with
+/// [`looks_like_unified_diff`].
+pub fn normalize(raw: &str) -> String {
+    let is_fence = |line: &str| line.starts_with("```");
+    let fenced = raw.lines().any(is_fence);
+    let mut normalized = String::with_capacity(raw.len());
+    let mut inside = false;
+    // `lines` also drops the `\r` of each `\r\n`.
+    for line in raw.lines() {
+        if is_fence(line) {
+            inside = !inside;
+        } else if inside || !fenced {
+            normalized.push_str(line);
+            normalized.push('\n');
+        }
+    }
+    normalized
+}
+
+/// True when `diff` has the `---`, `+++`, and `@@` lines of a unified diff,
+/// as `git diff` and `diff -u` print.
+pub fn looks_like_unified_diff(diff: &str) -> bool {
+    let has = |prefix: &str| diff.lines().any(|line| line.starts_with(prefix));
+    has("--- ") && has("+++ ") && has("@@ ")
+}
+
+/// Files touched by a unified diff, in order of appearance. Deleted files are
+/// reported under their old path.
+pub fn changed_files(diff: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut old_path: Option<&str> = None;
+    for line in diff.lines() {
+        let path = if let Some(header) = line.strip_prefix("diff --git ") {
+            old_path = None;
+            header.split_once(" b/").map(|(_, new)| new)
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            old_path = old.strip_prefix("a/");
+            None
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            new.strip_prefix("b/").or(old_path)
+        } else {
+            None
+        };
+        if let Some(path) = path {
+            let path = PathBuf::from(path.trim_end());
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// A function whose signature is an added line in the diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddedFunction {
+    pub path: PathBuf,
+    /// 1-based line of the signature in the new file.
+    pub line: usize,
+    pub name: String,
+    /// Lines from the signature through the closing brace.
+    pub lines: usize,
+}
+
+/// Functions added to `.rs` files, found with a brace-matching heuristic
+/// rather than a parser: braces inside strings, chars, or comments are
+/// counted, and functions whose signature is unchanged (only the body grew)
+/// are not reported. Nested functions count toward their parent.
+pub fn added_functions(diff: &str) -> Vec<AddedFunction> {
+    struct Open {
+        function: AddedFunction,
+        depth: i64,
+        opened: bool,
+    }
+
+    let mut found = Vec::new();
+    let mut path: Option<PathBuf> = None;
+    let mut new_line = 0usize;
+    let mut open: Option<Open> = None;
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") || line.starts_with("--- ") {
+            open = None;
+            continue;
+        }
+        if let Some(new) = line.strip_prefix("+++ ") {
+            open = None;
+            path = new
+                .strip_prefix("b/")
+                .map(|new| PathBuf::from(new.trim_end()))
+                .filter(|new| new.extension().is_some_and(|ext| ext == "rs"));
+            continue;
+        }
+        if let Some(header) = line.strip_prefix("@@ ") {
+            open = None;
+            new_line = header
+                .split_whitespace()
+                .find_map(|range| range.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(1);
+            continue;
+        }
+        let Some(file) = &path else {
+            continue;
+        };
+        let (added, code) = if let Some(code) = line.strip_prefix('+') {
+            (true, code)
+        } else if let Some(code) = line.strip_prefix(' ') {
+            (false, code)
+        } else if line.is_empty() {
+            (false, "")
+        } else {
+            // Removed lines don't exist in the new file.
+            continue;
+        };
+
+        if open.is_none() && added {
+            if let Some(name) = fn_name(code) {
+                open = Some(Open {
+                    function: AddedFunction {
+                        path: file.clone(),
+                        line: new_line,
+                        name,
+                        lines: 0,
+                    },
+                    depth: 0,
+                    opened: false,
+                });
+            }
+        }
+        if let Some(current) = &mut open {
+            current.function.lines += 1;
+            for c in code.chars() {
+                match c {
+                    '{' => {
+                        current.depth += 1;
+                        current.opened = true;
+                    }
+                    '}' => current.depth -= 1,
+                    _ => {}
+                }
+            }
+            let declaration_only = !current.opened && code.trim_end().ends_with(';');
+            if declaration_only {
+                open = None;
+            } else if current.opened && current.depth <= 0 {
+                found.push(open.take().unwrap().function);
+            }
+        }
+        new_line += 1;
+    }
+    found
+}
+
+/// Name of the function declared on `line`, if it starts a signature.
+fn fn_name(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("//") {
+        return None;
+    }
+    let rest = if let Some(rest) = trimmed.strip_prefix("fn ") {
+        rest
+    } else {
+        let (_, rest) = trimmed.split_once(" fn ")?;
+        rest
+    };
+    let name: String = rest
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Output of `git diff <rev>` in `workspace_root`: the working tree against
+/// `rev`. Like `git diff`, it leaves out untracked files; `git add -N` them
+/// first to include them. Fails when git can't be run or `rev` doesn't name a
+/// commit.
+pub fn from_git(workspace_root: &Path, rev: &str) -> Result<String> {
+    let verify = Command::new("git")
+        .current_dir(workspace_root)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{rev}^{{commit}}"))
+        .output()
+        .map_err(|source| GuardrailError::GitSpawn {
+            command: "rev-parse".into(),
+            source,
+        })?;
+    if !verify.status.success() {
+        return Err(GuardrailError::Git(format!(
+            "git revision {rev} not found in {}",
+            workspace_root.display()
+        )));
+    }
+    let output = Command::new("git")
+        .current_dir(workspace_root)
+        .args(["diff", rev])
+        .output()
+        .map_err(|source| GuardrailError::GitSpawn {
+            command: "diff".into(),
+            source,
+        })?;
+    if !output.status.success() {
+        return Err(GuardrailError::Git(format!(
+            "git diff {rev} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|err| GuardrailError::Git(format!("git diff {rev} is not UTF-8: {err}")))
+}
+
+/// True when `files` is non-empty and every file matches one of `globs`.
+pub fn generated_only(files: &[PathBuf], globs: &[String]) -> Result<bool> {
+    let patterns = globs
+        .iter()
+        .map(|glob| {
+            Pattern::new(glob).map_err(|err| {
+                GuardrailError::InvalidConfig(format!("invalid generated glob {glob}: {err}"))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(!files.is_empty()
+        && files
+            .iter()
+            .all(|file| patterns.iter().any(|pattern| pattern.matches_path(file))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    const GLOBS: [&str; 3] = ["*.lock", "target/**", "**/snapshots/*.snap"];
+
+    fn globs() -> Vec<String> {
+        GLOBS.iter().map(|glob| glob.to_string()).collect()
+    }
+
+    #[test]
+    fn lockfile_and_snapshot_diff_is_generated_only() {
+        let diff = "\
+diff --git a/Cargo.lock b/Cargo.lock
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1 +1 @@
+-version = 3
++version = 4
+diff --git a/crates/llm_regression/tests/snapshots/loot.snap b/crates/llm_regression/tests/snapshots/loot.snap
+deleted file mode 100644
+--- a/crates/llm_regression/tests/snapshots/loot.snap
++++ /dev/null
+@@ -1 +0,0 @@
+-old
+";
+        let files = changed_files(diff);
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("Cargo.lock"),
+                PathBuf::from("crates/llm_regression/tests/snapshots/loot.snap"),
+            ]
+        );
+        assert!(generated_only(&files, &globs()).unwrap());
+    }
+
+    #[test]
+    fn added_functions_measure_bodies_in_added_hunks() {
+        let functions = added_functions(include_str!("../tests/fixtures/long_function.diff"));
+        let summary: Vec<(&str, usize, usize)> = functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.line, function.lines))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("short_helper", 3, 3), ("resolve_everything", 7, 125)]
+        );
+        assert!(functions
+            .iter()
+            .all(|function| function.path == Path::new("crates/core_game/src/battle.rs")));
+    }
+
+    #[test]
+    fn git_diff_captures_working_tree_changes_against_rev() {
+        let root = std::env::temp_dir().join(format!("guardrail-git-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .current_dir(&root)
+                .args([
+                    "-c",
+                    "user.name=guardrail",
+                    "-c",
+                    "user.email=guardrail@example.com",
+                ])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?}");
+        };
+        git(&["init", "--quiet"]);
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&["add", "lib.rs"]);
+        git(&["commit", "--quiet", "-m", "initial"]);
+        std::fs::write(root.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(root.join("new.rs"), "fn c() {}\n").unwrap();
+
+        let diff = from_git(&root, "HEAD").unwrap();
+        assert_eq!(changed_files(&diff), vec![PathBuf::from("lib.rs")]);
+        assert!(diff.contains("+fn b() {}"));
+        git(&["add", "-N", "new.rs"]);
+        assert_eq!(
+            changed_files(&from_git(&root, "HEAD").unwrap()),
+            vec![PathBuf::from("lib.rs"), PathBuf::from("new.rs")]
+        );
+        let missing = from_git(&root, "no-such-rev").unwrap_err();
+        assert!(missing.to_string().contains("no-such-rev not found"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    const PLAIN: &str = "\
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1,2 @@
+ fn a() {}
++fn b() {}
+";
+
+    #[test]
+    fn fenced_diff_from_a_response_normalizes_to_the_plain_diff() {
+        let fenced = format!("Here is the change:\n\n```diff\n{PLAIN}```\n\nLet me know!\n");
+        assert!(!looks_like_unified_diff("Looks good to me.\n"));
+        assert_eq!(normalize(&fenced), PLAIN);
+        assert!(looks_like_unified_diff(&normalize(&fenced)));
+        assert_eq!(normalize(PLAIN), PLAIN);
+    }
+
+    #[test]
+    fn crlf_diff_normalizes_to_lf() {
+        let crlf = PLAIN.replace('\n', "\r\n");
+        assert_eq!(normalize(&crlf), PLAIN);
+        let fenced_crlf = format!("```patch\r\n{crlf}```");
+        assert_eq!(normalize(&fenced_crlf), PLAIN);
+        assert_eq!(
+            normalize(PLAIN.trim_end()),
+            PLAIN,
+            "missing final newline restored"
+        );
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn mixed_diff_is_not_generated_only() {
+        let diff = "\
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1 +1 @@
+-a
++b
+--- a/crates/core_game/src/gameplay.rs
++++ b/crates/core_game/src/gameplay.rs
+@@ -1 +1 @@
+-a
++b
+";
+        let files = changed_files(diff);
+        assert_eq!(files.len(), 2);
+        assert!(!generated_only(&files, &globs()).unwrap());
+        assert!(!generated_only(&[], &globs()).unwrap());
+    }
+}
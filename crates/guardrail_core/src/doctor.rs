@@ -0,0 +1,327 @@
+//! Checks that guardrail itself can run here, before a validation fails
+//! halfway with something like "fmt command failed to start".
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::analyzers::ValidationScope;
+use crate::config::{DiffSource, GuardrailConfig};
+use crate::error::{read_file, GuardrailError};
+use crate::report::{CheckResult, CheckStatus};
+
+/// What [`run_doctor`] inspects.
+pub struct DoctorOptions {
+    pub workspace_root: PathBuf,
+    /// Config to load; discovered from `workspace_root` like `validate` does
+    /// when `None`.
+    pub config_path: Option<PathBuf>,
+    /// Program run for the cargo check.
+    pub cargo: String,
+    /// Program run for the git check.
+    pub git: String,
+}
+
+impl DoctorOptions {
+    pub fn new(workspace_root: PathBuf) -> Self {
+        Self {
+            workspace_root,
+            config_path: None,
+            cargo: "cargo".into(),
+            git: "git".into(),
+        }
+    }
+
+    pub fn with_config(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    pub fn with_cargo(mut self, program: impl Into<String>) -> Self {
+        self.cargo = program.into();
+        self
+    }
+
+    pub fn with_git(mut self, program: impl Into<String>) -> Self {
+        self.git = program.into();
+        self
+    }
+}
+
+/// One result per prerequisite, in order: `config`, `cargo`, `git`,
+/// `workspace`, `report_dir`. A `Fail` means `validate` can't work as
+/// configured; `Warn` marks something only some features need, and
+/// `Skipped` a check the config makes moot.
+pub fn run_doctor(options: &DoctorOptions) -> Vec<CheckResult> {
+    let (config_check, config) = check_config(options);
+    let cargo_needed = config.as_ref().map_or(true, |config| {
+        config.analyzers.fmt_enabled() || config.analyzers.clippy_enabled()
+    });
+    let git_needed = config.as_ref().is_some_and(|config| {
+        config.sources.diff_from_git.is_some()
+            || matches!(config.sources.diff, DiffSource::Series(_))
+    });
+    vec![
+        config_check,
+        check_tool(
+            "cargo",
+            &options.cargo,
+            cargo_needed,
+            "the fmt and clippy analyzers",
+            "install Rust with rustup (https://rustup.rs) and make sure `cargo` is on PATH",
+        ),
+        check_tool(
+            "git",
+            &options.git,
+            git_needed,
+            "`diff_from_git`, `--diff-from-git`, and patch series in `sources.diff`",
+            "install git and make sure it is on PATH",
+        ),
+        check_workspace(&options.workspace_root, config.as_ref()),
+        check_report_dir(&options.workspace_root, config.as_ref()),
+    ]
+}
+
+fn result(name: &str, status: CheckStatus, details: String, remediation: &str) -> CheckResult {
+    let remediation =
+        matches!(status, CheckStatus::Fail | CheckStatus::Warn).then(|| remediation.to_string());
+    CheckResult {
+        name: name.into(),
+        status,
+        details,
+        log_path: None,
+        remediation,
+    }
+}
+
+fn check_config(options: &DoctorOptions) -> (CheckResult, Option<GuardrailConfig>) {
+    const REMEDIATION: &str = "create guardrail.toml (see tools/llm_guardrail_cli/guardrail.example.toml) or pass --config";
+    let path = match &options.config_path {
+        Some(path) => Ok(path.clone()),
+        None => GuardrailConfig::discover(&options.workspace_root),
+    };
+    match path.and_then(|path| GuardrailConfig::from_path(&path).map(|config| (path, config))) {
+        Ok((path, config)) => (
+            result(
+                "config",
+                CheckStatus::Pass,
+                format!("loaded {}", path.display()),
+                REMEDIATION,
+            ),
+            Some(config),
+        ),
+        Err(err) => (
+            result("config", CheckStatus::Fail, err.to_string(), REMEDIATION),
+            None,
+        ),
+    }
+}
+
+/// Runs `program --version`. A missing tool fails only when `needed`;
+/// otherwise it warns that `used_by` won't work.
+fn check_tool(
+    name: &str,
+    program: &str,
+    needed: bool,
+    used_by: &str,
+    remediation: &str,
+) -> CheckResult {
+    let missing = if needed {
+        CheckStatus::Fail
+    } else {
+        CheckStatus::Warn
+    };
+    match Command::new(program).arg("--version").output() {
+        Ok(output) if output.status.success() => result(
+            name,
+            CheckStatus::Pass,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            remediation,
+        ),
+        Ok(output) => result(
+            name,
+            missing,
+            format!(
+                "`{program} --version` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            remediation,
+        ),
+        Err(err) if err.kind() == ErrorKind::NotFound => result(
+            name,
+            missing,
+            format!("`{program}` not found on PATH; needed for {used_by}"),
+            remediation,
+        ),
+        Err(err) => result(
+            name,
+            missing,
+            format!("`{program}` failed to start: {err}"),
+            remediation,
+        ),
+    }
+}
+
+/// `Cargo.toml` at the root parses and declares a workspace or package, and
+/// the config's `[scope]` resolves against it.
+fn check_workspace(root: &Path, config: Option<&GuardrailConfig>) -> CheckResult {
+    const REMEDIATION: &str =
+        "run guardrail from the workspace root, next to a Cargo.toml with [workspace] or [package]";
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = read_file("manifest", &manifest_path).and_then(|contents| {
+        toml::from_str::<toml::Value>(&contents).map_err(|source| GuardrailError::ManifestParse {
+            path: manifest_path.clone(),
+            source,
+        })
+    });
+    let manifest = match manifest {
+        Ok(manifest) => manifest,
+        Err(err) => return result("workspace", CheckStatus::Fail, err.to_string(), REMEDIATION),
+    };
+    if manifest.get("workspace").is_none() && manifest.get("package").is_none() {
+        return result(
+            "workspace",
+            CheckStatus::Fail,
+            format!(
+                "{} has neither [workspace] nor [package]",
+                manifest_path.display()
+            ),
+            REMEDIATION,
+        );
+    }
+    if let Some(config) = config {
+        if let Err(err) = ValidationScope::resolve(&config.scope, root) {
+            return result(
+                "workspace",
+                CheckStatus::Fail,
+                err.to_string(),
+                "fix [scope] so its packages and paths exist in the workspace",
+            );
+        }
+    }
+    result(
+        "workspace",
+        CheckStatus::Pass,
+        format!("{} is a cargo manifest", manifest_path.display()),
+        REMEDIATION,
+    )
+}
+
+/// The directory `report.path` goes in can be created and written. Probes
+/// the nearest existing ancestor with a temporary file, so nothing is left
+/// behind.
+fn check_report_dir(root: &Path, config: Option<&GuardrailConfig>) -> CheckResult {
+    const REMEDIATION: &str =
+        "point report.path at a writable location, or fix the directory's permissions";
+    let Some(report) = config.and_then(|config| config.report.as_ref()) else {
+        return result(
+            "report_dir",
+            CheckStatus::Skipped,
+            "no [report] configured; the report only goes to stdout".into(),
+            REMEDIATION,
+        );
+    };
+    let path = root.join(&report.path);
+    let dir = path.parent().unwrap_or(root);
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.exists()) else {
+        return result(
+            "report_dir",
+            CheckStatus::Fail,
+            format!("no part of {} exists", dir.display()),
+            REMEDIATION,
+        );
+    };
+    if !existing.is_dir() {
+        return result(
+            "report_dir",
+            CheckStatus::Fail,
+            format!(
+                "{} can't be created: {} is not a directory",
+                dir.display(),
+                existing.display()
+            ),
+            REMEDIATION,
+        );
+    }
+    match tempfile::tempfile_in(existing) {
+        Ok(_) => result(
+            "report_dir",
+            CheckStatus::Pass,
+            format!("{} is writable", dir.display()),
+            REMEDIATION,
+        ),
+        Err(err) => result(
+            "report_dir",
+            CheckStatus::Fail,
+            format!("can't write to {}: {err}", existing.display()),
+            REMEDIATION,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A workspace with a config reporting to `report_path`.
+    fn workspace(report_path: &str) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("Cargo.toml"),
+            "[workspace]\nmembers = []\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join(crate::config::CONFIG_FILE_NAME),
+            format!("[report]\npath = \"{report_path}\"\n"),
+        )
+        .unwrap();
+        root
+    }
+
+    fn check<'a>(checks: &'a [CheckResult], name: &str) -> &'a CheckResult {
+        checks.iter().find(|check| check.name == name).unwrap()
+    }
+
+    #[test]
+    fn missing_cargo_fails_with_remediation() {
+        let root = workspace("reports/latest.json");
+        let checks = run_doctor(
+            &DoctorOptions::new(root.path().to_path_buf())
+                .with_cargo("guardrail-doctor-no-such-cargo"),
+        );
+        let cargo = check(&checks, "cargo");
+        assert_eq!(cargo.status, CheckStatus::Fail);
+        assert!(cargo.details.contains("not found on PATH"), "{cargo:?}");
+        assert!(cargo.remediation.as_deref().unwrap().contains("rustup"));
+        assert_eq!(check(&checks, "config").status, CheckStatus::Pass);
+        assert_eq!(check(&checks, "workspace").status, CheckStatus::Pass);
+        assert_eq!(check(&checks, "report_dir").status, CheckStatus::Pass);
+        assert!(!root.path().join("reports").exists());
+
+        std::fs::write(
+            root.path().join(crate::config::CONFIG_FILE_NAME),
+            "[analyzers]\nfmt = false\nclippy = false\n",
+        )
+        .unwrap();
+        let checks = run_doctor(
+            &DoctorOptions::new(root.path().to_path_buf())
+                .with_cargo("guardrail-doctor-no-such-cargo"),
+        );
+        assert_eq!(check(&checks, "cargo").status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn unwritable_report_dir_fails() {
+        let root = workspace("reports.txt/run/latest.json");
+        std::fs::write(root.path().join("reports.txt"), "").unwrap();
+        let checks = run_doctor(&DoctorOptions::new(root.path().to_path_buf()));
+        let report_dir = check(&checks, "report_dir");
+        assert_eq!(report_dir.status, CheckStatus::Fail);
+        assert!(
+            report_dir.details.contains("is not a directory"),
+            "{report_dir:?}"
+        );
+        assert!(report_dir.remediation.is_some());
+    }
+}
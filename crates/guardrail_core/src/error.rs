@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::config::CONFIG_FILE_NAME;
+
+/// Everything `guardrail_core` can fail with, by category, so embedders can
+/// tell a missing config from a broken one or from an analyzer that couldn't
+/// start. Each message names the file or analyzer involved and includes the
+/// underlying error.
+#[derive(Debug, Error)]
+pub enum GuardrailError {
+    #[error("no {CONFIG_FILE_NAME} found in {} or any parent directory; pass --config <path>", start.display())]
+    ConfigNotFound { start: PathBuf },
+    #[error("failed to parse config {}: {source}", path.display())]
+    ConfigParse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// The config parsed but is inconsistent: unknown analyzer names,
+    /// pipeline cycles, bad globs, or a scope that doesn't match the
+    /// workspace.
+    #[error("{0}")]
+    InvalidConfig(String),
+    #[error("Source {label} missing at {}", path.display())]
+    SourceMissing { label: &'static str, path: PathBuf },
+    /// `label` says what the file is for: `config`, `diff`, `baseline`, ...
+    #[error("failed to read {label} {}: {source}", path.display())]
+    Read {
+        label: &'static str,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to write {}: {source}", path.display())]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to walk {}: {source}", root.display())]
+    Walk {
+        root: PathBuf,
+        source: walkdir::Error,
+    },
+    #[error("failed to parse {}: {source}", path.display())]
+    ManifestParse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// A report JSON (e.g. a baseline) or a report index.
+    #[error("failed to parse {label} {}: {source}", path.display())]
+    ReportParse {
+        label: &'static str,
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("failed to serialize: {0}")]
+    Serialize(#[from] serde_json::Error),
+    /// `command` is the git subcommand, e.g. `diff` or `worktree add`.
+    #[error("failed to run git {command}; is git installed and on PATH? ({source})")]
+    GitSpawn {
+        command: String,
+        source: std::io::Error,
+    },
+    /// The revision doesn't exist, or `git diff` failed or printed non-UTF-8.
+    #[error("{0}")]
+    Git(String),
+    /// A patch in a `sources.diff` series didn't apply on top of the ones
+    /// before it; `index` counts from 1.
+    #[error("patch {index} of {count} ({}) does not apply: {message}", path.display())]
+    PatchApply {
+        index: usize,
+        count: usize,
+        path: PathBuf,
+        message: String,
+    },
+    #[error("{analyzer} command failed to start: {source}")]
+    AnalyzerSpawn {
+        analyzer: String,
+        source: std::io::Error,
+    },
+    #[error("{analyzer} analyzer panicked")]
+    AnalyzerPanicked { analyzer: String },
+}
+
+pub type Result<T, E = GuardrailError> = std::result::Result<T, E>;
+
+/// `fs::read_to_string` with what the file is and its path attached to the
+/// error.
+pub(crate) fn read_file(label: &'static str, path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|source| GuardrailError::Read {
+        label,
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// [`GuardrailError::Walk`] for a walk of `root`.
+pub(crate) fn walk_error(root: &Path) -> impl Fn(walkdir::Error) -> GuardrailError + '_ {
+    move |source| GuardrailError::Walk {
+        root: root.to_path_buf(),
+        source,
+    }
+}
+
+/// `fs::write` with the path attached to the error.
+pub(crate) fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    std::fs::write(path, contents).map_err(|source| GuardrailError::Write {
+        path: path.to_path_buf(),
+        source,
+    })
+}
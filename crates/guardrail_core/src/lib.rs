@@ -1,10 +1,29 @@
 pub mod analyzers;
 pub mod config;
+pub mod diff;
+pub mod doctor;
+pub mod error;
 pub mod report;
+pub mod trend;
+pub mod worktree;
 
-pub use analyzers::{run_validations, ValidationOptions};
-pub use config::{AnalyzerToggles, GuardrailConfig};
+pub use analyzers::{
+    plan_from_config, plan_validations, run_from_config, run_validations, PlannedAnalyzer,
+    ValidationOptions, ValidationPlan, ValidationScope,
+};
+pub use config::{
+    AnalyzerCategory, AnalyzerPlanEntry, AnalyzerToggles, ClippyOptions, ClippyToggle,
+    DeterminismConfig, DeterministicOptions, DeterministicToggle, DiffSource, GuardrailConfig,
+    ProfileConfig, ResolvedConfig, ScopeConfig,
+};
+pub use doctor::{run_doctor, DoctorOptions};
+pub use error::{GuardrailError, Result};
+pub use report::baseline::subtract_baseline;
+pub use report::index::{update_index, IndexEntry, ReportIndex};
+pub use report::render::{RenderOptions, Verbosity};
 pub use report::{
-    CheckResult, CheckStatus, GuardrailReport, NextAction, ReportStatus, ReportSummary, RiskEntry,
-    SourceInfo,
+    CheckResult, CheckStatus, CheckTallies, GuardrailReport, GuardrailReportBuilder, NextAction,
+    RedactOptions, ReportStatus, ReportSummary, RiskEntry, SourceInfo,
 };
+pub use trend::{aggregate_reports, AnalyzerTrend, ScorePoint, TrendReport};
+pub use worktree::PatchedWorktree;
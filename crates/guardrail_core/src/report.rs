@@ -1,6 +1,15 @@
+pub mod baseline;
+pub mod index;
+pub mod render;
+
+use std::path::{Path, PathBuf};
+
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
+/// Serialized reports are committed as goldens and diffed, so they must be
+/// byte-stable: map-typed fields here and in nested types use `BTreeMap`,
+/// never `HashMap`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuardrailReport {
     pub id: String,
@@ -10,6 +19,13 @@ pub struct GuardrailReport {
     pub risks: Vec<RiskEntry>,
     pub checks: Vec<CheckResult>,
     pub next_actions: Vec<NextAction>,
+    /// [`GuardrailConfig::fingerprint`](crate::GuardrailConfig::fingerprint)
+    /// of the producing config; empty in reports that predate it.
+    #[serde(default)]
+    pub fingerprint: String,
+    /// Effective config that produced the report (`[report] embed_config`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_snapshot: Option<serde_json::Value>,
 }
 
 impl GuardrailReport {
@@ -19,7 +35,8 @@ impl GuardrailReport {
         checks: Vec<CheckResult>,
         notes: impl Into<String>,
     ) -> Self {
-        let (status, score) = summarize_checks(&checks);
+        let (status, score, tallies) = summarize_checks(&checks);
+        let next_actions = remediation_actions(&checks);
         Self {
             id: id.into(),
             timestamp: Utc::now().to_rfc3339(),
@@ -28,22 +45,358 @@ impl GuardrailReport {
                 status,
                 score,
                 notes: notes.into(),
+                checks: tallies,
             },
             risks: Vec::new(),
             checks,
+            next_actions,
+            fingerprint: String::new(),
+            config_snapshot: None,
+        }
+    }
+}
+
+impl GuardrailReport {
+    /// Starts a [`GuardrailReportBuilder`], for assembling arbitrary reports
+    /// in tests and downstream tools.
+    pub fn builder(id: impl Into<String>) -> GuardrailReportBuilder {
+        GuardrailReportBuilder {
+            id: id.into(),
+            timestamp: None,
+            source: SourceInfo {
+                prompt_path: PathBuf::new(),
+                response_path: PathBuf::new(),
+                diff_path: PathBuf::new(),
+                spec_refs: Vec::new(),
+            },
+            checks: Vec::new(),
+            risks: Vec::new(),
             next_actions: Vec::new(),
+            notes: String::new(),
+            fingerprint: String::new(),
+        }
+    }
+}
+
+/// Declarative [`GuardrailReport`] construction. [`build`](Self::build)
+/// derives the summary and remediation actions from the checks the same way
+/// [`GuardrailReport::new`] does; source paths default to empty and the
+/// timestamp to now.
+#[derive(Debug, Clone)]
+pub struct GuardrailReportBuilder {
+    id: String,
+    timestamp: Option<String>,
+    source: SourceInfo,
+    checks: Vec<CheckResult>,
+    risks: Vec<RiskEntry>,
+    next_actions: Vec<NextAction>,
+    notes: String,
+    fingerprint: String,
+}
+
+impl GuardrailReportBuilder {
+    pub fn check(mut self, check: CheckResult) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    pub fn checks(mut self, checks: impl IntoIterator<Item = CheckResult>) -> Self {
+        self.checks.extend(checks);
+        self
+    }
+
+    pub fn risk(mut self, risk: RiskEntry) -> Self {
+        self.risks.push(risk);
+        self
+    }
+
+    /// Listed after the actions derived from failing checks' remediations.
+    pub fn next_action(mut self, action: NextAction) -> Self {
+        self.next_actions.push(action);
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: impl Into<String>) -> Self {
+        self.timestamp = Some(timestamp.into());
+        self
+    }
+
+    pub fn source(mut self, source: SourceInfo) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = notes.into();
+        self
+    }
+
+    pub fn fingerprint(mut self, fingerprint: impl Into<String>) -> Self {
+        self.fingerprint = fingerprint.into();
+        self
+    }
+
+    pub fn build(self) -> GuardrailReport {
+        let mut report = GuardrailReport::new(self.id, self.source, self.checks, self.notes);
+        if let Some(timestamp) = self.timestamp {
+            report.timestamp = timestamp;
+        }
+        report.risks = self.risks;
+        report.next_actions.extend(self.next_actions);
+        report.fingerprint = self.fingerprint;
+        report
+    }
+}
+
+/// Path prefixes [`GuardrailReport::redact`] rewrites; `None` leaves that
+/// form alone.
+#[derive(Debug, Clone, Default)]
+pub struct RedactOptions {
+    /// Paths under this root become relative to it.
+    pub workspace_root: Option<PathBuf>,
+    /// Remaining paths under this directory start with `~` instead.
+    pub home: Option<PathBuf>,
+}
+
+impl RedactOptions {
+    fn path(&self, path: &mut PathBuf) {
+        if let Some(relative) = self
+            .workspace_root
+            .as_ref()
+            .and_then(|root| path.strip_prefix(root).ok())
+        {
+            *path = if relative.as_os_str().is_empty() {
+                PathBuf::from(".")
+            } else {
+                relative.to_path_buf()
+            };
+        } else if let Some(relative) = self
+            .home
+            .as_ref()
+            .and_then(|home| path.strip_prefix(home).ok())
+        {
+            *path = Path::new("~").join(relative);
+        }
+    }
+
+    fn value(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(text) => self.text(text),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|item| self.value(item)),
+            serde_json::Value::Object(map) => map.values_mut().for_each(|item| self.value(item)),
+            _ => {}
+        }
+    }
+
+    fn text(&self, text: &mut String) {
+        if let Some(root) = &self.workspace_root {
+            *text = text.replace(&format!("{}/", root.display()), "");
+        }
+        if let Some(home) = &self.home {
+            *text = text.replace(&format!("{}/", home.display()), "~/");
         }
     }
 }
 
-fn summarize_checks(checks: &[CheckResult]) -> (ReportStatus, f32) {
-    if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+impl GuardrailReport {
+    /// Rewrites absolute paths in sources, log paths, and free text (check
+    /// details, risks, next actions) so the report can be shared without
+    /// leaking local directories.
+    pub fn redact(&mut self, opts: &RedactOptions) {
+        opts.path(&mut self.source.prompt_path);
+        opts.path(&mut self.source.response_path);
+        opts.path(&mut self.source.diff_path);
+        for check in &mut self.checks {
+            if let Some(log_path) = &mut check.log_path {
+                opts.path(log_path);
+            }
+            opts.text(&mut check.details);
+        }
+        for risk in &mut self.risks {
+            opts.text(&mut risk.description);
+            if let Some(action) = &mut risk.recommended_action {
+                opts.text(action);
+            }
+        }
+        for action in &mut self.next_actions {
+            opts.text(&mut action.description);
+        }
+        if let Some(snapshot) = &mut self.config_snapshot {
+            opts.value(snapshot);
+        }
+    }
+
+    /// Hash of what the run found: overall status, each check's name and
+    /// status, risks, and next actions. Timestamps, ids, check details (which
+    /// carry durations and logs), and source paths are left out, and absolute
+    /// paths in risk and action text are cut down to their file name, so two
+    /// runs with the same findings share a digest wherever they ran. Unlike
+    /// [`fingerprint`](Self::fingerprint), which says whether two reports are
+    /// comparable, this says whether the outcome changed.
+    pub fn content_digest(&self) -> String {
+        let mut checks: Vec<(&str, &CheckStatus)> = self
+            .checks
+            .iter()
+            .map(|check| (check.name.as_str(), &check.status))
+            .collect();
+        checks.sort_by_key(|&(name, _)| name);
+        let mut risks: Vec<[String; 4]> = self
+            .risks
+            .iter()
+            .map(|risk| {
+                [
+                    risk.category.clone(),
+                    strip_absolute_paths(&risk.description),
+                    risk.severity.clone(),
+                    risk.recommended_action
+                        .as_deref()
+                        .map(strip_absolute_paths)
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect();
+        risks.sort();
+        let next_actions: Vec<String> = self
+            .next_actions
+            .iter()
+            .map(|action| strip_absolute_paths(&action.description))
+            .collect();
+        let canonical = serde_json::json!({
+            "status": self.summary.status,
+            "checks": checks,
+            "risks": risks,
+            "next_actions": next_actions,
+        })
+        .to_string();
+        fnv1a_hex(&canonical)
+    }
+
+    /// GitHub Actions workflow commands (`::error` / `::warning` / `::notice`)
+    /// for failing or warning checks and for risks. A `path.rs:line` reference
+    /// in the details becomes the annotation's `file`/`line`.
+    pub fn github_annotations(&self) -> Vec<String> {
+        let checks = self.checks.iter().filter_map(|check| {
+            let level = match check.status {
+                CheckStatus::Fail => "error",
+                CheckStatus::Warn => "warning",
+                CheckStatus::Pass | CheckStatus::Skipped => return None,
+            };
+            Some(workflow_command(level, &check.name, &check.details))
+        });
+        let risks = self.risks.iter().map(|risk| {
+            let level = match risk.severity.as_str() {
+                "high" => "error",
+                "medium" => "warning",
+                _ => "notice",
+            };
+            let mut message = risk.description.clone();
+            if let Some(action) = &risk.recommended_action {
+                message.push('\n');
+                message.push_str(action);
+            }
+            workflow_command(level, &risk.category, &message)
+        });
+        checks.chain(risks).collect()
+    }
+}
+
+/// 64-bit FNV-1a as 16 hex digits; unlike `DefaultHasher` it is stable
+/// across Rust releases.
+pub(crate) fn fnv1a_hex(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Replaces each whitespace-separated absolute path (`/…`, `~/…`) with its
+/// last component.
+fn strip_absolute_paths(text: &str) -> String {
+    text.split_whitespace()
+        .map(|token| {
+            if token.starts_with('/') || token.starts_with("~/") {
+                token.rsplit('/').next().unwrap_or(token)
+            } else {
+                token
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn workflow_command(level: &str, title: &str, message: &str) -> String {
+    let mut properties = Vec::new();
+    if let Some((file, line)) = source_location(message) {
+        properties.push(format!("file={}", escape_property(&file)));
+        properties.push(format!("line={line}"));
+    }
+    properties.push(format!("title={}", escape_property(title)));
+    format!(
+        "::{level} {}::{}",
+        properties.join(","),
+        escape_data(message.trim())
+    )
+}
+
+/// First `something.rs:<line>` token, as printed by rustc (`--> src/lib.rs:3:5`)
+/// or written by analyzers (`tests/loot.rs:42`).
+fn source_location(text: &str) -> Option<(String, u32)> {
+    text.split_whitespace().find_map(|token| {
+        let token = token.trim_matches(|c: char| matches!(c, '(' | ')' | ',' | '`' | '\''));
+        let (file, rest) = token.split_once(".rs:")?;
+        let line = rest.split(':').next()?.parse().ok()?;
+        (!file.is_empty()).then(|| (format!("{file}.rs"), line))
+    })
+}
+
+/// Escaping rules from the workflow-command spec, which keep each command on
+/// one line.
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// One next action per failing check that suggests a fix.
+fn remediation_actions(checks: &[CheckResult]) -> Vec<NextAction> {
+    checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Fail)
+        .filter_map(|check| {
+            let remediation = check.remediation.as_ref()?;
+            Some(NextAction {
+                description: format!("{}: {remediation}", check.name),
+                owner: None,
+                linked_checklist: None,
+            })
+        })
+        .collect()
+}
+
+/// Skipped checks are tallied but never move the status or score.
+fn summarize_checks(checks: &[CheckResult]) -> (ReportStatus, f32, CheckTallies) {
+    let mut tallies = CheckTallies::default();
+    for check in checks {
+        match check.status {
+            CheckStatus::Pass => tallies.pass += 1,
+            CheckStatus::Fail => tallies.fail += 1,
+            CheckStatus::Warn => tallies.warn += 1,
+            CheckStatus::Skipped => tallies.skipped += 1,
+        }
+    }
+    let (status, score) = if tallies.fail > 0 {
         (ReportStatus::Fail, 0.0)
-    } else if checks.iter().any(|c| c.status == CheckStatus::Warn) {
+    } else if tallies.warn > 0 {
         (ReportStatus::Warn, 0.7)
     } else {
         (ReportStatus::Pass, 1.0)
-    }
+    };
+    (status, score, tallies)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +413,17 @@ pub struct ReportSummary {
     pub status: ReportStatus,
     pub score: f32,
     pub notes: String,
+    #[serde(default)]
+    pub checks: CheckTallies,
+}
+
+/// Number of checks per [`CheckStatus`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CheckTallies {
+    pub pass: usize,
+    pub fail: usize,
+    pub warn: usize,
+    pub skipped: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -84,6 +448,9 @@ pub struct CheckResult {
     pub status: CheckStatus,
     pub details: String,
     pub log_path: Option<std::path::PathBuf>,
+    /// How to fix a failing check, e.g. the command to run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -101,3 +468,288 @@ pub struct NextAction {
     pub owner: Option<String>,
     pub linked_checklist: Option<String>,
 }
+
+/// Check fixture shared by the report tests; no log or remediation.
+#[cfg(test)]
+pub(crate) fn test_check(name: &str, status: CheckStatus, details: &str) -> CheckResult {
+    CheckResult {
+        name: name.into(),
+        status,
+        details: details.into(),
+        log_path: None,
+        remediation: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_skipped_checks_pass_with_tallies() {
+        let (status, score, tallies) = summarize_checks(&[
+            test_check("fmt", CheckStatus::Skipped, "disabled in config"),
+            test_check("clippy", CheckStatus::Skipped, "disabled in config"),
+        ]);
+        assert_eq!(status, ReportStatus::Pass);
+        assert_eq!(score, 1.0);
+        assert_eq!(
+            tallies,
+            CheckTallies {
+                skipped: 2,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn skipped_checks_do_not_mask_other_statuses() {
+        let (status, score, tallies) = summarize_checks(&[
+            test_check("fmt", CheckStatus::Pass, ""),
+            test_check("clippy", CheckStatus::Warn, ""),
+            test_check(
+                "deterministic_seed_scan",
+                CheckStatus::Skipped,
+                "disabled in config",
+            ),
+        ]);
+        assert_eq!(status, ReportStatus::Warn);
+        assert_eq!(score, 0.7);
+        assert_eq!(
+            tallies,
+            CheckTallies {
+                pass: 1,
+                fail: 0,
+                warn: 1,
+                skipped: 1,
+            }
+        );
+
+        let (status, _, tallies) = summarize_checks(&[
+            test_check("fmt", CheckStatus::Skipped, "disabled in config"),
+            test_check("clippy", CheckStatus::Fail, ""),
+        ]);
+        assert_eq!(status, ReportStatus::Fail);
+        assert_eq!((tallies.fail, tallies.skipped), (1, 1));
+    }
+
+    #[test]
+    fn builder_computes_the_summary_from_its_checks() {
+        let mut failing = test_check("fmt", CheckStatus::Fail, "diff in src/lib.rs");
+        failing.remediation = Some("run `cargo fmt --all`".into());
+        let report = GuardrailReport::builder("built")
+            .timestamp("2025-03-01T00:00:00+00:00")
+            .check(test_check("clippy", CheckStatus::Pass, "clean"))
+            .check(test_check(
+                "complexity_scan",
+                CheckStatus::Warn,
+                "fn spawn is long",
+            ))
+            .risk(RiskEntry {
+                category: "complexity".into(),
+                description: "fn spawn spans 120 lines".into(),
+                severity: "medium".into(),
+                recommended_action: None,
+            })
+            .next_action(NextAction {
+                description: "split spawn".into(),
+                owner: Some("gameplay".into()),
+                linked_checklist: None,
+            })
+            .build();
+
+        assert_eq!(report.id, "built");
+        assert_eq!(report.timestamp, "2025-03-01T00:00:00+00:00");
+        assert_eq!(report.summary.status, ReportStatus::Warn);
+        assert_eq!(report.summary.score, 0.7);
+        assert_eq!(
+            report.summary.checks,
+            CheckTallies {
+                pass: 1,
+                warn: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(report.risks.len(), 1);
+        assert_eq!(report.next_actions.len(), 1);
+
+        let failed = GuardrailReport::builder("failed")
+            .check(failing)
+            .next_action(NextAction {
+                description: "rerun".into(),
+                owner: None,
+                linked_checklist: None,
+            })
+            .build();
+        assert_eq!(failed.summary.status, ReportStatus::Fail);
+        let actions: Vec<&str> = failed
+            .next_actions
+            .iter()
+            .map(|action| action.description.as_str())
+            .collect();
+        assert_eq!(actions, ["fmt: run `cargo fmt --all`", "rerun"]);
+    }
+
+    #[test]
+    fn identical_reports_serialize_identically() {
+        let build = || {
+            let mut report = report_with_paths("/workspace");
+            report.timestamp = "2025-03-01T00:00:00+00:00".into();
+            report.risks.push(RiskEntry {
+                category: "determinism".into(),
+                description: "thread_rng".into(),
+                severity: "high".into(),
+                recommended_action: None,
+            });
+            report
+        };
+        let first = serde_json::to_string_pretty(&build()).unwrap();
+        let second = serde_json::to_string_pretty(&build()).unwrap();
+        assert_eq!(first, second);
+
+        let round_trip: GuardrailReport = serde_json::from_str(&first).unwrap();
+        assert_eq!(serde_json::to_string_pretty(&round_trip).unwrap(), first);
+    }
+
+    fn report_with_paths(root: &str) -> GuardrailReport {
+        let source = SourceInfo {
+            prompt_path: format!("{root}/.llm_logs/latest/prompt.md").into(),
+            response_path: "/home/alice/drafts/response.md".into(),
+            diff_path: "relative/patch.diff".into(),
+            spec_refs: Vec::new(),
+        };
+        let mut check = test_check(
+            "clippy",
+            CheckStatus::Fail,
+            &format!(
+                "error\n  --> {root}/crates/core_game/src/ui.rs:12:9\nsee /home/alice/notes.txt"
+            ),
+        );
+        check.log_path = Some(format!("{root}/reports/clippy.log").into());
+        GuardrailReport::new("redact", source, vec![check], "")
+    }
+
+    #[test]
+    fn redact_makes_workspace_paths_relative() {
+        let root = "/home/alice/src/strict-llm";
+        let mut report = report_with_paths(root);
+        report.redact(&RedactOptions {
+            workspace_root: Some(root.into()),
+            home: None,
+        });
+
+        assert_eq!(
+            report.source.prompt_path,
+            PathBuf::from(".llm_logs/latest/prompt.md")
+        );
+        assert_eq!(
+            report.source.response_path,
+            PathBuf::from("/home/alice/drafts/response.md")
+        );
+        assert_eq!(
+            report.source.diff_path,
+            PathBuf::from("relative/patch.diff")
+        );
+        assert_eq!(
+            report.checks[0].log_path,
+            Some(PathBuf::from("reports/clippy.log"))
+        );
+        assert_eq!(
+            report.checks[0].details,
+            "error\n  --> crates/core_game/src/ui.rs:12:9\nsee /home/alice/notes.txt"
+        );
+    }
+
+    #[test]
+    fn redact_replaces_home_outside_the_workspace() {
+        let root = "/home/alice/src/strict-llm";
+        let mut report = report_with_paths(root);
+        report.redact(&RedactOptions {
+            workspace_root: Some(root.into()),
+            home: Some("/home/alice".into()),
+        });
+
+        assert_eq!(
+            report.source.prompt_path,
+            PathBuf::from(".llm_logs/latest/prompt.md")
+        );
+        assert_eq!(
+            report.source.response_path,
+            PathBuf::from("~/drafts/response.md")
+        );
+        assert_eq!(
+            report.checks[0].details,
+            "error\n  --> crates/core_game/src/ui.rs:12:9\nsee ~/notes.txt"
+        );
+
+        let mut home_only = report_with_paths(root);
+        home_only.redact(&RedactOptions {
+            workspace_root: None,
+            home: Some("/home/alice".into()),
+        });
+        assert_eq!(
+            home_only.checks[0].log_path,
+            Some(PathBuf::from("~/src/strict-llm/reports/clippy.log"))
+        );
+    }
+
+    #[test]
+    fn content_digest_ignores_when_and_where_a_run_happened() {
+        let run = |root: &str, timestamp: &str| {
+            let mut report = report_with_paths(root);
+            report.id = format!("run-{timestamp}");
+            report.timestamp = timestamp.into();
+            report.checks[0].details = format!("finished in {}s", timestamp.len());
+            report.risks.push(RiskEntry {
+                category: "complexity".into(),
+                description: format!("fn spawn at {root}/crates/core_game/src/battle.rs:7 is long"),
+                severity: "medium".into(),
+                recommended_action: None,
+            });
+            report
+        };
+        let first = run("/workspace", "2025-03-01T00:00:00+00:00");
+        let second = run("/home/ci/strict-llm", "2025-03-02T12:30:00+00:00");
+        assert_eq!(first.content_digest().len(), 16);
+        assert_eq!(first.content_digest(), second.content_digest());
+
+        let mut fixed = second.clone();
+        fixed.checks[0].status = CheckStatus::Pass;
+        assert_ne!(fixed.content_digest(), first.content_digest());
+        let mut new_risk = second;
+        new_risk.risks[0].severity = "high".into();
+        assert_ne!(new_risk.content_digest(), first.content_digest());
+    }
+
+    #[test]
+    fn github_annotations_cover_failures_warnings_and_risks() {
+        let report = GuardrailReport::builder("ci")
+            .check(test_check("fmt", CheckStatus::Pass, "ok"))
+            .check(test_check(
+                "clippy",
+                CheckStatus::Fail,
+                "error: unused variable\n  --> crates/core_game/src/ui.rs:12:9\n100% sure",
+            ))
+            .check(test_check(
+                "bevy_sandbox_checks",
+                CheckStatus::Warn,
+                "no location, here",
+            ))
+            .risk(RiskEntry {
+                category: "determinism".into(),
+                description: "thread_rng in tests/loot.rs:42".into(),
+                severity: "medium".into(),
+                recommended_action: None,
+            })
+            .build();
+
+        assert_eq!(
+            report.github_annotations(),
+            vec![
+                "::error file=crates/core_game/src/ui.rs,line=12,title=clippy::error: unused variable%0A  --> crates/core_game/src/ui.rs:12:9%0A100%25 sure",
+                "::warning title=bevy_sandbox_checks::no location, here",
+                "::warning file=tests/loot.rs,line=42,title=determinism::thread_rng in tests/loot.rs:42",
+            ]
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+
+use super::{
+    remediation_actions, source_location, summarize_checks, CheckResult, CheckStatus,
+    GuardrailReport,
+};
+
+/// Severity a risk gets once [`subtract_baseline`] finds it in the baseline.
+pub const BASELINE_SEVERITY: &str = "info";
+
+/// What makes two findings "the same" across runs: the analyzer (check name
+/// or risk category), the file it points at, and the message with line and
+/// column numbers dropped, since unrelated edits shift those.
+type FindingKey = (String, Option<String>, String);
+
+fn finding_key(analyzer: &str, message: &str) -> FindingKey {
+    let file = source_location(message).map(|(file, _)| file);
+    (analyzer.to_string(), file, normalize_message(message))
+}
+
+/// Whitespace collapsed and `path.rs:12:9` shortened to `path.rs`.
+fn normalize_message(message: &str) -> String {
+    message
+        .split_whitespace()
+        .map(|token| match token.split_once(".rs:") {
+            Some((file, rest)) => {
+                let suffix = rest.trim_start_matches(|c: char| c.is_ascii_digit() || c == ':');
+                format!("{file}.rs{suffix}")
+            }
+            None => token.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A rustc/cargo diagnostic's first line: `error: ..`, `warning[E..]: ..`.
+fn is_diagnostic_header(line: &str) -> bool {
+    ["error", "warning"].iter().any(|level| {
+        line.strip_prefix(level)
+            .is_some_and(|rest| rest.starts_with(':') || rest.starts_with('['))
+    })
+}
+
+/// Cargo's and rustc's tallies (`generated 3 warnings`, `could not compile`),
+/// which change whenever any diagnostic does.
+fn is_build_summary(header: &str) -> bool {
+    [
+        ") generated ",
+        "could not compile",
+        "aborting due to",
+        "build failed",
+    ]
+    .iter()
+    .any(|summary| header.contains(summary))
+}
+
+/// The individual findings in a failing check's details, so a baseline
+/// matches them one by one. Rustc-style output counts only its diagnostics,
+/// each keyed by its header and first `-->` location; other output (the
+/// source scans) counts each line, prefixed by the `...:` heading it falls
+/// under. Details with neither count as one finding.
+fn check_findings(check: &CheckResult) -> Vec<FindingKey> {
+    let mut diagnostics = Vec::new();
+    let mut lines = Vec::new();
+    // Header and location of the diagnostic being read; `None` inside a
+    // build summary.
+    let mut current: Option<Option<(String, Option<String>)>> = None;
+    let mut heading = "";
+    for line in check.details.lines() {
+        let trimmed = line.trim();
+        if is_diagnostic_header(trimmed) {
+            diagnostics.extend(current.take().flatten());
+            current = Some((!is_build_summary(trimmed)).then(|| (trimmed.to_string(), None)));
+        } else if trimmed.is_empty() {
+            diagnostics.extend(current.take().flatten());
+            heading = "";
+        } else if let Some(diagnostic) = &mut current {
+            if let Some((_, file @ None)) = diagnostic {
+                if let Some(location) = trimmed.strip_prefix("-->") {
+                    *file = source_location(location).map(|(file, _)| file);
+                }
+            }
+        } else if trimmed == "--- stderr ---"
+            || (trimmed.starts_with("(after ") && trimmed.ends_with(" attempts)"))
+        {
+            // Added by `run_command`, not the analyzer.
+        } else if trimmed.ends_with(':') {
+            heading = trimmed;
+        } else if heading.is_empty() {
+            lines.push(finding_key(&check.name, trimmed));
+        } else {
+            lines.push(finding_key(&check.name, &format!("{heading} {trimmed}")));
+        }
+    }
+    diagnostics.extend(current.flatten());
+
+    if !diagnostics.is_empty() {
+        diagnostics
+            .into_iter()
+            .map(|(header, file)| (check.name.clone(), file, normalize_message(&header)))
+            .collect()
+    } else if !lines.is_empty() {
+        lines
+    } else {
+        vec![finding_key(&check.name, &check.details)]
+    }
+}
+
+/// Takes one occurrence of `key` out of `known`, if any is left.
+fn take(known: &mut BTreeMap<FindingKey, usize>, key: &FindingKey) -> bool {
+    match known.get_mut(key) {
+        Some(count) if *count > 0 => {
+            *count -= 1;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Demotes every finding in `report` that `baseline` already had, so a run
+/// over legacy code only flags what is new. Failing and warning checks are
+/// compared diagnostic by diagnostic (see [`check_findings`]), counting
+/// repeats: a check whose findings were all in the baseline passes with a
+/// note in its details, while one with new findings keeps its status and
+/// lists them first. Matching risks drop to [`BASELINE_SEVERITY`]. The
+/// summary and next actions are recomputed. Returns how many risks and checks
+/// were demoted.
+pub fn subtract_baseline(report: &mut GuardrailReport, baseline: &GuardrailReport) -> usize {
+    let mut known: BTreeMap<FindingKey, usize> = BTreeMap::new();
+    let baseline_findings = baseline
+        .risks
+        .iter()
+        .map(|risk| finding_key(&risk.category, &risk.description))
+        .chain(
+            baseline
+                .checks
+                .iter()
+                .filter(|check| matches!(check.status, CheckStatus::Fail | CheckStatus::Warn))
+                .flat_map(check_findings),
+        );
+    for key in baseline_findings {
+        *known.entry(key).or_default() += 1;
+    }
+
+    let mut demoted = 0;
+    for risk in &mut report.risks {
+        if risk.severity != BASELINE_SEVERITY
+            && take(&mut known, &finding_key(&risk.category, &risk.description))
+        {
+            risk.severity = BASELINE_SEVERITY.into();
+            demoted += 1;
+        }
+    }
+    for check in &mut report.checks {
+        if !matches!(check.status, CheckStatus::Fail | CheckStatus::Warn) {
+            continue;
+        }
+        let findings = check_findings(check);
+        let new: Vec<String> = findings
+            .iter()
+            .filter(|key| !take(&mut known, key))
+            .map(|(_, _, message)| message.clone())
+            .collect();
+        if new.is_empty() {
+            check.details = format!(
+                "{} in baseline; not counted.\n{}",
+                if check.status == CheckStatus::Fail {
+                    "Failure"
+                } else {
+                    "Warning"
+                },
+                check.details
+            );
+            check.status = CheckStatus::Pass;
+            demoted += 1;
+        } else if new.len() < findings.len() {
+            check.details = format!(
+                "{} of {} findings not in baseline:\n{}\n\n{}",
+                new.len(),
+                findings.len(),
+                new.join("\n"),
+                check.details
+            );
+        }
+    }
+
+    let (status, score, tallies) = summarize_checks(&report.checks);
+    report.summary.status = status;
+    report.summary.score = score;
+    report.summary.checks = tallies;
+    report.next_actions = remediation_actions(&report.checks);
+    demoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{test_check, ReportStatus, RiskEntry};
+
+    fn report(clippy: &str, risk: &str) -> GuardrailReport {
+        let check = |name: &str, status, details: &str| CheckResult {
+            remediation: Some("run `cargo clippy --fix`".into()),
+            ..test_check(name, status, details)
+        };
+        GuardrailReport::builder("legacy")
+            .check(check("fmt", CheckStatus::Pass, ""))
+            .check(check("clippy", CheckStatus::Fail, clippy))
+            .risk(RiskEntry {
+                category: "complexity".into(),
+                description: risk.into(),
+                severity: "medium".into(),
+                recommended_action: None,
+            })
+            .build()
+    }
+
+    #[test]
+    fn findings_in_the_baseline_are_demoted_to_informational() {
+        let baseline = report(
+            "error: unused variable\n  --> src/lib.rs:3:9",
+            "fn tick at src/lib.rs:40 spans 90 lines (limit 80)",
+        );
+        // Same findings, a few lines further down.
+        let mut current = report(
+            "error: unused variable\n  --> src/lib.rs:7:9",
+            "fn tick at src/lib.rs:44 spans 90 lines (limit 80)",
+        );
+
+        assert_eq!(subtract_baseline(&mut current, &baseline), 2);
+        assert_eq!(current.risks[0].severity, BASELINE_SEVERITY);
+        let clippy = &current.checks[1];
+        assert_eq!(clippy.status, CheckStatus::Pass);
+        assert!(
+            clippy.details.starts_with("Failure in baseline"),
+            "{}",
+            clippy.details
+        );
+        assert_eq!(current.summary.status, ReportStatus::Pass);
+        assert_eq!(current.summary.checks.fail, 0);
+        assert!(current.next_actions.is_empty());
+        assert_eq!(subtract_baseline(&mut current, &baseline), 0);
+    }
+
+    #[test]
+    fn new_findings_stay_flagged() {
+        let baseline = report(
+            "error: unused variable\n  --> src/lib.rs:3:9",
+            "fn tick at src/lib.rs:40 spans 90 lines (limit 80)",
+        );
+        let mut current = report(
+            "error: unused variable\n  --> src/ui.rs:3:9",
+            "fn tick at src/lib.rs:40 spans 120 lines (limit 80)",
+        );
+
+        assert_eq!(subtract_baseline(&mut current, &baseline), 0);
+        assert_eq!(current.risks[0].severity, "medium");
+        assert_eq!(current.checks[1].status, CheckStatus::Fail);
+        assert_eq!(current.summary.status, ReportStatus::Fail);
+        assert_eq!(current.next_actions.len(), 1);
+    }
+
+    #[test]
+    fn one_new_lint_among_baselined_ones_keeps_the_check_failing() {
+        let clippy = |lints: &[(&str, u32)]| {
+            let mut details = String::from("    Checking game v0.1.0\n");
+            for (lint, line) in lints {
+                details.push_str(&format!(
+                    "warning: {lint}\n  --> src/lib.rs:{line}:9\n   |\n{line} |     let x = 1;\n   |\n\n"
+                ));
+            }
+            details.push_str(&format!(
+                "warning: `game` (lib) generated {} warnings\nerror: could not compile `game` (lib) due to {} previous errors",
+                lints.len(),
+                lints.len()
+            ));
+            details
+        };
+        let risk = "fn tick at src/lib.rs:40 spans 90 lines (limit 80)";
+        let baseline = report(
+            &clippy(&[("unused variable: `x`", 3), ("unused variable: `x`", 9)]),
+            risk,
+        );
+
+        let mut shifted = report(
+            &clippy(&[("unused variable: `x`", 5), ("unused variable: `x`", 11)]),
+            risk,
+        );
+        assert_eq!(subtract_baseline(&mut shifted, &baseline), 2);
+        assert_eq!(shifted.checks[1].status, CheckStatus::Pass);
+
+        let mut current = report(
+            &clippy(&[
+                ("unused variable: `x`", 5),
+                ("this `if` has identical blocks", 8),
+                ("unused variable: `x`", 11),
+            ]),
+            risk,
+        );
+        assert_eq!(subtract_baseline(&mut current, &baseline), 1);
+        let check = &current.checks[1];
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(
+            check.details.starts_with(
+                "1 of 3 findings not in baseline:\nwarning: this `if` has identical blocks\n"
+            ),
+            "{}",
+            check.details
+        );
+        assert_eq!(current.summary.status, ReportStatus::Fail);
+
+        // A third copy of a baselined lint is new too.
+        let mut repeated = report(
+            &clippy(&[
+                ("unused variable: `x`", 5),
+                ("unused variable: `x`", 11),
+                ("unused variable: `x`", 20),
+            ]),
+            risk,
+        );
+        subtract_baseline(&mut repeated, &baseline);
+        assert_eq!(repeated.checks[1].status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn scan_findings_are_matched_line_by_line() {
+        let baseline = report(
+            "Found thread_rng usage in:\ncrates/sim/src/lib.rs",
+            "fn tick at src/lib.rs:40 spans 90 lines (limit 80)",
+        );
+        let mut current = report(
+            "Found thread_rng usage in:\ncrates/sim/src/lib.rs\n\nFound Instant::now usage in:\ncrates/sim/src/lib.rs",
+            "fn tick at src/lib.rs:40 spans 90 lines (limit 80)",
+        );
+        assert_eq!(subtract_baseline(&mut current, &baseline), 1);
+        assert_eq!(current.checks[1].status, CheckStatus::Fail);
+        assert!(current.checks[1]
+            .details
+            .starts_with("1 of 2 findings not in baseline:\nFound Instant::now usage in: crates/sim/src/lib.rs\n"));
+    }
+}
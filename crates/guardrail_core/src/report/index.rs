@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use super::{GuardrailReport, ReportStatus};
+use crate::error::{read_file, walk_error, write_file, GuardrailError, Result};
+
+/// File [`update_index`] maintains at the top of a reports directory.
+pub const INDEX_FILE_NAME: &str = "index.json";
+
+/// Every report under a reports directory, keyed by run id so tools can list
+/// runs without opening each file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReportIndex {
+    pub runs: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexEntry {
+    pub timestamp: String,
+    pub status: ReportStatus,
+    pub score: f32,
+    #[serde(default)]
+    pub fingerprint: String,
+    /// Relative to the reports directory.
+    pub path: PathBuf,
+}
+
+impl IndexEntry {
+    fn new(report: &GuardrailReport, path: PathBuf) -> Self {
+        Self {
+            timestamp: report.timestamp.clone(),
+            status: report.summary.status.clone(),
+            score: report.summary.score,
+            fingerprint: report.fingerprint.clone(),
+            path,
+        }
+    }
+}
+
+impl ReportIndex {
+    /// Reads an index file; a missing file is an empty index.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = read_file("index", path)?;
+        serde_json::from_str(&data).map_err(|source| GuardrailError::ReportParse {
+            label: "index",
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Drops entries whose file is gone from `reports_dir`, then adds or
+    /// refreshes one entry per report found beneath it. JSON files that
+    /// aren't reports (trend output, the index itself) are skipped.
+    pub fn refresh(&mut self, reports_dir: &Path) -> Result<()> {
+        self.runs
+            .retain(|_, entry| reports_dir.join(&entry.path).is_file());
+
+        let mut found: Vec<PathBuf> = Vec::new();
+        for entry in WalkDir::new(reports_dir) {
+            let entry = entry.map_err(walk_error(reports_dir))?;
+            let path = entry.path();
+            if entry.file_type().is_file()
+                && path.extension().is_some_and(|ext| ext == "json")
+                && path != reports_dir.join(INDEX_FILE_NAME)
+            {
+                found.push(path.to_path_buf());
+            }
+        }
+        found.sort();
+
+        for path in found {
+            let data = read_file("report", &path)?;
+            let Ok(report) = serde_json::from_str::<GuardrailReport>(&data) else {
+                tracing::debug!("skipping non-report {}", path.display());
+                continue;
+            };
+            let relative = path.strip_prefix(reports_dir).unwrap_or(&path);
+            self.runs.insert(
+                report.id.clone(),
+                IndexEntry::new(&report, relative.to_path_buf()),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write_file(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Loads `reports_dir/index.json`, refreshes it against the reports on disk,
+/// and writes it back.
+pub fn update_index(reports_dir: &Path) -> Result<ReportIndex> {
+    let index_path = reports_dir.join(INDEX_FILE_NAME);
+    let mut index = ReportIndex::load(&index_path)?;
+    index.refresh(reports_dir)?;
+    index.save(&index_path)?;
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{test_check, CheckStatus};
+
+    fn write_report(dir: &Path, file: &str, id: &str, status: CheckStatus) {
+        let report = GuardrailReport::builder(id)
+            .check(test_check("fmt", status, ""))
+            .fingerprint("abc")
+            .build();
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, serde_json::to_string_pretty(&report).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn index_merges_new_reports_and_drops_deleted_ones() {
+        let dir = std::env::temp_dir().join(format!("guardrail-index-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_report(&dir, "a.json", "run-a", CheckStatus::Pass);
+        write_report(&dir, "nested/b.json", "run-b", CheckStatus::Fail);
+        std::fs::write(dir.join("trend.json"), "{\"runs\": 2}").unwrap();
+
+        let index = update_index(&dir).unwrap();
+        let ids: Vec<&str> = index.runs.keys().map(String::as_str).collect();
+        assert_eq!(ids, vec!["run-a", "run-b"]);
+        let b = &index.runs["run-b"];
+        assert_eq!(b.status, ReportStatus::Fail);
+        assert_eq!(b.path, Path::new("nested").join("b.json"));
+        assert_eq!(b.fingerprint, "abc");
+        assert_eq!(
+            ReportIndex::load(&dir.join(INDEX_FILE_NAME)).unwrap(),
+            index
+        );
+
+        std::fs::remove_file(dir.join("a.json")).unwrap();
+        write_report(&dir, "c.json", "run-c", CheckStatus::Warn);
+        let index = update_index(&dir).unwrap();
+        let ids: Vec<&str> = index.runs.keys().map(String::as_str).collect();
+        assert_eq!(ids, vec!["run-b", "run-c"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+use std::fmt::Write;
+
+use super::{CheckResult, CheckStatus, GuardrailReport};
+
+/// How much of each check's details a renderer prints. The stored JSON
+/// always keeps everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Name and status only.
+    Quiet,
+    /// Plus the first non-empty line of the details.
+    #[default]
+    Normal,
+    /// The full details.
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    pub verbosity: Verbosity,
+}
+
+impl RenderOptions {
+    pub fn new(verbosity: Verbosity) -> Self {
+        Self { verbosity }
+    }
+}
+
+fn status_label(status: &CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Pass => "pass",
+        CheckStatus::Fail => "fail",
+        CheckStatus::Warn => "warn",
+        CheckStatus::Skipped => "skipped",
+    }
+}
+
+/// Remediation worth showing: only failing checks get one.
+fn fix(check: &CheckResult) -> Option<&str> {
+    (check.status == CheckStatus::Fail)
+        .then_some(check.remediation.as_deref())
+        .flatten()
+}
+
+fn first_line(check: &CheckResult) -> Option<&str> {
+    check
+        .details
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+}
+
+/// Details without leading blank lines or trailing whitespace; indentation
+/// of the first line is kept.
+fn details_body(check: &CheckResult) -> &str {
+    let details = check.details.trim_end();
+    let start = details
+        .lines()
+        .take_while(|line| line.trim().is_empty())
+        .map(|line| line.len() + 1)
+        .sum::<usize>();
+    &details[start.min(details.len())..]
+}
+
+impl GuardrailReport {
+    /// Plain-text report for terminals: the summary line, then one line per
+    /// check (with indented details at [`Verbosity::Full`]). Failing checks
+    /// get a `fix:` line at every verbosity.
+    pub fn render_text(&self, opts: &RenderOptions) -> String {
+        let tallies = self.summary.checks;
+        let mut out = format!(
+            "Report {} -> {:?} ({:.2}) [{} pass, {} warn, {} fail, {} skipped]\n",
+            self.id,
+            self.summary.status,
+            self.summary.score,
+            tallies.pass,
+            tallies.warn,
+            tallies.fail,
+            tallies.skipped
+        );
+        for check in &self.checks {
+            let status = status_label(&check.status);
+            match (opts.verbosity, first_line(check)) {
+                (Verbosity::Normal, Some(line)) => {
+                    let _ = writeln!(out, "  {}: {status} - {line}", check.name);
+                }
+                (Verbosity::Full, Some(_)) => {
+                    let _ = writeln!(out, "  {}: {status}", check.name);
+                    for line in details_body(check).lines() {
+                        let _ = writeln!(out, "    {line}");
+                    }
+                }
+                _ => {
+                    let _ = writeln!(out, "  {}: {status}", check.name);
+                }
+            }
+            if let Some(fix) = fix(check) {
+                let _ = writeln!(out, "    fix: {fix}");
+            }
+        }
+        out
+    }
+
+    /// Markdown report for PR comments: a summary paragraph, a check table,
+    /// a list of fixes for failing checks, and at [`Verbosity::Full`] each
+    /// check's details in a fenced block.
+    pub fn render_markdown(&self, opts: &RenderOptions) -> String {
+        let tallies = self.summary.checks;
+        let mut out = format!(
+            "## Guardrail report `{}`\n\n**Status:** {:?} (score {:.2}), {} pass, {} warn, {} fail, {} skipped\n\n",
+            self.id,
+            self.summary.status,
+            self.summary.score,
+            tallies.pass,
+            tallies.warn,
+            tallies.fail,
+            tallies.skipped
+        );
+        if opts.verbosity == Verbosity::Normal {
+            out.push_str("| Check | Status | Details |\n| --- | --- | --- |\n");
+        } else {
+            out.push_str("| Check | Status |\n| --- | --- |\n");
+        }
+        for check in &self.checks {
+            let status = status_label(&check.status);
+            if opts.verbosity == Verbosity::Normal {
+                let line = first_line(check).unwrap_or("").replace('|', "\\|");
+                let _ = writeln!(out, "| {} | {status} | {line} |", check.name);
+            } else {
+                let _ = writeln!(out, "| {} | {status} |", check.name);
+            }
+        }
+        let fixes: Vec<(&str, &str)> = self
+            .checks
+            .iter()
+            .filter_map(|check| Some((check.name.as_str(), fix(check)?)))
+            .collect();
+        if !fixes.is_empty() {
+            out.push_str("\n**Fixes**\n\n");
+            for (name, fix) in fixes {
+                let _ = writeln!(out, "- `{name}`: {fix}");
+            }
+        }
+        if opts.verbosity == Verbosity::Full {
+            for check in self
+                .checks
+                .iter()
+                .filter(|check| first_line(check).is_some())
+            {
+                let _ = write!(
+                    out,
+                    "\n### {}\n\n```text\n{}\n```\n",
+                    check.name,
+                    details_body(check)
+                );
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_check;
+
+    fn sample() -> GuardrailReport {
+        GuardrailReport::builder("pr-7")
+            .check(CheckResult {
+                remediation: Some("run `cargo fmt --all`".into()),
+                ..test_check("fmt", CheckStatus::Pass, "")
+            })
+            .check(CheckResult {
+                remediation: Some("run `cargo clippy --fix`".into()),
+                ..test_check(
+                    "clippy",
+                    CheckStatus::Fail,
+                    "\nerror: unused | variable\n  --> src/lib.rs:3:9\n",
+                )
+            })
+            .build()
+    }
+
+    #[test]
+    fn text_verbosity_levels() {
+        let report = sample();
+        let summary = "Report pr-7 -> Fail (0.00) [1 pass, 0 warn, 1 fail, 0 skipped]\n";
+        let fix = "    fix: run `cargo clippy --fix`\n";
+
+        assert_eq!(
+            report.render_text(&RenderOptions::new(Verbosity::Quiet)),
+            format!("{summary}  fmt: pass\n  clippy: fail\n{fix}")
+        );
+        assert_eq!(
+            report.render_text(&RenderOptions::default()),
+            format!("{summary}  fmt: pass\n  clippy: fail - error: unused | variable\n{fix}")
+        );
+        assert_eq!(
+            report.render_text(&RenderOptions::new(Verbosity::Full)),
+            format!(
+                "{summary}  fmt: pass\n  clippy: fail\n    error: unused | variable\n      --> src/lib.rs:3:9\n{fix}"
+            )
+        );
+    }
+
+    #[test]
+    fn markdown_verbosity_levels() {
+        let report = sample();
+        let fixes = "\n**Fixes**\n\n- `clippy`: run `cargo clippy --fix`\n";
+
+        let quiet = report.render_markdown(&RenderOptions::new(Verbosity::Quiet));
+        assert!(quiet.contains("| Check | Status |\n"), "{quiet}");
+        assert!(quiet.contains("| clippy | fail |\n"), "{quiet}");
+        assert!(!quiet.contains("unused"), "{quiet}");
+        assert!(quiet.ends_with(fixes), "{quiet}");
+
+        let normal = report.render_markdown(&RenderOptions::default());
+        assert!(
+            normal.contains("| clippy | fail | error: unused \\| variable |\n"),
+            "{normal}"
+        );
+        assert!(normal.contains("| fmt | pass |  |\n"), "{normal}");
+        assert!(!normal.contains("src/lib.rs"), "{normal}");
+        assert!(normal.contains(fixes), "{normal}");
+        assert!(!normal.contains("cargo fmt"), "{normal}");
+
+        let full = report.render_markdown(&RenderOptions::new(Verbosity::Full));
+        assert!(full.contains("| clippy | fail |\n"), "{full}");
+        assert!(
+            full.contains(
+                "### clippy\n\n```text\nerror: unused | variable\n  --> src/lib.rs:3:9\n```\n"
+            ),
+            "{full}"
+        );
+        assert!(!full.contains("### fmt"), "{full}");
+    }
+
+    #[test]
+    fn failing_remediations_become_next_actions() {
+        let actions: Vec<String> = sample()
+            .next_actions
+            .into_iter()
+            .map(|action| action.description)
+            .collect();
+        assert_eq!(actions, vec!["clippy: run `cargo clippy --fix`"]);
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::report::{CheckStatus, GuardrailReport, ReportStatus};
+
+/// Rollup of many guardrail runs, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrendReport {
+    pub runs: usize,
+    /// Keyed by check name, so output order is stable.
+    pub analyzers: BTreeMap<String, AnalyzerTrend>,
+    pub scores: Vec<ScorePoint>,
+}
+
+/// Outcomes of one analyzer across the runs that reported it. Runs from
+/// configs without the analyzer are absent, not failures; skipped runs are
+/// counted but excluded from the rates.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AnalyzerTrend {
+    pub present: usize,
+    pub pass: usize,
+    pub warn: usize,
+    pub fail: usize,
+    pub skipped: usize,
+    pub pass_rate: f32,
+    pub fail_rate: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScorePoint {
+    pub id: String,
+    pub timestamp: String,
+    pub status: ReportStatus,
+    pub score: f32,
+}
+
+/// Sorts `reports` by timestamp and tallies per-analyzer outcomes. Timestamps
+/// that aren't RFC 3339 sort before parseable ones, by string.
+pub fn aggregate_reports(reports: &[GuardrailReport]) -> TrendReport {
+    let mut ordered: Vec<&GuardrailReport> = reports.iter().collect();
+    ordered.sort_by(|a, b| {
+        let key = |report: &GuardrailReport| {
+            DateTime::<FixedOffset>::parse_from_rfc3339(&report.timestamp).ok()
+        };
+        key(a)
+            .cmp(&key(b))
+            .then_with(|| a.timestamp.cmp(&b.timestamp))
+    });
+
+    let mut analyzers: BTreeMap<String, AnalyzerTrend> = BTreeMap::new();
+    for report in &ordered {
+        for check in &report.checks {
+            let trend = analyzers.entry(check.name.clone()).or_default();
+            trend.present += 1;
+            match check.status {
+                CheckStatus::Pass => trend.pass += 1,
+                CheckStatus::Warn => trend.warn += 1,
+                CheckStatus::Fail => trend.fail += 1,
+                CheckStatus::Skipped => trend.skipped += 1,
+            }
+        }
+    }
+    for trend in analyzers.values_mut() {
+        let ran = trend.pass + trend.warn + trend.fail;
+        if ran > 0 {
+            trend.pass_rate = trend.pass as f32 / ran as f32;
+            trend.fail_rate = trend.fail as f32 / ran as f32;
+        }
+    }
+
+    let scores = ordered
+        .iter()
+        .map(|report| ScorePoint {
+            id: report.id.clone(),
+            timestamp: report.timestamp.clone(),
+            status: report.summary.status.clone(),
+            score: report.summary.score,
+        })
+        .collect();
+
+    TrendReport {
+        runs: ordered.len(),
+        analyzers,
+        scores,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::test_check;
+
+    fn report(id: &str, timestamp: &str, checks: &[(&str, CheckStatus)]) -> GuardrailReport {
+        GuardrailReport::builder(id)
+            .timestamp(timestamp)
+            .checks(
+                checks
+                    .iter()
+                    .map(|(name, status)| test_check(name, status.clone(), "")),
+            )
+            .build()
+    }
+
+    #[test]
+    fn trend_json_is_independent_of_input_order() {
+        let a = report(
+            "a",
+            "2025-03-01T00:00:00Z",
+            &[("fmt", CheckStatus::Pass), ("clippy", CheckStatus::Fail)],
+        );
+        let b = report(
+            "b",
+            "2025-03-02T00:00:00Z",
+            &[
+                ("deterministic_seed_scan", CheckStatus::Warn),
+                ("fmt", CheckStatus::Pass),
+            ],
+        );
+        let forward = aggregate_reports(&[a.clone(), b.clone()]);
+        let backward = aggregate_reports(&[b, a]);
+        assert_eq!(
+            serde_json::to_string_pretty(&forward).unwrap(),
+            serde_json::to_string_pretty(&backward).unwrap()
+        );
+    }
+
+    #[test]
+    fn trend_orders_runs_and_treats_missing_analyzers_as_absent() {
+        let reports = [
+            report(
+                "new",
+                "2025-03-02T00:00:00+00:00",
+                &[("fmt", CheckStatus::Pass), ("clippy", CheckStatus::Fail)],
+            ),
+            report(
+                "old",
+                "2025-03-01T12:00:00+02:00",
+                &[("fmt", CheckStatus::Fail)],
+            ),
+            report(
+                "newest",
+                "2025-03-03T00:00:00Z",
+                &[("fmt", CheckStatus::Pass), ("clippy", CheckStatus::Skipped)],
+            ),
+        ];
+
+        let trend = aggregate_reports(&reports);
+        assert_eq!(trend.runs, 3);
+        let ids: Vec<&str> = trend.scores.iter().map(|point| point.id.as_str()).collect();
+        assert_eq!(ids, vec!["old", "new", "newest"]);
+        assert_eq!(trend.scores[0].score, 0.0);
+        assert_eq!(trend.scores[2].status, ReportStatus::Pass);
+
+        let fmt = &trend.analyzers["fmt"];
+        assert_eq!((fmt.present, fmt.pass, fmt.fail), (3, 2, 1));
+        assert!((fmt.pass_rate - 2.0 / 3.0).abs() < 1e-6);
+
+        let clippy = &trend.analyzers["clippy"];
+        assert_eq!((clippy.present, clippy.fail, clippy.skipped), (2, 1, 1));
+        assert_eq!(clippy.fail_rate, 1.0);
+    }
+}
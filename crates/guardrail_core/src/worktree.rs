@@ -0,0 +1,249 @@
+//! Throwaway git worktrees for validating a series of patches as one change.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use tempfile::TempDir;
+
+use crate::diff;
+use crate::error::{read_file, GuardrailError, Result};
+
+/// A detached checkout of `HEAD` with patches applied, under a temporary
+/// directory. Dropping it unregisters the worktree and deletes the directory.
+#[derive(Debug)]
+pub struct PatchedWorktree {
+    repo: PathBuf,
+    path: PathBuf,
+    /// Where `repo` sits below the repository's top level.
+    prefix: PathBuf,
+    // Removed after the worktree is unregistered; see `Drop`.
+    _dir: TempDir,
+}
+
+impl PatchedWorktree {
+    /// Checks out `HEAD` of the repository containing `repo` into a fresh
+    /// worktree and applies `patches` in order, each normalized with
+    /// [`diff::normalize`]. Fails with [`GuardrailError::PatchApply`] on the
+    /// first patch that doesn't apply to the tree the earlier ones left.
+    pub fn apply(repo: &Path, patches: &[PathBuf]) -> Result<Self> {
+        let prefix = git(repo, &["rev-parse", "--show-prefix"], None, None)?;
+        if !prefix.status.success() {
+            return Err(GuardrailError::Git(format!(
+                "{} is not inside a git repository: {}",
+                repo.display(),
+                String::from_utf8_lossy(&prefix.stderr).trim()
+            )));
+        }
+        let prefix = PathBuf::from(String::from_utf8_lossy(&prefix.stdout).trim());
+        let dir = tempfile::Builder::new()
+            .prefix("guardrail-worktree-")
+            .tempdir()
+            .map_err(|source| GuardrailError::Write {
+                path: std::env::temp_dir(),
+                source,
+            })?;
+        let path = dir.path().join("tree");
+        let added = git(
+            repo,
+            &["worktree", "add", "--detach", "--quiet"],
+            Some(&path),
+            None,
+        )?;
+        if !added.status.success() {
+            return Err(GuardrailError::Git(format!(
+                "git worktree add in {} failed: {}",
+                repo.display(),
+                String::from_utf8_lossy(&added.stderr).trim()
+            )));
+        }
+        let worktree = Self {
+            repo: repo.to_path_buf(),
+            path,
+            prefix,
+            _dir: dir,
+        };
+        for (index, patch) in patches.iter().enumerate() {
+            let contents = diff::normalize(&read_file("patch", patch)?);
+            let applied = git(
+                &worktree.path,
+                &["apply", "--index", "-"],
+                None,
+                Some(&contents),
+            )?;
+            if !applied.status.success() {
+                return Err(GuardrailError::PatchApply {
+                    index: index + 1,
+                    count: patches.len(),
+                    path: patch.clone(),
+                    message: String::from_utf8_lossy(&applied.stderr).trim().to_string(),
+                });
+            }
+        }
+        Ok(worktree)
+    }
+
+    /// Top level of the patched tree.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The patched counterpart of the `repo` directory passed to
+    /// [`apply`](Self::apply), which may sit below the repository's top
+    /// level; run analyzers here.
+    pub fn workspace_root(&self) -> PathBuf {
+        self.path.join(&self.prefix)
+    }
+
+    /// Everything the patches changed, as one diff against `HEAD`.
+    pub fn combined_diff(&self) -> Result<String> {
+        let output = git(&self.path, &["diff", "--cached", "HEAD"], None, None)?;
+        if !output.status.success() {
+            return Err(GuardrailError::Git(format!(
+                "git diff in {} failed: {}",
+                self.path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        String::from_utf8(output.stdout)
+            .map_err(|err| GuardrailError::Git(format!("combined diff is not UTF-8: {err}")))
+    }
+}
+
+impl Drop for PatchedWorktree {
+    fn drop(&mut self) {
+        let removed = git(
+            &self.repo,
+            &["worktree", "remove", "--force"],
+            Some(&self.path),
+            None,
+        );
+        if !removed.is_ok_and(|output| output.status.success()) {
+            // The directory goes with the `TempDir` either way; prune drops
+            // the registration it leaves behind.
+            tracing::warn!("failed to remove worktree {}", self.path.display());
+            let _ = std::fs::remove_dir_all(&self.path);
+            let _ = git(&self.repo, &["worktree", "prune"], None, None);
+        }
+    }
+}
+
+/// Runs `git <args> [path]` in `dir`, feeding `stdin` when given.
+fn git(dir: &Path, args: &[&str], path: Option<&Path>, stdin: Option<&str>) -> Result<Output> {
+    let mut command = Command::new("git");
+    command.current_dir(dir).args(args);
+    if let Some(path) = path {
+        command.arg(path);
+    }
+    command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let spawn_error = |source| GuardrailError::GitSpawn {
+        command: args
+            .iter()
+            .take_while(|arg| !arg.starts_with('-'))
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" "),
+        source,
+    };
+    let mut child = command.spawn().map_err(spawn_error)?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        // A git that quit early closes the pipe; its exit status and stderr
+        // say why, so the write error adds nothing.
+        let _ = pipe.write_all(input.as_bytes());
+    }
+    child.wait_with_output().map_err(spawn_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequential_patches_leave_both_changes_and_clean_up() {
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .current_dir(root)
+                .args([
+                    "-c",
+                    "user.name=guardrail",
+                    "-c",
+                    "user.email=guardrail@example.com",
+                ])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?}");
+        };
+        git(&["init", "--quiet"]);
+        std::fs::write(root.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&["add", "lib.rs"]);
+        git(&["commit", "--quiet", "-m", "initial"]);
+
+        let patches = root.join("patches");
+        std::fs::create_dir(&patches).unwrap();
+        let first = patches.join("1.diff");
+        std::fs::write(
+            &first,
+            "--- a/lib.rs\n+++ b/lib.rs\n@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n",
+        )
+        .unwrap();
+        // Only applies on top of the first patch.
+        let second = patches.join("2.diff");
+        std::fs::write(
+            &second,
+            "--- a/lib.rs\n+++ b/lib.rs\n@@ -1,2 +1,2 @@\n fn a() {}\n-fn b() {}\n+fn b() -> u8 { 1 }\n--- /dev/null\n+++ b/new.rs\n@@ -0,0 +1 @@\n+fn c() {}\n",
+        )
+        .unwrap();
+
+        let worktree = PatchedWorktree::apply(root, &[first.clone(), second.clone()]).unwrap();
+        let tree = worktree.path().to_path_buf();
+        assert_eq!(
+            std::fs::read_to_string(tree.join("lib.rs")).unwrap(),
+            "fn a() {}\nfn b() -> u8 { 1 }\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(tree.join("new.rs")).unwrap(),
+            "fn c() {}\n"
+        );
+        let combined = worktree.combined_diff().unwrap();
+        assert_eq!(
+            diff::changed_files(&combined),
+            vec![PathBuf::from("lib.rs"), PathBuf::from("new.rs")]
+        );
+        assert!(combined.contains("+fn b() -> u8 { 1 }"));
+        assert_eq!(
+            std::fs::read_to_string(root.join("lib.rs")).unwrap(),
+            "fn a() {}\n",
+            "the original checkout must stay untouched"
+        );
+        drop(worktree);
+        assert!(!tree.exists());
+
+        let err = PatchedWorktree::apply(root, &[second.clone(), first.clone()]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("patch 1 of 2"), "{message}");
+        assert!(message.contains(&second.display().to_string()), "{message}");
+        std::fs::create_dir(root.join("game")).unwrap();
+        let nested = PatchedWorktree::apply(&root.join("game"), &[first]).unwrap();
+        assert_eq!(nested.workspace_root(), nested.path().join("game"));
+        assert!(nested.path().join("lib.rs").is_file());
+        drop(nested);
+
+        let listed = Command::new("git")
+            .current_dir(root)
+            .args(["worktree", "list"])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&listed.stdout).lines().count(), 1);
+    }
+}
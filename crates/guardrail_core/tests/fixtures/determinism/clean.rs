@@ -0,0 +1,12 @@
+use std::collections::BTreeMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub fn roll_damage(seed: u64, tick: u64) -> f32 {
+    StdRng::seed_from_u64(seed ^ tick).gen_range(4.0..8.0)
+}
+
+pub fn first_target(threat: &BTreeMap<u32, f32>) -> Option<u32> {
+    threat.keys().next().copied()
+}
@@ -0,0 +1,5 @@
+use std::time::Instant;
+
+pub fn cooldown_ready(fired_at: Instant) -> bool {
+    Instant::now().duration_since(fired_at).as_secs_f32() > 0.7
+}
@@ -0,0 +1,5 @@
+use std::hash::{BuildHasher, RandomState};
+
+pub fn bucket(id: u32) -> u64 {
+    RandomState::new().hash_one(id) % 16
+}
@@ -0,0 +1,6 @@
+use std::collections::{BTreeMap, HashMap};
+
+pub fn first_target(threat: &HashMap<u32, f32>, order: &mut BTreeMap<u32, f32>) -> Option<u32> {
+    order.extend(threat.iter().map(|(id, score)| (*id, *score)));
+    threat.keys().next().copied()
+}
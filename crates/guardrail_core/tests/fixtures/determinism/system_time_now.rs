@@ -0,0 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn spawn_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
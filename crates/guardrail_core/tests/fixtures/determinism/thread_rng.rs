@@ -0,0 +1,5 @@
+use rand::{thread_rng, Rng};
+
+pub fn roll_damage() -> f32 {
+    thread_rng().gen_range(4.0..8.0)
+}
@@ -0,0 +1,79 @@
+//! Times one `unit_combat_system` run over crowded boards.
+//!
+//! Throughput is reported per unit (units processed per second), so flat
+//! numbers across sizes mean linear scaling. Run with
+//! `cargo bench -p llm_regression --bench combat`.
+
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use core_game::gameplay::{combat_system, BoardSettings, PlayerId, Unit, UnitKind};
+use core_game::scenario::ScenarioBuilder;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use llm_regression::harness::headless_app_with;
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+const UNIT_COUNTS: [usize; 3] = [100, 500, 1000];
+const PLAYERS: usize = 4;
+const BOARD_SIZE: f32 = 1600.0;
+
+#[derive(ScheduleLabel, Hash, Debug, PartialEq, Eq, Clone)]
+struct CombatOnly;
+
+/// `units` lasers spread over a grid and dealt round-robin to the players,
+/// so every unit has allies and enemies in range. Health is raised far
+/// enough that nobody dies while the benchmark runs.
+fn crowded_app(units: usize) -> App {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 1.0e6,
+            pylon_count: 0,
+            ..Default::default()
+        },
+        |app| {
+            let side = (units as f32).sqrt().ceil() as usize;
+            let spacing = BOARD_SIZE * 0.8 / side as f32;
+            let origin = -BOARD_SIZE * 0.4;
+            let mut scenario = ScenarioBuilder::new()
+                .board_size(BOARD_SIZE)
+                .player_count(PLAYERS);
+            for idx in 0..units {
+                let position = Vec2::new(
+                    origin + (idx % side) as f32 * spacing,
+                    origin + (idx / side) as f32 * spacing,
+                );
+                scenario = scenario.unit(PlayerId(idx % PLAYERS), UnitKind::Laser, position);
+            }
+            scenario.build(app);
+        },
+    );
+    app.add_systems(CombatOnly, combat_system());
+
+    let world = app.world_mut();
+    for mut unit in world.query::<&mut Unit>().iter_mut(world) {
+        unit.max_health = 1.0e9;
+        unit.health = unit.max_health;
+    }
+    app
+}
+
+fn combat_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("combat_tick_per_unit");
+    for units in UNIT_COUNTS {
+        let mut app = crowded_app(units);
+        group.throughput(Throughput::Elements(units as u64));
+        group.bench_function(BenchmarkId::from_parameter(units), |b| {
+            b.iter(|| {
+                app.world_mut()
+                    .resource_mut::<Time>()
+                    .advance_by(Duration::from_millis(33));
+                app.world_mut().run_schedule(CombatOnly);
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, combat_tick);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Shared setup for headless `core_game` scenarios.
+
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::*;
+use core_game::gameplay::{BoardSettings, RenderEffects, RenderInterpolation, SimulationParams};
+use core_game::{run_simulation_tick, CoreGamePlugin};
+use std::time::Duration;
+
+/// Builds a headless app running [`CoreGamePlugin`] with the given seed and
+/// board, with `Startup` already executed and cosmetic [`RenderEffects`]
+/// and [`RenderInterpolation`] disabled. Drive it with [`run_fixed_ticks`].
+pub fn headless_app(seed: u64, board: BoardSettings) -> App {
+    headless_app_with(seed, board, |_| {})
+}
+
+/// Like [`headless_app`], but `configure` runs before the plugins are added so
+/// it can insert resources such as `Teams` or `InitialLayout`.
+pub fn headless_app_with(seed: u64, board: BoardSettings, configure: impl FnOnce(&mut App)) -> App {
+    let mut app = App::new();
+    app.insert_resource(SimulationParams::from_seed(seed));
+    app.insert_resource(board);
+    app.insert_resource(DiagnosticsStore::default());
+    app.insert_resource(RenderEffects(false));
+    app.insert_resource(RenderInterpolation(false));
+    configure(&mut app);
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(CoreGamePlugin);
+    app.update();
+    app
+}
+
+/// Advances `Time` by `step` and runs one simulation tick, `ticks` times.
+pub fn run_fixed_ticks(app: &mut App, ticks: usize, step: Duration) {
+    for _ in 0..ticks {
+        app.world_mut().resource_mut::<Time>().advance_by(step);
+        run_simulation_tick(app);
+    }
+}
@@ -1,17 +1,54 @@
 //! Helpers for deterministic regression tests.
 
+pub mod harness;
+
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use serde_json::json;
+use std::fmt::Debug;
 
 pub const DEFAULT_SEED: u64 = 42;
 
+/// Seeds swept by [`assert_deterministic`] when a test has no reason to pick its own.
+pub const DEFAULT_SEEDS: &[u64] = &[42, 7, 100, 999];
+
 pub fn sample_combat_roll(seed: u64) -> serde_json::Value {
     let mut rng = StdRng::seed_from_u64(seed);
     let roll = rng.gen_range(1..=20);
     json!({ "roll": roll, "seed": seed })
 }
 
+/// Recommended determinism harness for regression scenarios.
+///
+/// Runs `f` twice per seed and asserts both runs match, then asserts that at
+/// least two seeds produce different outputs so a function that ignores its
+/// seed cannot pass. Pass [`DEFAULT_SEEDS`] unless the scenario needs specific
+/// seeds.
+pub fn assert_deterministic<F, T>(seeds: &[u64], f: F)
+where
+    F: Fn(u64) -> T,
+    T: PartialEq + Debug,
+{
+    assert!(
+        seeds.len() >= 2,
+        "assert_deterministic needs at least two seeds, got {seeds:?}"
+    );
+
+    let mut outputs: Vec<T> = Vec::with_capacity(seeds.len());
+    for &seed in seeds {
+        let baseline = f(seed);
+        let repeat = f(seed);
+        assert_eq!(baseline, repeat, "seed {seed} produced different outputs");
+        outputs.push(baseline);
+    }
+
+    let diverges = outputs.iter().any(|output| output != &outputs[0]);
+    assert!(
+        diverges,
+        "all seeds {seeds:?} produced the same output; is the seed ignored?"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -22,4 +59,15 @@ mod tests {
         let b = sample_combat_roll(DEFAULT_SEED);
         assert_eq!(a, b);
     }
+
+    #[test]
+    fn seed_sweep_accepts_seeded_rolls() {
+        assert_deterministic(DEFAULT_SEEDS, sample_combat_roll);
+    }
+
+    #[test]
+    #[should_panic(expected = "is the seed ignored")]
+    fn seed_sweep_rejects_seed_independent_output() {
+        assert_deterministic(DEFAULT_SEEDS, |_| 4);
+    }
 }
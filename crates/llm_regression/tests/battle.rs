@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use core_game::battle::{resolve_battle, UnitSpec};
+use core_game::gameplay::{GameOutcome, PlayerId, UnitKind};
+use llm_regression::DEFAULT_SEED;
+
+fn army(x: f32, count: usize) -> Vec<UnitSpec> {
+    (0..count)
+        .map(|idx| UnitSpec::new(UnitKind::Laser, Vec2::new(x, idx as f32 * 30.0 - 90.0)))
+        .collect()
+}
+
+#[test]
+fn superior_army_wins_deterministically() {
+    let result = resolve_battle(&army(-300.0, 3), &army(300.0, 8), DEFAULT_SEED, 3_000);
+
+    assert_eq!(result.outcome, GameOutcome::Victory(PlayerId(1)));
+    assert_eq!(result.winner, Some(PlayerId(1)));
+    assert_eq!(result.survivors[0], 0);
+    assert!(result.survivors[1] > 0);
+    assert_eq!(result.stats[1].kills, 3);
+    assert!(result.ticks < 3_000);
+
+    let replay = resolve_battle(&army(-300.0, 3), &army(300.0, 8), DEFAULT_SEED, 3_000);
+    assert_eq!(replay, result);
+}
+
+#[test]
+fn tick_cap_ends_the_battle_in_a_draw() {
+    let result = resolve_battle(&army(-300.0, 3), &army(300.0, 3), DEFAULT_SEED, 5);
+    assert_eq!(result.outcome, GameOutcome::Draw);
+    assert_eq!(result.winner, None);
+    assert_eq!(result.ticks, 5);
+    assert_eq!(result.survivors, [3, 3]);
+}
+
+#[test]
+fn mirrored_armies_draw() {
+    let result = resolve_battle(&army(-300.0, 4), &army(300.0, 4), DEFAULT_SEED, 3_000);
+    assert_eq!(result.outcome, GameOutcome::Draw);
+    assert_eq!(result.winner, None);
+    assert_eq!(result.survivors, [0, 0]);
+    assert_eq!(result.stats[0], result.stats[1]);
+    assert!(result.ticks < 3_000, "traded down to nobody before the cap");
+}
+
+#[test]
+fn mirrored_armies_out_of_range_draw_at_the_cap() {
+    // 1200 apart, closing at 240/s: still out of laser range when the cap hits.
+    let result = resolve_battle(&army(-600.0, 4), &army(600.0, 4), DEFAULT_SEED, 90);
+    assert_eq!(result.outcome, GameOutcome::Draw);
+    assert_eq!(result.winner, None);
+    assert_eq!(result.ticks, 90);
+    assert_eq!(result.survivors, [4, 4]);
+    assert_eq!(result.stats[0], result.stats[1]);
+    assert_eq!(result.stats[0].shots, 0);
+}
+
+#[test]
+fn empty_army_loses_on_the_first_tick() {
+    // Reinforcements are off, so army B's first units will never arrive.
+    let result = resolve_battle(&army(-300.0, 1), &[], DEFAULT_SEED, 500);
+    assert_eq!(result.outcome, GameOutcome::Victory(PlayerId(0)));
+    assert_eq!(result.winner, Some(PlayerId(0)));
+    assert_eq!(result.ticks, 1);
+    assert_eq!(result.survivors, [1, 0]);
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use core_game::gameplay::{BoardSettings, CombatEvent, CombatLog, PlayerId, UnitId, UnitKind};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app, headless_app_with, run_fixed_ticks};
+use llm_regression::{assert_deterministic, DEFAULT_SEED};
+use std::time::Duration;
+
+#[test]
+fn combat_log_is_deterministic_and_ordered() {
+    assert_deterministic(&[42, 7], |seed| {
+        let log = simulate_combat_log(seed);
+        assert_shots_sorted_per_tick(&log);
+        serde_json::to_value(&log).expect("combat events serialize")
+    });
+}
+
+/// Two lasers against one, close enough to trade fire from the first tick:
+/// shots from both sides, then the lone unit's death.
+#[test]
+fn two_on_one_combat_log_snapshot() {
+    let mut app = headless_app_with(DEFAULT_SEED, BoardSettings::default(), |app| {
+        ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, -20.0))
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, 20.0))
+            .unit(PlayerId(1), UnitKind::Laser, Vec2::new(200.0, 0.0))
+            .build(app);
+    });
+    run_fixed_ticks(&mut app, 60, Duration::from_millis(100));
+
+    let events: Vec<&CombatEvent> = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .collect();
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, CombatEvent::Kill { .. })),
+        "the lone unit should fall"
+    );
+    insta::assert_json_snapshot!("two_on_one", events);
+}
+
+fn simulate_combat_log(seed: u64) -> Vec<CombatEvent> {
+    let mut app = headless_app(
+        seed,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 0.5,
+            board_size: 500.0,
+            ..Default::default()
+        },
+    );
+    run_fixed_ticks(&mut app, 240, Duration::from_millis(100));
+    let events: Vec<CombatEvent> = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .cloned()
+        .collect();
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, CombatEvent::Shot { .. })),
+        "opposing squads should exchange fire"
+    );
+    events
+}
+
+fn assert_shots_sorted_per_tick(events: &[CombatEvent]) {
+    let shots: Vec<(u64, UnitId)> = events
+        .iter()
+        .filter_map(|event| match event {
+            CombatEvent::Shot { tick, attacker, .. } => Some((*tick, *attacker)),
+            _ => None,
+        })
+        .collect();
+    assert!(shots.windows(2).all(|pair| pair[0] <= pair[1]));
+}
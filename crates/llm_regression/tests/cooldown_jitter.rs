@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, CombatEvent, CombatLog, PlayerId, Unit, UnitId, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Ticks on which each of two side-by-side player 0 units fired at a sturdy
+/// enemy in range of both.
+fn shot_ticks(cooldown_jitter: f32) -> [Vec<u64>; 2] {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(BalanceSettings {
+                cooldown_jitter,
+                ..Default::default()
+            });
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, -20.0))
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, 20.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(200.0, 0.0))
+                .build(app);
+        },
+    );
+    let world = app.world_mut();
+    for mut unit in world.query::<&mut Unit>().iter_mut(world) {
+        if unit.player == PlayerId(1) {
+            unit.max_health = 10_000.0;
+            unit.health = unit.max_health;
+        }
+    }
+    run_fixed_ticks(&mut app, 60, Duration::from_millis(100));
+
+    let mut ticks = [Vec::new(), Vec::new()];
+    for event in app.world().resource::<CombatLog>().events() {
+        if let CombatEvent::Shot {
+            tick,
+            attacker: UnitId(id @ 0..=1),
+            ..
+        } = *event
+        {
+            ticks[id as usize].push(tick);
+        }
+    }
+    ticks
+}
+
+#[test]
+fn units_without_jitter_fire_in_lockstep() {
+    let [first, second] = shot_ticks(0.0);
+    assert!(!first.is_empty());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn jitter_desyncs_units_spawned_together() {
+    let [first, second] = shot_ticks(0.1);
+    assert!(!first.is_empty() && !second.is_empty());
+    assert_ne!(first[0], second[0], "first volley still simultaneous");
+    assert_eq!(shot_ticks(0.1), [first, second]);
+}
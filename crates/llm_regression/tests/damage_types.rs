@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, CombatEvent, CombatLog, DamageType, PlayerId, Unit, UnitId,
+    UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+#[test]
+fn one_target_takes_each_damage_type_at_its_own_resistance() {
+    // Cannons shrug off kinetic hits and are weak to energy.
+    let resistances: HashMap<_, _> = [
+        ((UnitKind::Cannon, DamageType::Kinetic), 0.5),
+        ((UnitKind::Cannon, DamageType::Energy), 1.5),
+    ]
+    .into_iter()
+    .collect();
+    let mut app = headless_app_with(DEFAULT_SEED, BoardSettings::default(), |app| {
+        app.insert_resource(BalanceSettings {
+            resistances,
+            ..Default::default()
+        });
+        ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, 20.0))
+            .unit(PlayerId(0), UnitKind::Cannon, Vec2::new(0.0, -20.0))
+            .unit(PlayerId(1), UnitKind::Cannon, Vec2::new(200.0, 0.0))
+            .build(app);
+    });
+    // Long enough for the slower Cannon to fire once.
+    run_fixed_ticks(&mut app, 20, Duration::from_millis(100));
+
+    let world = app.world_mut();
+    let attackers: HashMap<UnitId, UnitKind> = world
+        .query::<(&UnitId, &Unit)>()
+        .iter(world)
+        .filter(|(_, unit)| unit.player == PlayerId(0))
+        .map(|(id, unit)| (*id, unit.kind))
+        .collect();
+    let first_shot = |kind: UnitKind| {
+        app.world()
+            .resource::<CombatLog>()
+            .events()
+            .iter()
+            .find_map(|event| match *event {
+                CombatEvent::Shot {
+                    attacker, damage, ..
+                } if attackers.get(&attacker) == Some(&kind) => Some(damage),
+                _ => None,
+            })
+            .expect("both attackers fire on the target in range")
+    };
+
+    // Same base damage and multipliers, so only the resistances differ.
+    let energy = first_shot(UnitKind::Laser);
+    let kinetic = first_shot(UnitKind::Cannon);
+    assert!(
+        (energy / kinetic - 3.0).abs() < 1e-5,
+        "{energy} vs {kinetic}"
+    );
+}
@@ -1,13 +1,34 @@
-use core_game::gameplay::{SimulationParams, SimulationRng};
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, PlayerHealth, SimulationParams, SimulationRng,
+};
+use llm_regression::harness::{headless_app, run_fixed_ticks};
+use llm_regression::{assert_deterministic, DEFAULT_SEED, DEFAULT_SEEDS};
+use std::time::Duration;
 
 #[test]
 fn simulation_rng_is_deterministic() {
-    let baseline = sample_values(42);
-    let repeat = sample_values(42);
-    assert_eq!(baseline, repeat, "same seed should match");
+    assert_deterministic(DEFAULT_SEEDS, sample_values);
+}
+
+#[test]
+fn player_health_decays_linearly_per_tick() {
+    let mut app = headless_app(DEFAULT_SEED, BoardSettings::default());
+    let fixed_delta = app.world().resource::<SimulationParams>().fixed_delta as f32;
+    let rate = app
+        .world()
+        .resource::<BalanceSettings>()
+        .health_decay_per_second;
+
+    let ticks = 30;
+    run_fixed_ticks(&mut app, ticks, Duration::from_millis(500));
 
-    let different = sample_values(7);
-    assert_ne!(baseline, different, "different seeds should diverge");
+    let health = app.world().resource::<PlayerHealth>().value;
+    let expected = health.max() - ticks as f32 * rate * fixed_delta;
+    assert!(
+        (health.current() - expected).abs() < 1e-3,
+        "expected {expected}, got {}",
+        health.current()
+    );
 }
 
 fn sample_values(seed: u64) -> Vec<u32> {
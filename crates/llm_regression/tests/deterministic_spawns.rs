@@ -1,58 +1,72 @@
-use bevy::app::FixedUpdate;
-use bevy::diagnostic::DiagnosticsStore;
 use bevy::prelude::*;
-use bevy::time::TimePlugin;
-use core_game::gameplay::{BoardSettings, SimulationParams, Unit};
-use core_game::CoreGamePlugin;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, NextUnitId, SimulationParams, SimulationPosition, Unit, UnitId,
+};
+use llm_regression::assert_deterministic;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
 use std::time::Duration;
 
 #[test]
 fn rts_spawns_are_deterministic() {
-    let baseline = simulate_player_centroids(42);
-    let repeat = simulate_player_centroids(42);
-    assert_eq!(baseline, repeat, "same seed should match");
+    assert_deterministic(&[42, 7], |seed| simulate_player_centroids(seed, false));
+}
 
-    let different = simulate_player_centroids(7);
-    assert_ne!(baseline, different, "different seeds should diverge");
+#[test]
+fn rts_spawns_are_deterministic_with_portable_math() {
+    assert_deterministic(&[42, 7], |seed| simulate_player_centroids(seed, true));
 }
 
-fn simulate_player_centroids(seed: u64) -> Vec<(i32, i32)> {
-    let mut app = App::new();
-    app.insert_resource(SimulationParams::from_seed(seed));
-    app.insert_resource(BoardSettings {
+/// Player centroids after each of 120 half-second ticks: the original
+/// test's workload (a reinforcement wave every other tick, uncapped, three
+/// players fighting), compared tick by tick so a divergence fails where it
+/// happens rather than only if it survives to the end.
+fn simulate_player_centroids(seed: u64, deterministic_math: bool) -> Vec<[(i32, i32); 3]> {
+    const STEP: Duration = Duration::from_millis(500);
+    let board = BoardSettings {
         player_count: 3,
         spawn_interval: 0.8,
         board_size: 800.0,
+        max_units_per_player: 0,
+        ..Default::default()
+    };
+    let mut app = headless_app_with(seed, board, |app| {
+        app.insert_resource(SimulationParams {
+            fixed_delta: STEP.as_secs_f64(),
+            ..SimulationParams::from_seed(seed)
+        });
+        app.insert_resource(BalanceSettings {
+            deterministic_math,
+            ..Default::default()
+        });
     });
-    app.insert_resource(DiagnosticsStore::default());
-    app.add_plugins(MinimalPlugins.set(TimePlugin::default()));
-    app.add_plugins(CoreGamePlugin);
+    let centroids: Vec<[(i32, i32); 3]> = (0..120)
+        .map(|_| {
+            run_fixed_ticks(&mut app, 1, STEP);
+            player_centroids(&mut app)
+        })
+        .collect();
 
-    app.update();
-    for _ in 0..120 {
-        {
-            let mut time = app.world_mut().resource_mut::<Time>();
-            time.advance_by(Duration::from_millis(500));
-        }
-        app.world_mut().run_schedule(FixedUpdate);
-    }
+    // Ids count up from 0, so the next one is the number spawned: 2 starters
+    // each, plus one unit each per wave, a wave every round(0.8 / 0.5) ticks.
+    let spawned = app.world_mut().resource_mut::<NextUnitId>().allocate();
+    assert_eq!(spawned, UnitId(3 * 2 + 3 * 60));
+    centroids
+}
 
+fn player_centroids(app: &mut App) -> [(i32, i32); 3] {
     let world = app.world_mut();
-    let mut sums = vec![Vec2::ZERO; 3];
-    let mut counts = vec![0.0; 3];
-    let mut query = world.query::<(&Unit, &Transform)>();
-    for (unit, transform) in query.iter(&world) {
+    let mut sums = [Vec2::ZERO; 3];
+    let mut counts = [0.0; 3];
+    let mut query = world.query::<(&Unit, &SimulationPosition)>();
+    for (unit, position) in query.iter(world) {
         let idx = unit.player.0;
-        sums[idx] += transform.translation.truncate();
+        sums[idx] += position.current;
         counts[idx] += 1.0;
     }
-    sums.iter_mut()
-        .zip(counts.iter())
-        .map(|(sum, count)| {
-            if *count > 0.0 {
-                *sum /= *count;
-            }
-            (sum.x.round() as i32, sum.y.round() as i32)
-        })
-        .collect()
+    let mut centroids = [(0, 0); 3];
+    for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+        let mean = if count > 0.0 { sum / count } else { sum };
+        *centroid = (mean.x.round() as i32, mean.y.round() as i32);
+    }
+    centroids
 }
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use core_game::gameplay::{BalanceSettings, BoardSettings, Economy, PlayerId, Unit};
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Units per player after a few dozen reinforcement waves on a board too wide
+/// for anyone to fight, along with the app for inspecting the economy.
+fn after_waves(balance: BalanceSettings) -> (App, [usize; 2]) {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 0.1,
+            board_size: 6000.0,
+            max_units_per_player: 0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(balance);
+        },
+    );
+    // One wave every three ticks.
+    run_fixed_ticks(&mut app, 90, Duration::from_millis(100));
+
+    let world = app.world_mut();
+    let mut counts = [0; 2];
+    for unit in world.query::<&Unit>().iter(world) {
+        counts[unit.player.0] += 1;
+    }
+    (app, counts)
+}
+
+#[test]
+fn economy_off_spawns_every_wave_for_free() {
+    let (app, counts) = after_waves(BalanceSettings::default());
+    assert_eq!(counts, [2 + 30, 2 + 30]);
+    assert_eq!(app.world().resource::<Economy>().balance(PlayerId(0)), 0.0);
+}
+
+#[test]
+fn resource_starved_player_stops_spawning() {
+    let (app, counts) = after_waves(BalanceSettings {
+        economy_enabled: true,
+        income_per_second: 0.0,
+        pylon_income_per_second: 0.0,
+        ..Default::default()
+    });
+    assert_eq!(counts, [2, 2]);
+    assert_eq!(app.world().resource::<Economy>().balance(PlayerId(1)), 0.0);
+}
+
+#[test]
+fn income_pays_for_waves_at_the_rate_it_accrues() {
+    // Ten per second buys one unit every half second: every fifth wave.
+    let (app, counts) = after_waves(BalanceSettings {
+        economy_enabled: true,
+        income_per_second: 10.0,
+        pylon_income_per_second: 0.0,
+        unit_cost: 5.0,
+        ..Default::default()
+    });
+    assert_eq!(counts, [2 + 6, 2 + 6]);
+    let balance = app.world().resource::<Economy>().balance(PlayerId(0));
+    assert!((0.0..5.0).contains(&balance), "{balance}");
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    BoardSettings, EventFeed, FeedEntry, FeedEvent, GameOutcome, PlayerId, UnitId, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Feed entries from two player 0 lasers finishing off a lone player 1 laser,
+/// with `Update` run after every tick as a rendered build would.
+fn feed_after_skirmish() -> Vec<FeedEntry> {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, -20.0))
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, 20.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(150.0, 0.0))
+                .build(app);
+        },
+    );
+    for _ in 0..300 {
+        run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+        app.world_mut().run_schedule(Update);
+        if *app.world().resource::<GameOutcome>() != GameOutcome::InProgress {
+            break;
+        }
+    }
+    app.world()
+        .resource::<EventFeed>()
+        .entries()
+        .copied()
+        .collect()
+}
+
+#[test]
+fn feed_narrates_kill_elimination_and_result_in_tick_order() {
+    let entries = feed_after_skirmish();
+    let events: Vec<FeedEvent> = entries.iter().map(|entry| entry.event).collect();
+    assert!(
+        matches!(
+            events.as_slice(),
+            [
+                FeedEvent::UnitDestroyed {
+                    unit: UnitId(2),
+                    owner: Some(PlayerId(1)),
+                    by: UnitId(0 | 1),
+                },
+                FeedEvent::PlayerEliminated(PlayerId(1)),
+                FeedEvent::MatchDecided(GameOutcome::Victory(PlayerId(0))),
+            ]
+        ),
+        "{events:?}"
+    );
+    assert!(entries[0].tick > 0);
+    assert!(entries.iter().all(|entry| entry.tick == entries[0].tick));
+    assert!(entries[1].to_string().ends_with("player 1 eliminated"));
+
+    assert_eq!(feed_after_skirmish(), entries);
+}
+
+#[test]
+fn feed_drops_oldest_entries_past_capacity() {
+    let mut feed = EventFeed::with_capacity(2);
+    for tick in 1..=3 {
+        feed.push(tick, FeedEvent::PlayerEliminated(PlayerId(tick as usize)));
+    }
+    let ticks: Vec<u64> = feed.entries().map(|entry| entry.tick).collect();
+    assert_eq!(ticks, [2, 3]);
+    assert_eq!(feed.recent(1).next().unwrap().tick, 3);
+}
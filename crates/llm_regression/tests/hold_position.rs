@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    diagnose_unit, issue_order, BoardSettings, CombatEvent, CombatLog, Order, PlayerId,
+    SimulationPosition, Unit, UnitId, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Units spawned by `scenario`, in spawn order, with reinforcements off.
+fn scenario(build: impl FnOnce(ScenarioBuilder) -> ScenarioBuilder) -> (App, Vec<Entity>) {
+    let board = BoardSettings {
+        spawn_interval: 600.0,
+        ..Default::default()
+    };
+    let mut app = headless_app_with(DEFAULT_SEED, board, |app| {
+        build(ScenarioBuilder::new()).build(app);
+    });
+    let world = app.world_mut();
+    let mut units: Vec<(UnitId, Entity)> = world
+        .query::<(&UnitId, Entity)>()
+        .iter(world)
+        .map(|(id, entity)| (*id, entity))
+        .collect();
+    units.sort();
+    (app, units.into_iter().map(|(_, entity)| entity).collect())
+}
+
+fn position(app: &App, unit: Entity) -> Vec2 {
+    app.world().get::<SimulationPosition>(unit).unwrap().current
+}
+
+#[test]
+fn held_unit_fires_but_does_not_pursue() {
+    let (mut app, units) = scenario(|builder| {
+        builder.unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO).unit(
+            PlayerId(1),
+            UnitKind::Laser,
+            Vec2::new(200.0, 0.0),
+        )
+    });
+    let (holder, enemy) = (units[0], units[1]);
+    let world = app.world_mut();
+    world.get_mut::<Unit>(enemy).unwrap().max_health = 10_000.0;
+    world.get_mut::<Unit>(enemy).unwrap().health = 10_000.0;
+    issue_order(world, &[holder], Order::Hold);
+    assert!(diagnose_unit(world, UnitId(0)).unwrap().holding);
+
+    run_fixed_ticks(&mut app, 30, Duration::from_millis(100));
+    let fired = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .any(|event| {
+            matches!(
+                event,
+                CombatEvent::Shot {
+                    attacker: UnitId(0),
+                    ..
+                }
+            )
+        });
+    assert!(fired, "held unit stopped shooting");
+
+    issue_order(
+        app.world_mut(),
+        &[enemy],
+        Order::Move(Vec2::new(700.0, 0.0)),
+    );
+    run_fixed_ticks(&mut app, 90, Duration::from_millis(100));
+    assert!(position(&app, enemy).x > 600.0, "enemy walked away");
+    assert!(
+        position(&app, holder).length() < 1.0,
+        "held unit drifted to {}",
+        position(&app, holder)
+    );
+    assert!(app.world().get::<Unit>(holder).unwrap().holding);
+}
+
+#[test]
+fn stop_and_move_orders_release_a_hold() {
+    let spread = |hold: bool| {
+        let (mut app, units) = scenario(|builder| {
+            builder
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, 0.0))
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(10.0, 0.0))
+        });
+        issue_order(app.world_mut(), &units, Order::Move(Vec2::new(300.0, 0.0)));
+        issue_order(app.world_mut(), &units, Order::Stop);
+        if hold {
+            issue_order(app.world_mut(), &units, Order::Hold);
+        }
+        run_fixed_ticks(&mut app, 30, Duration::from_millis(100));
+        position(&app, units[0]).distance(position(&app, units[1]))
+    };
+    // Stopped units still shoulder each other apart; held ones stay put.
+    assert!(spread(false) > 20.0);
+    assert!((spread(true) - 10.0).abs() < 1.0);
+
+    let (mut app, units) =
+        scenario(|builder| builder.unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO));
+    issue_order(app.world_mut(), &units, Order::Hold);
+    issue_order(app.world_mut(), &units, Order::Move(Vec2::new(100.0, 0.0)));
+    assert!(!app.world().get::<Unit>(units[0]).unwrap().holding);
+    run_fixed_ticks(&mut app, 30, Duration::from_millis(100));
+    assert!(position(&app, units[0]).x > 50.0);
+}
@@ -0,0 +1,45 @@
+use core_game::gameplay::{BoardSettings, GameOutcome};
+use core_game::summary::MatchSummarySettings;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::fs;
+use std::time::Duration;
+
+#[test]
+fn tick_limit_writes_summary_and_exits() {
+    let path = std::env::temp_dir().join(format!("match-summary-{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 3,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(MatchSummarySettings {
+                path: path.clone(),
+                max_ticks: 20,
+            });
+        },
+    );
+
+    // The summary cap is the board's, so the board itself calls the draw.
+    assert_eq!(app.world().resource::<BoardSettings>().max_match_ticks, 20);
+    run_fixed_ticks(&mut app, 19, Duration::from_millis(100));
+    assert!(!path.exists(), "summary must wait for the tick limit");
+    run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+    assert!(app.should_exit().is_some());
+    assert_eq!(*app.world().resource::<GameOutcome>(), GameOutcome::Draw);
+
+    let summary: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).expect("summary written")).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(summary["seed"], DEFAULT_SEED);
+    assert_eq!(summary["final_tick"], 20);
+    assert_eq!(summary["outcome"]["result"], "draw");
+    let players = summary["players"].as_array().unwrap();
+    assert_eq!(players.len(), 3);
+    assert_eq!(players[2]["player"], 2);
+    assert!(players[0]["units_alive"].as_u64().unwrap() > 0);
+    assert!(players[0].get("kills").is_some());
+}
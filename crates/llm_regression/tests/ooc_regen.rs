@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, CombatEvent, CombatLog, PlayerId, Unit, UnitId, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Player 0's unit at the origin starts at `health`, facing one player 1
+/// unit at `enemy_x` with effectively unlimited health, with units
+/// regenerating `regen` HP/s after 30 quiet ticks. Returns the app and player
+/// 0's unit.
+fn duel(health: f32, enemy_x: f32, regen: f32) -> (App, Entity) {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(BalanceSettings {
+                ooc_regen_per_second: regen,
+                ooc_regen_grace_ticks: 30,
+                ..Default::default()
+            });
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(enemy_x, 0.0))
+                .build(app);
+        },
+    );
+
+    let world = app.world_mut();
+    let mut lone = None;
+    for (entity, mut unit) in world.query::<(Entity, &mut Unit)>().iter_mut(world) {
+        if unit.player == PlayerId(0) {
+            unit.health = health;
+            lone = Some(entity);
+        } else {
+            unit.max_health = 10_000.0;
+            unit.health = unit.max_health;
+        }
+    }
+    (app, lone.expect("scenario spawns player 0"))
+}
+
+#[test]
+fn lone_damaged_unit_recovers_to_full() {
+    let (mut app, unit) = duel(5.0, 700.0, 2.0);
+    run_fixed_ticks(&mut app, 400, Duration::from_millis(100));
+
+    let unit = app.world().get::<Unit>(unit).unwrap();
+    assert_eq!(unit.health, unit.max_health);
+}
+
+#[test]
+fn regen_is_off_by_default() {
+    assert_eq!(BalanceSettings::default().ooc_regen_per_second, 0.0);
+    let (mut app, unit) = duel(5.0, 700.0, BalanceSettings::default().ooc_regen_per_second);
+    run_fixed_ticks(&mut app, 400, Duration::from_millis(100));
+
+    assert_eq!(app.world().get::<Unit>(unit).unwrap().health, 5.0);
+}
+
+#[test]
+fn unit_under_fire_does_not_regenerate() {
+    let (mut app, unit) = duel(90.0, 200.0, 2.0);
+    // Twice the grace window, so a quiet unit would have started healing.
+    run_fixed_ticks(&mut app, 60, Duration::from_millis(100));
+
+    let health = app.world().get::<Unit>(unit).unwrap().health;
+    assert!(health < 90.0, "unit kept {health} health under fire");
+    let id = *app.world().get::<UnitId>(unit).unwrap();
+    let healed = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .any(|event| matches!(*event, CombatEvent::Heal { unit: healed, .. } if healed == id));
+    assert!(!healed, "unit regenerated while being shot");
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    issue_order, BoardSettings, Order, PlayerId, SimulationPosition, Unit, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+#[test]
+fn programmatic_orders_move_units_into_formation() {
+    let board = BoardSettings {
+        spawn_interval: 600.0,
+        ..Default::default()
+    };
+    let mut app = headless_app_with(DEFAULT_SEED, board, |app| {
+        ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-100.0, 0.0))
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-100.0, 50.0))
+            .build(app);
+    });
+    let world = app.world_mut();
+    let mut units: Vec<Entity> = world
+        .query_filtered::<Entity, With<Unit>>()
+        .iter(world)
+        .collect();
+    units.sort();
+
+    let anchor = Vec2::new(200.0, 0.0);
+    let waypoint = Vec2::new(200.0, 200.0);
+    issue_order(world, &units, Order::Move(anchor));
+    issue_order(world, &units, Order::Queue(waypoint));
+    issue_order(world, &[Entity::from_raw(9_999)], Order::Move(anchor));
+
+    let first = world.get::<Unit>(units[0]).unwrap();
+    assert_eq!(first.rally_target, anchor);
+    assert_eq!(first.waypoints.front(), Some(&waypoint));
+    let second = world.get::<Unit>(units[1]).unwrap();
+    assert!((second.rally_target.distance(anchor) - 60.0).abs() < 1e-3);
+
+    run_fixed_ticks(&mut app, 20, Duration::from_millis(100));
+    let moved = app
+        .world()
+        .get::<SimulationPosition>(units[0])
+        .unwrap()
+        .current;
+    assert!(moved.x > -100.0, "unit followed the order to {anchor}");
+}
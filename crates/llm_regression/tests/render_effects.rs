@@ -0,0 +1,115 @@
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::*;
+use core_game::diagnostics::BEAM_EFFECTS;
+use core_game::gameplay::{
+    BoardSettings, CombatEvent, CombatLog, PlayerId, RenderEffects, RenderSettings, Unit, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+const TICKS: usize = 10_000;
+
+#[derive(Debug, PartialEq)]
+struct RunSummary {
+    shots: usize,
+    kills: usize,
+    units_per_player: [usize; 2],
+}
+
+/// Two lines of six units facing each other inside weapon range.
+fn skirmish(app: &mut App) {
+    let scenario = (0..6).fold(ScenarioBuilder::new(), |scenario, row| {
+        let y = row as f32 * 40.0 - 100.0;
+        scenario
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-100.0, y))
+            .unit(PlayerId(1), UnitKind::Laser, Vec2::new(100.0, y))
+    });
+    scenario.build(app);
+}
+
+/// Runs a long match and returns the live entity count with a summary of the
+/// combat. Entity ids shift when cosmetic entities are spawned, so the summary
+/// only keeps id-independent numbers.
+fn long_run(render: bool) -> (u32, RunSummary) {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 60.0,
+            board_size: 1600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(RenderEffects(render));
+            skirmish(app);
+        },
+    );
+    run_fixed_ticks(&mut app, TICKS, Duration::from_millis(33));
+
+    let world = app.world_mut();
+    let entities = world.entities().len();
+    let mut units_per_player = [0; 2];
+    for unit in world.query::<&Unit>().iter(world) {
+        units_per_player[unit.player.0] += 1;
+    }
+    let events = world.resource::<CombatLog>().events();
+    let count = |pred: fn(&CombatEvent) -> bool| events.iter().filter(|e| pred(e)).count();
+    let summary = RunSummary {
+        shots: count(|e| matches!(e, CombatEvent::Shot { .. })),
+        kills: count(|e| matches!(e, CombatEvent::Kill { .. })),
+        units_per_player,
+    };
+    (entities, summary)
+}
+
+#[test]
+fn headless_runs_skip_cosmetic_entities_without_changing_combat() {
+    let (rendered_entities, rendered) = long_run(true);
+    let (headless_entities, headless) = long_run(false);
+
+    assert!(headless.shots > 0, "the match should see combat");
+    assert_eq!(rendered, headless);
+    assert!(
+        headless_entities * 4 < rendered_entities,
+        "expected far fewer entities headless: {headless_entities} vs {rendered_entities}"
+    );
+}
+
+#[test]
+fn beam_effects_stop_at_configured_cap() {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 60.0,
+            board_size: 1600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(RenderEffects(true));
+            app.insert_resource(RenderSettings {
+                max_beam_effects: 5,
+            });
+            skirmish(app);
+        },
+    );
+    // `update_beam_effects` runs in `Update`, so beams never expire here.
+    run_fixed_ticks(&mut app, 300, Duration::from_millis(33));
+
+    let shots = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .filter(|event| matches!(event, CombatEvent::Shot { .. }))
+        .count();
+    assert!(shots > 5, "the skirmish should fire past the cap");
+    let live = app
+        .world()
+        .resource::<DiagnosticsStore>()
+        .get(&BEAM_EFFECTS)
+        .and_then(|diagnostic| diagnostic.value());
+    assert_eq!(live, Some(5.0));
+}
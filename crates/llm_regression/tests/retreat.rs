@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, PlayerId, SimulationPosition, Unit, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Player 0's unit at the origin, just above half health, in range of a
+/// sturdy player 1 unit on the side away from player 0's spawn. Returns the
+/// app and player 0's unit.
+fn skirmish(retreat_threshold: f32) -> (App, Entity) {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(BalanceSettings {
+                retreat_threshold,
+                ..Default::default()
+            });
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(-200.0, 0.0))
+                .build(app);
+        },
+    );
+
+    let world = app.world_mut();
+    let mut damaged = None;
+    for (entity, mut unit) in world.query::<(Entity, &mut Unit)>().iter_mut(world) {
+        if unit.player == PlayerId(0) {
+            unit.health = unit.max_health * 0.55;
+            damaged = Some(entity);
+        } else {
+            unit.max_health = 10_000.0;
+            unit.health = unit.max_health;
+        }
+    }
+    (app, damaged.expect("scenario spawns player 0"))
+}
+
+#[test]
+fn damaged_unit_without_retreat_fights_to_the_death() {
+    let (mut app, unit) = skirmish(0.0);
+    run_fixed_ticks(&mut app, 150, Duration::from_millis(100));
+
+    assert!(app.world().get::<Unit>(unit).is_none());
+}
+
+#[test]
+fn damaged_unit_flees_toward_spawn_and_survives() {
+    let (mut app, unit) = skirmish(0.5);
+    run_fixed_ticks(&mut app, 150, Duration::from_millis(100));
+
+    let world = app.world();
+    let fled = world.get::<Unit>(unit).expect("retreating unit survived");
+    assert!(fled.is_retreating());
+    assert!(fled.health > 0.0);
+    let x = world.get::<SimulationPosition>(unit).unwrap().current.x;
+    // Player 0's spawn is on +x, the enemy at -200 with range 260.
+    assert!(x > 60.0, "unit only reached x = {x}");
+}
+
+#[test]
+fn retreating_unit_resumes_its_order_once_healed() {
+    let (mut app, unit) = skirmish(0.5);
+    run_fixed_ticks(&mut app, 150, Duration::from_millis(100));
+    let world = app.world_mut();
+    let mut fled = world.get_mut::<Unit>(unit).unwrap();
+    fled.order_move(Vec2::new(100.0, 100.0));
+    assert!(fled.is_retreating(), "orders wait until the unit heals");
+    fled.health = fled.max_health;
+    run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+
+    let resumed = app.world().get::<Unit>(unit).unwrap();
+    assert!(!resumed.is_retreating());
+    assert_eq!(resumed.rally_target, Vec2::new(100.0, 100.0));
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use core_game::gameplay::{BoardSettings, PlayerId, SimulationPosition, Unit, UnitKind};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::headless_app_with;
+use llm_regression::DEFAULT_SEED;
+
+#[test]
+fn builder_spawns_exactly_the_declared_layout() {
+    let mut app = headless_app_with(DEFAULT_SEED, BoardSettings::default(), |app| {
+        // Two units of player 0 flanking one of player 1.
+        ScenarioBuilder::new()
+            .board_size(900.0)
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-150.0, 80.0))
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-150.0, -80.0))
+            .unit(PlayerId(1), UnitKind::Laser, Vec2::new(150.0, 0.0))
+            .pylon(Vec2::ZERO)
+            .build(app);
+    });
+
+    let board = app.world().resource::<BoardSettings>();
+    assert_eq!(board.board_size, 900.0);
+    assert_eq!(board.player_count, 2);
+
+    let world = app.world_mut();
+    let mut placements: Vec<(usize, IVec2)> = world
+        .query::<(&Unit, &SimulationPosition)>()
+        .iter(world)
+        .map(|(unit, position)| (unit.player.0, position.current.as_ivec2()))
+        .collect();
+    placements.sort_by_key(|&(player, pos)| (player, pos.x, pos.y));
+    assert_eq!(
+        placements,
+        vec![
+            (0, IVec2::new(-150, -80)),
+            (0, IVec2::new(-150, 80)),
+            (1, IVec2::new(150, 0)),
+        ]
+    );
+}
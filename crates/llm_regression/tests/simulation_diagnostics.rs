@@ -0,0 +1,28 @@
+use bevy::diagnostic::{DiagnosticPath, DiagnosticsStore};
+use core_game::diagnostics::{MATCH_TICK, UNITS_PER_PLAYER, UNITS_TOTAL};
+use core_game::gameplay::BoardSettings;
+use llm_regression::harness::{headless_app, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+#[test]
+fn simulation_diagnostics_track_units_and_ticks() {
+    let mut app = headless_app(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 0.8,
+            board_size: 800.0,
+            ..Default::default()
+        },
+    );
+    run_fixed_ticks(&mut app, 10, Duration::from_millis(100));
+
+    let store = app.world().resource::<DiagnosticsStore>();
+    let value = |path: &DiagnosticPath| store.get(path).and_then(|d| d.value()).unwrap_or(-1.0);
+
+    assert_eq!(value(&MATCH_TICK), 10.0);
+    let per_player: f64 = UNITS_PER_PLAYER.iter().map(value).sum();
+    assert_eq!(value(&UNITS_TOTAL), per_player);
+    assert!(value(&UNITS_PER_PLAYER[0]) >= 2.0, "starter units counted");
+}
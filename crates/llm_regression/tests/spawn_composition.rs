@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use core_game::gameplay::{BoardSettings, SimulationPosition, Unit, UnitId, UnitKind};
+use llm_regression::assert_deterministic;
+use llm_regression::harness::{headless_app, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+
+/// Every unit after a few dozen reinforcement waves, on a board wide enough
+/// that the players never come into range of each other.
+fn spawned_units(
+    seed: u64,
+    spawn_composition: Vec<(UnitKind, u32)>,
+) -> Vec<(UnitId, UnitKind, IVec2)> {
+    let mut app = headless_app(
+        seed,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 0.1,
+            board_size: 6000.0,
+            max_units_per_player: 0,
+            spawn_composition,
+            ..Default::default()
+        },
+    );
+    // One wave every three ticks.
+    run_fixed_ticks(&mut app, 120, Duration::from_millis(100));
+
+    let world = app.world_mut();
+    let mut units: Vec<(UnitId, UnitKind, IVec2)> = world
+        .query::<(&UnitId, &Unit, &SimulationPosition)>()
+        .iter(world)
+        .map(|(id, unit, position)| (*id, unit.kind, position.current.round().as_ivec2()))
+        .collect();
+    units.sort_by_key(|(id, ..)| *id);
+    units
+}
+
+#[test]
+fn composition_leaves_other_streams_alone() {
+    let drawn = spawned_units(DEFAULT_SEED, BoardSettings::default().spawn_composition);
+    assert_eq!(drawn.len(), 2 * 2 + 2 * 40);
+    assert!(drawn.iter().all(|(_, kind, _)| *kind == UnitKind::Laser));
+
+    // No weights draws nothing from the composition stream; the units still
+    // get the same ids in the same places.
+    let undrawn = spawned_units(DEFAULT_SEED, Vec::new());
+    assert_eq!(drawn, undrawn);
+}
+
+fn cannon_count(units: &[(UnitId, UnitKind, IVec2)]) -> usize {
+    units
+        .iter()
+        .filter(|(_, kind, _)| *kind == UnitKind::Cannon)
+        .count()
+}
+
+#[test]
+fn even_composition_fields_both_kinds() {
+    let units = spawned_units(
+        DEFAULT_SEED,
+        vec![(UnitKind::Laser, 1), (UnitKind::Cannon, 1)],
+    );
+    assert_eq!(units.len(), 2 * 2 + 2 * 40);
+    // Roughly half, and exactly this many under the default seed.
+    assert_eq!(cannon_count(&units), 47);
+
+    let all_cannons = spawned_units(DEFAULT_SEED, vec![(UnitKind::Cannon, 1)]);
+    assert_eq!(cannon_count(&all_cannons), units.len());
+}
+
+#[test]
+fn composition_draws_are_reproducible() {
+    assert_deterministic(&[DEFAULT_SEED, 7], |seed| {
+        spawned_units(seed, vec![(UnitKind::Laser, 3), (UnitKind::Cannon, 1)])
+    });
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, PlayerId, SimulationPosition, SupplyState, Unit,
+};
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+fn supply_app(grace_ticks: u64) -> App {
+    headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 60.0,
+            board_size: 1600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(BalanceSettings {
+                supply_grace_ticks: grace_ticks,
+                ..Default::default()
+            });
+        },
+    )
+}
+
+/// Runs one tick, then strands a supplied player 0 unit in the middle of the
+/// board, far from spawn and allies.
+fn strand_player_0_unit(app: &mut App) -> Entity {
+    run_fixed_ticks(app, 1, Duration::from_millis(100));
+
+    let world = app.world_mut();
+    let mut units = world.query::<(Entity, &Unit)>();
+    let unit = units
+        .iter(world)
+        .find(|(_, unit)| unit.player == PlayerId(0))
+        .map(|(entity, _)| entity)
+        .expect("player 0 starts with units");
+    assert!(app.world().resource::<SupplyState>().is_supplied(unit));
+
+    let mut stranded = app.world_mut().entity_mut(unit);
+    *stranded.get_mut::<SimulationPosition>().unwrap() = SimulationPosition::new(Vec2::ZERO);
+    let mut state = stranded.get_mut::<Unit>().unwrap();
+    state.rally_target = Vec2::ZERO;
+    state.velocity = Vec2::ZERO;
+    unit
+}
+
+#[test]
+fn unit_cut_off_from_spawn_loses_supply() {
+    let mut app = supply_app(0);
+    let unit = strand_player_0_unit(&mut app);
+    run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+
+    let supply = app.world().resource::<SupplyState>();
+    assert!(!supply.is_supplied(unit));
+    assert_eq!(supply.pylon_bonus(unit), 0.0);
+    assert!(supply.supplied_count() > 0, "units at spawn stay supplied");
+}
+
+#[test]
+fn cut_off_unit_stays_boosted_for_the_grace_period() {
+    let mut app = supply_app(5);
+    let unit = strand_player_0_unit(&mut app);
+
+    for tick in 1..=5 {
+        run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+        assert!(
+            app.world().resource::<SupplyState>().is_supplied(unit),
+            "still supplied {tick} ticks after disconnecting"
+        );
+        let scale = app.world().get::<Transform>(unit).unwrap().scale;
+        assert!(
+            scale.x > 1.0,
+            "still boosted {tick} ticks after disconnecting"
+        );
+    }
+
+    run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+    assert!(!app.world().resource::<SupplyState>().is_supplied(unit));
+    assert_eq!(app.world().get::<Transform>(unit).unwrap().scale.x, 1.0);
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    issue_order, BalanceSettings, BoardSettings, CombatEvent, CombatLog, Order, PlayerId, Unit,
+    UnitId, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Targets of every shot the lone player 0 laser fires while, of two sturdy
+/// enemies in range, the nearer one backs off and the farther one closes in.
+fn shooter_targets(target_lock: bool) -> Vec<UnitId> {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(BalanceSettings {
+                target_lock,
+                ..Default::default()
+            });
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(120.0, 0.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(220.0, 60.0))
+                .build(app);
+        },
+    );
+    let world = app.world_mut();
+    let mut units: Vec<(UnitId, Entity)> = world
+        .query::<(&UnitId, Entity)>()
+        .iter(world)
+        .map(|(id, entity)| (*id, entity))
+        .collect();
+    units.sort();
+    for &(_, entity) in &units {
+        let mut unit = world.get_mut::<Unit>(entity).unwrap();
+        unit.max_health = 100_000.0;
+        unit.health = unit.max_health;
+    }
+    run_fixed_ticks(&mut app, 30, Duration::from_millis(100));
+    let world = app.world_mut();
+    issue_order(world, &[units[1].1], Order::Move(Vec2::new(240.0, 0.0)));
+    issue_order(world, &[units[2].1], Order::Move(Vec2::new(100.0, 60.0)));
+    run_fixed_ticks(&mut app, 50, Duration::from_millis(100));
+
+    app.world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .filter_map(|event| match *event {
+            CombatEvent::Shot {
+                attacker: UnitId(0),
+                target,
+                ..
+            } => Some(target),
+            _ => None,
+        })
+        .collect()
+}
+
+fn switches(targets: &[UnitId]) -> usize {
+    targets.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+#[test]
+fn locked_units_switch_targets_less_often() {
+    let free = shooter_targets(false);
+    let locked = shooter_targets(true);
+    assert!(free.len() > 10 && locked.len() > 10, "{free:?} {locked:?}");
+    assert!(switches(&free) > 0, "nearest never changed: {free:?}");
+    assert!(
+        switches(&locked) < switches(&free),
+        "{locked:?} vs {free:?}"
+    );
+    assert_eq!(shooter_targets(true), locked);
+}
+
+/// `(tick, target)` of the player 0 laser's shots when the enemy it starts on
+/// walks out of range, leaving another enemy in range.
+fn shots_as_target_leaves(target_lock: bool) -> Vec<(u64, UnitId)> {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(BalanceSettings {
+                target_lock,
+                target_lock_grace_ticks: 20,
+                ..Default::default()
+            });
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(120.0, 0.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(200.0, 80.0))
+                .build(app);
+        },
+    );
+    let world = app.world_mut();
+    for mut unit in world.query::<&mut Unit>().iter_mut(world) {
+        unit.max_health = 100_000.0;
+        unit.health = unit.max_health;
+    }
+    run_fixed_ticks(&mut app, 20, Duration::from_millis(100));
+    let world = app.world_mut();
+    let leaver = world
+        .query::<(&UnitId, Entity)>()
+        .iter(world)
+        .find(|(id, _)| **id == UnitId(1))
+        .map(|(_, entity)| entity)
+        .unwrap();
+    issue_order(world, &[leaver], Order::Move(Vec2::new(700.0, 0.0)));
+    run_fixed_ticks(&mut app, 60, Duration::from_millis(100));
+
+    app.world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .filter_map(|event| match *event {
+            CombatEvent::Shot {
+                tick,
+                attacker: UnitId(0),
+                target,
+                ..
+            } => Some((tick, target)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Ticks between the last shot at the enemy that left and the first at the
+/// one that stayed.
+fn retarget_gap(shots: &[(u64, UnitId)]) -> u64 {
+    let last_on_leaver = shots.iter().rfind(|(_, target)| *target == UnitId(1));
+    let first_on_stayer = shots.iter().find(|(_, target)| *target == UnitId(2));
+    match (last_on_leaver, first_on_stayer) {
+        (Some((left, _)), Some((stayed, _))) if stayed > left => stayed - left,
+        _ => panic!("never moved on: {shots:?}"),
+    }
+}
+
+#[test]
+fn lock_holds_through_the_grace_period_then_moves_on() {
+    let free = retarget_gap(&shots_as_target_leaves(false));
+    let locked = retarget_gap(&shots_as_target_leaves(true));
+    assert!(free < 20, "unlocked unit waited {free} ticks");
+    assert!(locked > 20, "locked unit only waited {locked} ticks");
+}
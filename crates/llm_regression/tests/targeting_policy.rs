@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, CombatEvent, CombatLog, PlayerId, SimulationPosition,
+    TargetingPolicy, Unit, UnitId, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// One attacker faces a healthy enemy up close and a wounded one further
+/// back; returns the id of each and the first target it shot.
+fn first_target(policy: TargetingPolicy) -> (UnitId, UnitId, UnitId) {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 60.0,
+            board_size: 1600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(BalanceSettings {
+                targeting: policy,
+                ..Default::default()
+            });
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, 0.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(80.0, 0.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(200.0, 0.0))
+                .build(app);
+        },
+    );
+
+    let world = app.world_mut();
+    let mut query = world.query::<(&UnitId, &SimulationPosition, &mut Unit)>();
+    let mut attacker = None;
+    let mut near = None;
+    let mut far = None;
+    for (&id, position, mut unit) in query.iter_mut(world) {
+        match (unit.player, position.current.x > 100.0) {
+            (PlayerId(0), _) => attacker = Some(id),
+            (_, false) => near = Some(id),
+            (_, true) => {
+                unit.health = 5.0;
+                far = Some(id);
+            }
+        }
+    }
+    let (attacker, near, far) = (attacker.unwrap(), near.unwrap(), far.unwrap());
+
+    run_fixed_ticks(&mut app, 10, Duration::from_millis(100));
+
+    let target = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .find_map(|event| match *event {
+            CombatEvent::Shot {
+                attacker: shooter,
+                target,
+                ..
+            } if shooter == attacker => Some(target),
+            _ => None,
+        })
+        .expect("attacker should fire within range");
+    (near, far, target)
+}
+
+#[test]
+fn nearest_policy_shoots_closest_enemy() {
+    let (near, _, target) = first_target(TargetingPolicy::Nearest);
+    assert_eq!(target, near);
+}
+
+#[test]
+fn lowest_health_policy_focuses_wounded_enemy() {
+    let (_, far, target) = first_target(TargetingPolicy::LowestHealth);
+    assert_eq!(target, far);
+}
@@ -0,0 +1,94 @@
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::*;
+use core_game::diagnostics::SUPPORT_LINKS;
+use core_game::gameplay::{
+    BoardSettings, CombatEvent, CombatLog, GameOutcome, PlayerId, SupplyState, TeamId, Teams, Unit,
+    UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+#[test]
+fn allied_players_hold_fire_and_share_support_links() {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 60.0,
+            board_size: 1600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(Teams::from_alliances(&[vec![0, 1]]));
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(0.0, 0.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(60.0, 0.0))
+                .build(app);
+        },
+    );
+
+    run_fixed_ticks(&mut app, 60, Duration::from_millis(100));
+
+    let shots = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .filter(|event| matches!(event, CombatEvent::Shot { .. }))
+        .count();
+    assert_eq!(shots, 0, "allies must not fire on each other");
+
+    let links = app
+        .world()
+        .resource::<DiagnosticsStore>()
+        .get(&SUPPORT_LINKS)
+        .and_then(|diagnostic| diagnostic.value());
+    assert_eq!(links, Some(1.0), "allied units should link");
+    assert_eq!(
+        *app.world().resource::<GameOutcome>(),
+        GameOutcome::TeamVictory(TeamId(0))
+    );
+}
+
+/// Player 1's units sit at player 0's spawn, the second one linked only
+/// through the first, and far from player 1's own spawn.
+fn units_at_ally_spawn(teams: Teams) -> Vec<bool> {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 60.0,
+            board_size: 1600.0,
+            ..Default::default()
+        },
+        |app| {
+            app.insert_resource(teams);
+            ScenarioBuilder::new()
+                .player_count(2)
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(560.0, 0.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(680.0, 0.0))
+                .build(app);
+        },
+    );
+
+    run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+
+    let world = app.world_mut();
+    let units: Vec<Entity> = world
+        .query_filtered::<Entity, With<Unit>>()
+        .iter(world)
+        .collect();
+    let supply = app.world().resource::<SupplyState>();
+    units.iter().map(|unit| supply.is_supplied(*unit)).collect()
+}
+
+#[test]
+fn allies_share_supply_through_each_others_spawns() {
+    assert_eq!(
+        units_at_ally_spawn(Teams::from_alliances(&[vec![0, 1]])),
+        [true, true]
+    );
+    assert_eq!(units_at_ally_spawn(Teams::default()), [false, false]);
+}
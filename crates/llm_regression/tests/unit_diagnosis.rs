@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use core_game::gameplay::{
+    diagnose_unit, issue_order, BoardSettings, Order, PlayerId, UnitActivity, UnitId, UnitKind,
+    UnitOrder,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+
+/// Two lone lasers, well out of range of each other.
+fn standoff() -> App {
+    headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(700.0, 0.0))
+                .build(app);
+        },
+    )
+}
+
+#[test]
+fn unit_with_no_order_and_no_enemy_in_range_is_idle() {
+    let mut app = standoff();
+    run_fixed_ticks(&mut app, 10, Duration::from_millis(100));
+
+    let diagnosis = diagnose_unit(app.world(), UnitId(0)).expect("unit 0 is alive");
+    assert_eq!(diagnosis.player, PlayerId(0));
+    assert_eq!(diagnosis.activity, UnitActivity::Idle);
+    assert_eq!(diagnosis.order, UnitOrder::AtRally);
+    let enemy = diagnosis.nearest_enemy.expect("player 1 is on the board");
+    assert_eq!(enemy.id, UnitId(1));
+    assert!((enemy.distance - 700.0).abs() < 1.0, "{enemy:?}");
+    assert!(!enemy.in_range && enemy.distance > enemy.range);
+
+    assert_eq!(diagnose_unit(app.world(), UnitId(99)), None);
+}
+
+#[test]
+fn ordered_unit_reports_its_destination() {
+    let mut app = standoff();
+    let world = app.world_mut();
+    let entity = world
+        .query::<(Entity, &UnitId)>()
+        .iter(world)
+        .find_map(|(entity, id)| (*id == UnitId(0)).then_some(entity))
+        .unwrap();
+    issue_order(world, &[entity], Order::Move(Vec2::new(0.0, 300.0)));
+    run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+
+    let diagnosis = diagnose_unit(app.world(), UnitId(0)).unwrap();
+    assert_eq!(diagnosis.activity, UnitActivity::Moving);
+    match diagnosis.order {
+        UnitOrder::Move {
+            target,
+            distance,
+            queued_waypoints,
+        } => {
+            assert_eq!(target, [0.0, 300.0]);
+            assert!(distance > 0.0 && distance < 300.0, "{distance}");
+            assert_eq!(queued_waypoints, 0);
+        }
+        UnitOrder::AtRally => panic!("move order not reflected"),
+    }
+}
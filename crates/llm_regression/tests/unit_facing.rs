@@ -0,0 +1,61 @@
+use std::f32::consts::FRAC_PI_2;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use core_game::gameplay::{issue_order, BoardSettings, Order, PlayerId, Unit, UnitKind};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+
+/// A lone unit ordered 400 units to the right, with its facing sampled after
+/// each of `ticks` ticks.
+fn facings_moving_right(ticks: usize) -> (App, Vec<f32>) {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            spawn_interval: 600.0,
+            ..Default::default()
+        },
+        |app| {
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-200.0, 0.0))
+                .build(app);
+        },
+    );
+    let world = app.world_mut();
+    let unit = world.query_filtered::<Entity, With<Unit>>().single(world);
+    issue_order(world, &[unit], Order::Move(Vec2::new(200.0, 0.0)));
+
+    let mut facings = Vec::new();
+    for _ in 0..ticks {
+        run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+        facings.push(app.world().get::<Unit>(unit).unwrap().facing);
+    }
+    (app, facings)
+}
+
+#[test]
+fn unit_moving_right_turns_to_face_right() {
+    let (mut app, facings) = facings_moving_right(20);
+    assert!(
+        (facings[0] - FRAC_PI_2).abs() < 1.0,
+        "snapped from up to {} in one tick",
+        facings[0]
+    );
+    let facing = *facings.last().unwrap();
+    assert!(facing.abs() < 0.05, "still facing {facing}");
+
+    // Sprites follow the simulation in `Update`.
+    app.world_mut().run_schedule(Update);
+    let world = app.world_mut();
+    let transform = world
+        .query_filtered::<&Transform, With<Unit>>()
+        .single(world);
+    // The sprite is drawn pointing up, so facing +x is a quarter turn clockwise.
+    let up = transform.rotation * Vec3::Y;
+    assert!(up.truncate().distance(Vec2::X) < 0.05, "sprite points {up}");
+
+    let replay = facings_moving_right(20).1;
+    let bits = |facings: &[f32]| facings.iter().map(|f| f.to_bits()).collect::<Vec<_>>();
+    assert_eq!(bits(&replay), bits(&facings));
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use core_game::gameplay::{BoardSettings, CombatEvent, CombatLog, UnitId};
+use llm_regression::harness::{headless_app, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+
+fn living_ids(app: &mut bevy::prelude::App) -> Vec<UnitId> {
+    let world = app.world_mut();
+    let mut ids: Vec<UnitId> = world.query::<&UnitId>().iter(world).copied().collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn unit_ids_are_sequential_and_survive_a_round_trip() {
+    let mut app = headless_app(
+        DEFAULT_SEED,
+        BoardSettings {
+            player_count: 2,
+            spawn_interval: 0.5,
+            board_size: 500.0,
+            ..Default::default()
+        },
+    );
+    assert_eq!(living_ids(&mut app), (0..4).map(UnitId).collect::<Vec<_>>());
+
+    run_fixed_ticks(&mut app, 240, Duration::from_millis(100));
+    let living = living_ids(&mut app);
+    let events: Vec<CombatEvent> = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .cloned()
+        .collect();
+    let killed: Vec<UnitId> = events
+        .iter()
+        .filter_map(|event| match *event {
+            CombatEvent::Kill { target, .. } => Some(target),
+            _ => None,
+        })
+        .collect();
+    assert!(!killed.is_empty(), "squads should trade kills");
+
+    // Units only leave by dying, so survivors plus the dead cover every id
+    // handed out, with no gaps or repeats.
+    let issued: BTreeSet<UnitId> = living.iter().chain(&killed).copied().collect();
+    assert_eq!(issued.len(), living.len() + killed.len());
+    let expected: BTreeSet<UnitId> = (0..issued.len() as u64).map(UnitId).collect();
+    assert_eq!(issued, expected);
+
+    let json = serde_json::to_string(&(&living, &events)).unwrap();
+    let (restored_ids, restored_events): (Vec<UnitId>, Vec<CombatEvent>) =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(restored_ids, living);
+    assert_eq!(restored_events, events);
+}
@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use core_game::gameplay::{
+    BalanceSettings, BoardSettings, CombatEvent, CombatLog, PlayerId, SimulationPosition, Unit,
+    UnitId, UnitKind,
+};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+/// Player 0's shooter faces a target at x = 200. With `with_victim`, a
+/// one-hit victim stands closer and is killed first, promoting the shooter.
+/// Returns the shooter's rank and the damage of its first shot at the target.
+fn damage_against_target(veterancy: bool, with_victim: bool) -> (u8, f32) {
+    let mut app = headless_app_with(DEFAULT_SEED, BoardSettings::default(), |app| {
+        app.insert_resource(BalanceSettings {
+            veterancy,
+            veterancy_thresholds: vec![1],
+            veterancy_damage_bonus: 0.5,
+            ..Default::default()
+        });
+        let mut scenario = ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+            .unit(PlayerId(1), UnitKind::Laser, Vec2::new(200.0, 0.0));
+        if with_victim {
+            scenario = scenario.unit(PlayerId(1), UnitKind::Laser, Vec2::new(100.0, 0.0));
+        }
+        scenario.build(app);
+    });
+
+    let world = app.world_mut();
+    let mut shooter = None;
+    let mut target = None;
+    for (entity, position, mut unit) in world
+        .query::<(Entity, &SimulationPosition, &mut Unit)>()
+        .iter_mut(world)
+    {
+        match position.current.x as i32 {
+            0 => shooter = Some(entity),
+            200 => target = Some(entity),
+            _ => unit.health = 1.0,
+        }
+    }
+    let (shooter, target) = (shooter.unwrap(), target.unwrap());
+
+    let (shooter_id, target_id) = {
+        let world = app.world();
+        (
+            *world.get::<UnitId>(shooter).unwrap(),
+            *world.get::<UnitId>(target).unwrap(),
+        )
+    };
+
+    run_fixed_ticks(&mut app, 30, Duration::from_millis(100));
+
+    let damage = app
+        .world()
+        .resource::<CombatLog>()
+        .events()
+        .iter()
+        .find_map(|event| match *event {
+            CombatEvent::Shot {
+                attacker,
+                target: hit,
+                damage,
+                ..
+            } if attacker == shooter_id && hit == target_id => Some(damage),
+            _ => None,
+        })
+        .expect("shooter reaches the target");
+    let rank = app.world().get::<Unit>(shooter).unwrap().rank;
+    (rank, damage)
+}
+
+#[test]
+fn veteran_deals_more_damage_than_fresh_unit() {
+    let (fresh_rank, fresh_damage) = damage_against_target(true, false);
+    let (veteran_rank, veteran_damage) = damage_against_target(true, true);
+
+    assert_eq!(fresh_rank, 0);
+    assert_eq!(veteran_rank, 1);
+    assert!(
+        (veteran_damage / fresh_damage - 1.5).abs() < 1e-4,
+        "veteran {veteran_damage} vs fresh {fresh_damage}"
+    );
+}
+
+#[test]
+fn kills_promote_nobody_while_veterancy_is_off() {
+    let (fresh_rank, fresh_damage) = damage_against_target(false, false);
+    let (rank, damage) = damage_against_target(false, true);
+
+    assert_eq!((fresh_rank, rank), (0, 0));
+    assert_eq!(damage, fresh_damage);
+}
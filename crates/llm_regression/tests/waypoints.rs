@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use core_game::gameplay::{BoardSettings, PlayerId, SimulationPosition, Unit, UnitKind};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use std::time::Duration;
+
+#[test]
+fn unit_visits_queued_waypoints_in_order() {
+    let board = BoardSettings {
+        spawn_interval: 600.0,
+        ..Default::default()
+    };
+    let mut app = headless_app_with(DEFAULT_SEED, board, |app| {
+        ScenarioBuilder::new()
+            .unit(PlayerId(0), UnitKind::Laser, Vec2::ZERO)
+            .build(app);
+    });
+    let world = app.world_mut();
+    let unit = world.query_filtered::<Entity, With<Unit>>().single(world);
+    let first = Vec2::new(200.0, 0.0);
+    let second = Vec2::new(200.0, 200.0);
+    {
+        let mut state = world.get_mut::<Unit>(unit).unwrap();
+        state.order_move(first);
+        state.queue_move(second);
+    }
+
+    let mut visited = Vec::new();
+    for _ in 0..80 {
+        run_fixed_ticks(&mut app, 1, Duration::from_millis(100));
+        let pos = app.world().get::<SimulationPosition>(unit).unwrap().current;
+        for (idx, waypoint) in [first, second].into_iter().enumerate() {
+            if pos.distance(waypoint) < 15.0 && !visited.contains(&idx) {
+                visited.push(idx);
+            }
+        }
+    }
+
+    assert_eq!(visited, vec![0, 1]);
+    assert!(app.world().get::<Unit>(unit).unwrap().waypoints.is_empty());
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use core_game::gameplay::{export_world_state, BoardSettings, PlayerId, UnitKind};
+use core_game::scenario::ScenarioBuilder;
+use llm_regression::harness::{headless_app_with, run_fixed_ticks};
+use llm_regression::DEFAULT_SEED;
+use serde_json::json;
+use std::time::Duration;
+
+#[test]
+fn export_describes_a_known_scenario() {
+    let mut app = headless_app_with(
+        DEFAULT_SEED,
+        BoardSettings {
+            reinforcements: false,
+            ..Default::default()
+        },
+        |app| {
+            ScenarioBuilder::new()
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-600.0, 0.0))
+                .unit(PlayerId(0), UnitKind::Laser, Vec2::new(-600.0, 80.0))
+                .unit(PlayerId(1), UnitKind::Laser, Vec2::new(600.0, 0.0))
+                .pylon(Vec2::new(0.0, 500.0))
+                .build(app);
+        },
+    );
+
+    let state = export_world_state(app.world());
+    assert_eq!(
+        serde_json::to_value(&state).unwrap(),
+        json!({
+            "tick": 0,
+            "outcome": { "result": "in_progress" },
+            "units": [
+                { "id": 0, "player": 0, "kind": "laser", "position": [-600.0, 0.0], "health": 45.0, "max_health": 45.0, "supplied": false },
+                { "id": 1, "player": 0, "kind": "laser", "position": [-600.0, 80.0], "health": 45.0, "max_health": 45.0, "supplied": false },
+                { "id": 2, "player": 1, "kind": "laser", "position": [600.0, 0.0], "health": 45.0, "max_health": 45.0, "supplied": false },
+            ],
+            "pylons": [
+                { "position": [0.0, 500.0], "owner": null, "health": 300.0 },
+            ],
+        })
+    );
+
+    run_fixed_ticks(&mut app, 3, Duration::from_millis(100));
+    let state = export_world_state(app.world());
+    assert_eq!(state.tick, 3);
+    let ids: Vec<u64> = state.units.iter().map(|unit| unit.id.0).collect();
+    assert_eq!(ids, vec![0, 1, 2]);
+}